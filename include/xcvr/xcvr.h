@@ -443,4 +443,19 @@ xcvr_status_t xcvr_get_transceiver_status(
     _Out_ xcvr_transceiver_status_t *transceiver_status
 );
 
+/**
+ * @brief Returns the label of the port as it is printed on the front panel of
+ * the device (e.g. "1" or "Eth1/1").
+ * 
+ * This function is optional: consumers must not fail to load a library which
+ * does not export it. The label is a NULL terminated string which must fit
+ * into label_size bytes including the NULL byte, otherwise the call must fail.
+*/
+xcvr_status_t xcvr_get_port_label(
+    _In_ const char *platform,
+    _In_ idx_t index,
+    _Out_ char *label,
+    _In_ size_t label_size
+);
+
 #endif /* __XCVR_H_ */
@@ -217,6 +217,7 @@ fn app() -> anyhow::Result<()> {
                         );
                     }
                     println!("onie_lldp_{}_is_hh=\"{}\"", dev, network_config.is_hh);
+                    println!("onie_lldp_{}_applied=\"{}\"", dev, resp.applied);
                     break;
                 }
                 wait_secs -= 1;
@@ -42,6 +42,10 @@ struct Cli {
     #[arg(long, default_value = "false", default_missing_value = "true")]
     auto_discovery_with_breakout: Option<Option<bool>>,
 
+    /// Whether to program the IP address and routes which are learned through LLDP onto the interfaces
+    #[arg(long, default_value = "false", default_missing_value = "true")]
+    lldp_apply_network_config: Option<Option<bool>>,
+
     /// The platform to use: this should always be auto-detected.
     #[arg(long, default_value = arg_platform())]
     platform: String,
@@ -149,7 +153,8 @@ fn app(cli: Cli) -> anyhow::Result<()> {
     .context("failed to set signal handler for SIGINT, SIGTERM and SIGHUP")?;
 
     // this initializes the switch, and prepares the system for receiving processing requests either from RPC, or the other threads
-    let proc = Processor::new().context("failed to initialize ONIE SAI processor")?;
+    let proc = Processor::new(cli.lldp_apply_network_config.flatten().unwrap_or_default())
+        .context("failed to initialize ONIE SAI processor")?;
 
     // move the signal handling to its own thread
     // send a shutdown request to the processor when we receive it
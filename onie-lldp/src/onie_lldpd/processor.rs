@@ -5,6 +5,7 @@ use std::sync::mpsc::Receiver;
 use std::sync::mpsc::Sender;
 use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
 
 use onie_sai_rpc::onie_sai;
 use onie_sai_rpc::wrap_message_field;
@@ -44,6 +45,7 @@ pub(crate) enum ProcessRequest {
     NetlinkLinkRemoved(u32),
     LLDPTLVsReceived((u32, LLDPTLVs)),
     LLDPNetworkConfigReceived((u32, NetworkConfig)),
+    LLDPNeighborExpired(u32),
     LLDPStatus(
         (
             onie_sai::LLDPStatusRequest,
@@ -62,10 +64,11 @@ pub(crate) struct Processor {
     rx: Receiver<ProcessRequest>,
     tx: Sender<ProcessRequest>,
     hifs: Vec<HostInterface>,
+    lldp_apply_network_config: bool,
 }
 
 impl Processor {
-    pub(crate) fn new() -> anyhow::Result<Self> {
+    pub(crate) fn new(lldp_apply_network_config: bool) -> anyhow::Result<Self> {
         // the processor channel
         let (tx, rx) = channel();
 
@@ -77,6 +80,7 @@ impl Processor {
                 lldp_socket: None,
                 lldp_tlvs: None,
                 lldp_network_config: None,
+                lldp_network_config_applied: None,
             };
             if let Ok(is_up) = netlink::get_link_status(idx) {
                 if is_up {
@@ -90,6 +94,7 @@ impl Processor {
             rx: rx,
             tx: tx,
             hifs: hifs,
+            lldp_apply_network_config: lldp_apply_network_config,
         })
     }
 
@@ -151,6 +156,9 @@ impl Processor {
                 ProcessRequest::LLDPNetworkConfigReceived((if_idx, config)) => {
                     p.process_lldp_network_config_received(if_idx, config)
                 }
+                ProcessRequest::LLDPNeighborExpired(if_idx) => {
+                    p.process_lldp_neighbor_expired(if_idx)
+                }
             }
         }
     }
@@ -231,6 +239,7 @@ impl Processor {
                 if let Some(ref config) = hif.lldp_network_config {
                     return Ok(onie_sai::LLDPNetworkConfigResponse {
                         network_config: wrap_message_field(Some(config.clone().into())),
+                        applied: hif.lldp_network_config_applied.as_ref() == Some(config),
                         ..Default::default()
                     });
                 }
@@ -256,6 +265,7 @@ impl Processor {
                         hif.start_lldp_recv_thread(self.tx.clone());
                     } else {
                         hif.stop_lldp_recv_thread();
+                        hif.remove_network_config();
                     }
                 }
                 Err(e) => {
@@ -303,6 +313,7 @@ impl Processor {
                     hif.start_lldp_recv_thread(self.tx.clone());
                 } else {
                     hif.stop_lldp_recv_thread();
+                    hif.remove_network_config();
                 }
                 break;
             }
@@ -316,6 +327,7 @@ impl Processor {
                 lldp_socket: None,
                 lldp_tlvs: None,
                 lldp_network_config: None,
+                lldp_network_config_applied: None,
             };
             if is_up {
                 hif.start_lldp_recv_thread(self.tx.clone());
@@ -353,6 +365,9 @@ impl Processor {
             if hif.idx == if_idx {
                 found = true;
                 hif.lldp_network_config = Some(config);
+                if self.lldp_apply_network_config {
+                    hif.apply_network_config();
+                }
                 break;
             }
         }
@@ -361,6 +376,18 @@ impl Processor {
             log::warn!("host interface {if_name} ({if_idx}) not found during LLDP network config event. Discovered LLDP Network Config was not stored.");
         }
     }
+
+    fn process_lldp_neighbor_expired(&mut self, if_idx: u32) {
+        // the neighbor is gone, and so is everything that we learned from it
+        for hif in self.hifs.iter_mut() {
+            if hif.idx == if_idx {
+                hif.lldp_tlvs = None;
+                hif.lldp_network_config = None;
+                hif.remove_network_config();
+                break;
+            }
+        }
+    }
 }
 
 impl Drop for Processor {
@@ -378,9 +405,59 @@ pub(crate) struct HostInterface {
     pub(crate) lldp_socket: Option<Arc<LLDPSocket>>,
     pub(crate) lldp_tlvs: Option<LLDPTLVs>,
     pub(crate) lldp_network_config: Option<NetworkConfig>,
+    /// the network config which we have programmed onto the interface ourselves
+    pub(crate) lldp_network_config_applied: Option<NetworkConfig>,
 }
 
 impl HostInterface {
+    /// programs the address and routes of the LLDP network config onto the interface
+    /// NOTE: a different config that was applied before gets removed first
+    fn apply_network_config(&mut self) {
+        let config = match self.lldp_network_config {
+            Some(ref config) => config.clone(),
+            None => return,
+        };
+        if self.lldp_network_config_applied.as_ref() == Some(&config) {
+            return;
+        }
+        self.remove_network_config();
+        match netlink::add_network_config(self.idx, &config) {
+            Ok(_) => {
+                log::info!(
+                    "Host Interface {}: applied LLDP network config {:?}",
+                    self.name,
+                    config
+                );
+                self.lldp_network_config_applied = Some(config);
+            }
+            Err(e) => {
+                log::error!(
+                    "Host Interface {}: failed to apply LLDP network config: {:?}",
+                    self.name,
+                    e
+                );
+            }
+        }
+    }
+
+    /// removes the address and routes of an applied LLDP network config from the interface
+    fn remove_network_config(&mut self) {
+        if let Some(config) = self.lldp_network_config_applied.take() {
+            match netlink::del_network_config(self.idx, &config) {
+                Ok(_) => log::info!(
+                    "Host Interface {}: removed LLDP network config {:?}",
+                    self.name,
+                    config
+                ),
+                Err(e) => log::error!(
+                    "Host Interface {}: failed to remove LLDP network config: {:?}",
+                    self.name,
+                    e
+                ),
+            }
+        }
+    }
+
     fn start_lldp_recv_thread(&mut self, processor_sender: Sender<ProcessRequest>) {
         if self.lldp_socket.is_none() {
             match LLDPSocket::new(self.idx as i32) {
@@ -405,6 +482,32 @@ impl HostInterface {
                                         }
                                     };
 
+                                    // the neighbor is gone if we don't hear from it again within its TTL,
+                                    // and a TTL of 0 means that it is shutting down right now
+                                    let ttl = lldp_tlvs.get_ttl();
+                                    if let Err(e) = socket.set_recv_timeout(
+                                        ttl.map(|v| Duration::from_secs(v as u64)),
+                                    ) {
+                                        log::error!(
+                                            "Host Interface {}: failed to set LLDP receive timeout: {:?}",
+                                            hif_name,
+                                            e
+                                        );
+                                    }
+                                    if ttl == Some(0) {
+                                        log::info!("Host Interface {hif_name}: LLDP neighbor is shutting down");
+                                        if let Err(e) = processor_sender
+                                            .send(ProcessRequest::LLDPNeighborExpired(hif_idx))
+                                        {
+                                            log::error!(
+                                                "Host Interface {}: failed to send LLDP neighbor expiry to processor thread: {:?}",
+                                                hif_name,
+                                                e
+                                            );
+                                        }
+                                        continue;
+                                    }
+
                                     // send the LLDP TLVs to the processor thread
                                     // this is for general LLDP information that we received on this host interface
                                     // which can be queried through onie-saictl
@@ -439,6 +542,26 @@ impl HostInterface {
                                         }
                                     }
                                 }
+                                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                                    // the receive timeout is the TTL of the last LLDP packet
+                                    log::info!("Host Interface {hif_name}: LLDP neighbor expired");
+                                    if let Err(e) = socket.set_recv_timeout(None) {
+                                        log::error!(
+                                            "Host Interface {}: failed to reset LLDP receive timeout: {:?}",
+                                            hif_name,
+                                            e
+                                        );
+                                    }
+                                    if let Err(e) = processor_sender
+                                        .send(ProcessRequest::LLDPNeighborExpired(hif_idx))
+                                    {
+                                        log::error!(
+                                            "Host Interface {}: failed to send LLDP neighbor expiry to processor thread: {:?}",
+                                            hif_name,
+                                            e
+                                        );
+                                    }
+                                }
                                 Err(e) => {
                                     // an error receiving most likely means that the socket was closed
                                     // we simply abort the thread for now until we know exactly what error to watch for
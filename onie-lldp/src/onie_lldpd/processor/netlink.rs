@@ -1,3 +1,4 @@
+use ipnet::IpNet;
 use libc::RTMGRP_LINK;
 use netlink_packet_core::constants::NLM_F_ACK;
 use netlink_packet_core::constants::NLM_F_REQUEST;
@@ -6,14 +7,20 @@ use netlink_packet_core::NetlinkMessage;
 use netlink_packet_core::NetlinkPayload;
 use netlink_packet_route::LinkMessage;
 use netlink_packet_route::RtnlMessage;
-use netlink_packet_utils::DecodeError;
 use netlink_sys::protocols::NETLINK_ROUTE;
 use netlink_sys::Socket;
 use netlink_sys::SocketAddr;
+use onie_sai_common::netlink::add_address_and_routes;
+use onie_sai_common::netlink::del_address_and_routes;
 use std::ffi::CStr;
+use std::net::IpAddr;
 use std::sync::mpsc::Sender;
 use std::thread;
 
+pub(crate) use onie_sai_common::netlink::SetLinkError;
+
+use crate::onie_lldpd::lldp::NetworkConfig;
+
 use super::ProcessRequest;
 
 pub(crate) fn netlink_link_monitor(
@@ -136,41 +143,6 @@ pub(crate) fn get_interfaces() -> Result<Vec<(u32, String)>, std::io::Error> {
     Ok(ret)
 }
 
-#[derive(Debug)]
-pub(crate) enum SetLinkError {
-    IOError(std::io::Error),
-    NetlinkDecodeError(DecodeError),
-    UnexpectedNetlinkMessage(u16),
-    NetlinkError(i32),
-}
-
-impl std::error::Error for SetLinkError {}
-
-impl std::fmt::Display for SetLinkError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SetLinkError::IOError(e) => write!(f, "IO error: {}", e),
-            SetLinkError::NetlinkDecodeError(e) => write!(f, "netlink decoding error: {}", e),
-            SetLinkError::UnexpectedNetlinkMessage(v) => {
-                write!(f, "unexpected netlink message received: {}", v)
-            }
-            SetLinkError::NetlinkError(v) => write!(f, "netlink error: {}", v),
-        }
-    }
-}
-
-impl From<std::io::Error> for SetLinkError {
-    fn from(e: std::io::Error) -> Self {
-        SetLinkError::IOError(e)
-    }
-}
-
-impl From<DecodeError> for SetLinkError {
-    fn from(e: DecodeError) -> Self {
-        SetLinkError::NetlinkDecodeError(e)
-    }
-}
-
 pub(crate) fn get_link_status(index: u32) -> Result<bool, SetLinkError> {
     let mut socket = Socket::new(NETLINK_ROUTE)?;
     let sock_addr = socket.bind_auto()?;
@@ -297,6 +269,24 @@ pub(crate) fn set_link_status(index: u32, oper_status: bool) -> Result<(), SetLi
     }
 }
 
+/// adds the address and all routes of the network config to the interface
+pub(crate) fn add_network_config(index: u32, config: &NetworkConfig) -> Result<(), SetLinkError> {
+    add_address_and_routes(index, &config.ip, &network_config_routes(config))
+}
+
+/// removes all routes and the address of the network config from the interface
+pub(crate) fn del_network_config(index: u32, config: &NetworkConfig) -> Result<(), SetLinkError> {
+    del_address_and_routes(index, &config.ip, &network_config_routes(config))
+}
+
+fn network_config_routes(config: &NetworkConfig) -> Vec<(IpNet, IpAddr)> {
+    config
+        .routes
+        .iter()
+        .flat_map(|route| route.destinations.iter().map(|dst| (*dst, route.gateway)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
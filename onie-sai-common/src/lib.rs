@@ -1,3 +1,6 @@
+pub mod netlink;
+pub mod port_config;
+
 use std::process::{ExitCode, Termination};
 
 pub struct App(pub anyhow::Result<()>);
@@ -0,0 +1,205 @@
+use ipnet::IpNet;
+use netlink_packet_core::constants::NLM_F_ACK;
+use netlink_packet_core::constants::NLM_F_CREATE;
+use netlink_packet_core::constants::NLM_F_REPLACE;
+use netlink_packet_core::constants::NLM_F_REQUEST;
+use netlink_packet_core::NetlinkHeader;
+use netlink_packet_core::NetlinkMessage;
+use netlink_packet_core::NetlinkPayload;
+use netlink_packet_route::rtnl::address::AddressMessage;
+use netlink_packet_route::rtnl::nlas::address::Nla;
+use netlink_packet_route::rtnl::nlas::route::Nla as RouteNla;
+use netlink_packet_route::RouteMessage;
+use netlink_packet_route::RtnlMessage;
+use netlink_packet_route::RTN_UNICAST;
+use netlink_packet_route::RTPROT_STATIC;
+use netlink_packet_route::RT_SCOPE_UNIVERSE;
+use netlink_packet_route::RT_TABLE_MAIN;
+use netlink_packet_utils::DecodeError;
+use netlink_sys::protocols::NETLINK_ROUTE;
+use netlink_sys::Socket;
+use netlink_sys::SocketAddr;
+use std::net::IpAddr;
+
+#[derive(Debug)]
+pub enum SetLinkError {
+    IOError(std::io::Error),
+    NetlinkDecodeError(DecodeError),
+    UnexpectedNetlinkMessage(u16),
+    NetlinkError(i32),
+}
+
+impl std::error::Error for SetLinkError {}
+
+impl std::fmt::Display for SetLinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetLinkError::IOError(e) => write!(f, "IO error: {}", e),
+            SetLinkError::NetlinkDecodeError(e) => write!(f, "netlink decoding error: {}", e),
+            SetLinkError::UnexpectedNetlinkMessage(v) => {
+                write!(f, "unexpected netlink message received: {}", v)
+            }
+            SetLinkError::NetlinkError(v) => write!(f, "netlink error: {}", v),
+        }
+    }
+}
+
+impl From<std::io::Error> for SetLinkError {
+    fn from(e: std::io::Error) -> Self {
+        SetLinkError::IOError(e)
+    }
+}
+
+impl From<DecodeError> for SetLinkError {
+    fn from(e: DecodeError) -> Self {
+        SetLinkError::NetlinkDecodeError(e)
+    }
+}
+
+/// adds the address and the routes given as destination and gateway to the interface
+/// NOTE: if this fails halfway, everything that was added gets removed again
+pub fn add_address_and_routes(
+    index: u32,
+    ip: &IpNet,
+    routes: &[(IpNet, IpAddr)],
+) -> Result<(), SetLinkError> {
+    let ret = add_address(index, ip).and_then(|_| {
+        routes
+            .iter()
+            .try_for_each(|(dst, gateway)| add_route(index, dst, gateway))
+    });
+    if ret.is_err() {
+        let _ = del_address_and_routes(index, ip, routes);
+    }
+    ret
+}
+
+/// removes the routes given as destination and gateway and the address from the interface
+/// NOTE: routes or addresses which are already gone are not an error as the kernel
+/// removes them itself when the interface goes down or away
+pub fn del_address_and_routes(
+    index: u32,
+    ip: &IpNet,
+    routes: &[(IpNet, IpAddr)],
+) -> Result<(), SetLinkError> {
+    let mut ret = Ok(());
+    for (dst, gateway) in routes.iter() {
+        match del_route(index, dst, gateway) {
+            Err(SetLinkError::NetlinkError(code)) if code == -libc::ESRCH => {}
+            Err(e) => ret = ret.and(Err(e)),
+            Ok(_) => {}
+        }
+    }
+    match del_address(index, ip) {
+        Err(SetLinkError::NetlinkError(code)) if code == -libc::EADDRNOTAVAIL => {}
+        Err(e) => ret = ret.and(Err(e)),
+        Ok(_) => {}
+    }
+    ret
+}
+
+pub fn add_address(index: u32, ip: &IpNet) -> Result<(), SetLinkError> {
+    request_and_ack(
+        RtnlMessage::NewAddress(address_message(index, ip)),
+        NLM_F_CREATE | NLM_F_REPLACE,
+    )
+}
+
+pub fn del_address(index: u32, ip: &IpNet) -> Result<(), SetLinkError> {
+    request_and_ack(RtnlMessage::DelAddress(address_message(index, ip)), 0)
+}
+
+pub fn add_route(index: u32, dst: &IpNet, gateway: &IpAddr) -> Result<(), SetLinkError> {
+    request_and_ack(
+        RtnlMessage::NewRoute(route_message(index, dst, gateway)),
+        NLM_F_CREATE | NLM_F_REPLACE,
+    )
+}
+
+pub fn del_route(index: u32, dst: &IpNet, gateway: &IpAddr) -> Result<(), SetLinkError> {
+    request_and_ack(RtnlMessage::DelRoute(route_message(index, dst, gateway)), 0)
+}
+
+fn address_message(index: u32, ip: &IpNet) -> AddressMessage {
+    let mut am = AddressMessage::default();
+    am.header.family = address_family(&ip.addr());
+    am.header.prefix_len = ip.prefix_len();
+    am.header.scope = RT_SCOPE_UNIVERSE;
+    am.header.index = index;
+    // this is what iproute2 does as well: local and address are the same on non point-to-point links
+    am.nlas.push(Nla::Local(address_bytes(&ip.addr())));
+    am.nlas.push(Nla::Address(address_bytes(&ip.addr())));
+    am
+}
+
+fn route_message(index: u32, dst: &IpNet, gateway: &IpAddr) -> RouteMessage {
+    let mut rm = RouteMessage::default();
+    rm.header.address_family = address_family(&dst.addr());
+    rm.header.destination_prefix_length = dst.prefix_len();
+    rm.header.table = RT_TABLE_MAIN;
+    rm.header.protocol = RTPROT_STATIC;
+    rm.header.scope = RT_SCOPE_UNIVERSE;
+    rm.header.kind = RTN_UNICAST;
+    // a default route has no destination attribute
+    if dst.prefix_len() > 0 {
+        rm.nlas
+            .push(RouteNla::Destination(address_bytes(&dst.network())));
+    }
+    rm.nlas.push(RouteNla::Gateway(address_bytes(gateway)));
+    rm.nlas.push(RouteNla::Oif(index));
+    rm
+}
+
+fn address_family(addr: &IpAddr) -> u8 {
+    match addr {
+        IpAddr::V4(_) => libc::AF_INET as u8,
+        IpAddr::V6(_) => libc::AF_INET6 as u8,
+    }
+}
+
+fn address_bytes(addr: &IpAddr) -> Vec<u8> {
+    match addr {
+        IpAddr::V4(v) => v.octets().to_vec(),
+        IpAddr::V6(v) => v.octets().to_vec(),
+    }
+}
+
+/// sends a request with the given flags on a new socket, and waits for the ACK
+pub fn request_and_ack(msg: RtnlMessage, flags: u16) -> Result<(), SetLinkError> {
+    let mut socket = Socket::new(NETLINK_ROUTE)?;
+    let sock_addr = socket.bind_auto()?;
+    let port_number = sock_addr.port_number();
+    socket.connect(&SocketAddr::new(0, 0))?;
+
+    let mut hdr = NetlinkHeader::default();
+    hdr.flags = NLM_F_REQUEST | NLM_F_ACK | flags;
+    hdr.port_number = port_number;
+    let mut req = NetlinkMessage::new(hdr, NetlinkPayload::from(msg));
+    req.finalize();
+    send_and_ack(&socket, req)
+}
+
+/// sends a request on a connected socket, and waits for the ACK
+pub fn send_and_ack(socket: &Socket, req: NetlinkMessage<RtnlMessage>) -> Result<(), SetLinkError> {
+    // serialize the request
+    let mut buf = vec![0u8; req.header.length as usize];
+    req.serialize(buf.as_mut_slice());
+
+    // send the request
+    let _ = socket.send(buf.as_slice(), 0)?;
+
+    // receive the response
+    let mut buf = vec![0u8; 4096];
+    let _ = socket.recv(&mut &mut buf[..], 0)?;
+
+    // deserialize the response
+    // we are expecting an NLMSG_ERROR message without an error (which equals an ACK)
+    let resp = <NetlinkMessage<RtnlMessage>>::deserialize(buf.as_slice())?;
+    match resp.payload {
+        NetlinkPayload::Error(err_msg) => match err_msg.code {
+            None => Ok(()),
+            Some(code) => Err(SetLinkError::NetlinkError(code.into())),
+        },
+        v => Err(SetLinkError::UnexpectedNetlinkMessage(v.message_type())),
+    }
+}
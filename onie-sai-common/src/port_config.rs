@@ -0,0 +1,311 @@
+pub mod check;
+
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PhysicalPortConfigSpeed {
+    #[serde(rename = "EightLanes")]
+    pub eight_lanes: Option<u32>,
+    #[serde(rename = "FourLanes")]
+    pub four_lanes: Option<u32>,
+    #[serde(rename = "TwoLanes")]
+    pub two_lanes: Option<u32>,
+    #[serde(rename = "OneLane")]
+    pub one_lane: Option<u32>,
+}
+
+impl PhysicalPortConfigSpeed {
+    /// sets the speed of a port with `num_lanes` lanes: other lane counts are ignored
+    pub fn set(&mut self, num_lanes: usize, speed: u32) {
+        match num_lanes {
+            1 => self.one_lane = Some(speed),
+            2 => self.two_lanes = Some(speed),
+            4 => self.four_lanes = Some(speed),
+            8 => self.eight_lanes = Some(speed),
+            _ => {}
+        }
+    }
+
+    /// the speed of a port with `num_lanes` lanes if it is configured
+    pub fn get(&self, num_lanes: usize) -> Option<u32> {
+        match num_lanes {
+            1 => self.one_lane,
+            2 => self.two_lanes,
+            4 => self.four_lanes,
+            8 => self.eight_lanes,
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhysicalPortConfigBreakoutMode {
+    OneLane,
+    TwoLanes,
+    FourLanes,
+    EightLanes,
+}
+
+impl PhysicalPortConfigBreakoutMode {
+    /// the number of lanes of every logical port in this breakout mode
+    pub fn lanes_per_port(&self) -> usize {
+        match self {
+            PhysicalPortConfigBreakoutMode::OneLane => 1,
+            PhysicalPortConfigBreakoutMode::TwoLanes => 2,
+            PhysicalPortConfigBreakoutMode::FourLanes => 4,
+            PhysicalPortConfigBreakoutMode::EightLanes => 8,
+        }
+    }
+}
+
+impl std::fmt::Display for PhysicalPortConfigBreakoutMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PhysicalPortConfigBreakoutMode::OneLane => write!(f, "OneLane"),
+            PhysicalPortConfigBreakoutMode::TwoLanes => write!(f, "TwoLanes"),
+            PhysicalPortConfigBreakoutMode::FourLanes => write!(f, "FourLanes"),
+            PhysicalPortConfigBreakoutMode::EightLanes => write!(f, "EightLanes"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhysicalPortConfigFECMode {
+    None,
+    RS,
+    FC,
+}
+
+#[derive(Debug, Error)]
+pub enum LaneSplitError {
+    #[error("invalid number of hw lanes: {0}")]
+    InvalidNumberOfHwLanes(usize),
+
+    #[error("invalid number of hw lanes for breakout mode {1}: {0}")]
+    InvalidNumberOfHwLanesForMode(usize, PhysicalPortConfigBreakoutMode),
+}
+
+/// splits the lanes of a physical port into the lanes of its logical ports for a breakout mode
+pub fn split_lanes(
+    mode: PhysicalPortConfigBreakoutMode,
+    lanes: &[u32],
+) -> Result<Vec<Vec<u32>>, LaneSplitError> {
+    // validate number of lanes first
+    let lanes_len = lanes.len();
+    if lanes_len == 0 || lanes_len > 8 {
+        return Err(LaneSplitError::InvalidNumberOfHwLanes(lanes_len));
+    }
+    if !lanes_len.is_multiple_of(mode.lanes_per_port()) {
+        return Err(LaneSplitError::InvalidNumberOfHwLanesForMode(
+            lanes_len, mode,
+        ));
+    }
+    Ok(lanes
+        .chunks(mode.lanes_per_port())
+        .map(|chunk| chunk.to_vec())
+        .collect())
+}
+
+/// if both lists hold the same lanes: the order of the lanes does not matter
+pub fn same_lanes(a: &[u32], b: &[u32]) -> bool {
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    a.sort_unstable();
+    b.sort_unstable();
+    a == b
+}
+
+/// The configuration of a physical port. Only the lanes and the speeds are required. All other fields
+/// are static overrides which pin a port to certain settings, and they get honored by port discovery.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PhysicalPortConfig {
+    pub lanes: Vec<u32>,
+    pub speed: PhysicalPortConfigSpeed,
+
+    /// the port is always broken out like this, and breakout discovery is disabled for it
+    #[serde(default)]
+    pub breakout_mode: Option<PhysicalPortConfigBreakoutMode>,
+
+    /// the speed of all logical ports: speed discovery is disabled for the port if set
+    #[serde(default)]
+    pub fixed_speed: Option<u32>,
+
+    /// the FEC mode of all logical ports: FEC discovery is disabled for the port if set
+    #[serde(default)]
+    pub fec: Option<PhysicalPortConfigFECMode>,
+
+    /// FEC discovery tries this FEC mode first: unlike `fec` this does not pin the port to it
+    #[serde(default)]
+    pub preferred_fec: Option<PhysicalPortConfigFECMode>,
+
+    /// if set, discovery is only going to try auto negotiation, or never going to try it
+    #[serde(default)]
+    pub auto_neg: Option<bool>,
+
+    #[serde(default)]
+    pub mtu: Option<u32>,
+
+    /// the port stays admin down: there is no discovery and there are no host interfaces for it
+    #[serde(default)]
+    pub admin_down: bool,
+
+    /// the front panel name of the port
+    #[serde(default)]
+    pub alias: Option<String>,
+
+    /// if this is set to false, the port is not going to be discovered, but configured with the settings from here
+    #[serde(default)]
+    pub discovery: Option<bool>,
+}
+
+impl PhysicalPortConfig {
+    /// creates a port config from what we can take over from the SONiC config files
+    /// NOTE: the FEC mode from SONiC is only a hint for discovery, as SONiC configs usually come
+    /// with the same FEC mode for every port
+    pub fn from_sonic(
+        lanes: Vec<u32>,
+        speed: PhysicalPortConfigSpeed,
+        alias: Option<String>,
+        preferred_fec: Option<PhysicalPortConfigFECMode>,
+        mtu: Option<u32>,
+    ) -> Self {
+        Self {
+            lanes,
+            speed,
+            breakout_mode: None,
+            fixed_speed: None,
+            fec: None,
+            preferred_fec,
+            auto_neg: None,
+            mtu,
+            admin_down: false,
+            alias,
+            discovery: None,
+        }
+    }
+
+    /// if the port takes part in auto discovery at all
+    pub fn discovery_enabled(&self) -> bool {
+        !self.admin_down && self.discovery.unwrap_or(true)
+    }
+
+    pub fn get_default_speed(&self) -> u32 {
+        match self.lanes.len() {
+            1 => match self.speed.one_lane {
+                Some(v) => v,
+                None => {
+                    log::error!(
+                        "Physical Port Config: missing OneLane speed configuration. Returning 10000.",
+                    );
+                    10000
+                }
+            },
+            2 => match self.speed.two_lanes {
+                Some(v) => v,
+                None => {
+                    log::error!(
+                        "Physical Port Config: missing TwoLanes speed configuration. Returning 50000.",
+                    );
+                    50000
+                }
+            },
+            4 => match self.speed.four_lanes {
+                Some(v) => v,
+                None => {
+                    log::error!(
+                        "Physical Port Config: missing FourLanes speed configuration. Returning 100000.",
+                    );
+                    100000
+                }
+            },
+            8 => match self.speed.eight_lanes {
+                Some(v) => v,
+                None => {
+                    log::error!(
+                        "Physical Port Config: missing EightLanes speed configuration. Returning 400000.",
+                    );
+                    400000
+                }
+            },
+            _ => {
+                log::error!(
+                    "Physical Port Config: invalid number of lanes: {}. Returning 0.",
+                    self.lanes.len()
+                );
+                0
+            }
+        }
+    }
+
+    // when the supported breakout mode type SAI property is not available
+    // we will use this function to guess the available breakout modes
+    // NOTE: on Broadcom SAI, this can only work if all possible breakouts
+    // have actually been defined as inactive ports in the config.bcm (or through port flex)
+    pub fn get_supported_breakout_modes(&self) -> Vec<PhysicalPortConfigBreakoutMode> {
+        match self.lanes.len() {
+            1 => {
+                vec![PhysicalPortConfigBreakoutMode::OneLane]
+            }
+            2 => {
+                vec![
+                    PhysicalPortConfigBreakoutMode::OneLane,
+                    PhysicalPortConfigBreakoutMode::TwoLanes,
+                ]
+            }
+            4 => {
+                vec![
+                    PhysicalPortConfigBreakoutMode::OneLane,
+                    PhysicalPortConfigBreakoutMode::TwoLanes,
+                    PhysicalPortConfigBreakoutMode::FourLanes,
+                ]
+            }
+            8 => {
+                vec![
+                    PhysicalPortConfigBreakoutMode::OneLane,
+                    PhysicalPortConfigBreakoutMode::TwoLanes,
+                    PhysicalPortConfigBreakoutMode::FourLanes,
+                    PhysicalPortConfigBreakoutMode::EightLanes,
+                ]
+            }
+            _ => {
+                log::error!("Physical Port Config: invalid number of lanes: {}. Returning no breakout types.", self.lanes.len());
+                vec![]
+            }
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Vec<PhysicalPortConfig>, serde_json::Error> {
+        serde_json::from_str(contents)
+    }
+
+    pub fn from_file(path: &PathBuf) -> Option<Vec<PhysicalPortConfig>> {
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) => {
+                log::error!("failed to open physical port config file: {:?}", e);
+                return None;
+            }
+        };
+        let mut contents = String::new();
+        match file.read_to_string(&mut contents) {
+            Ok(_) => {}
+            Err(e) => {
+                log::error!("failed to read physical port config file: {:?}", e);
+                return None;
+            }
+        };
+        let config: Vec<PhysicalPortConfig> = match Self::parse(&contents) {
+            Ok(config) => config,
+            Err(e) => {
+                log::error!("failed to parse physical port config file: {:?}", e);
+                return None;
+            }
+        };
+        Some(config)
+    }
+}
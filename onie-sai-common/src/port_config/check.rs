@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+
+use super::same_lanes;
+use super::split_lanes;
+use super::PhysicalPortConfig;
+
+/// the speeds per lane that we know of: everything else is most likely a typo
+const KNOWN_LANE_SPEEDS: [u32; 6] = [1000, 10000, 20000, 25000, 50000, 100000];
+
+/// the MTU range which SAI and the kernel accept for our ports
+const MTU_RANGE: std::ops::RangeInclusive<u32> = 68..=9216;
+
+/// The problems found in a port config. Every problem is a message which names the offending
+/// port config entry (0-based, like our physical port indexes).
+#[derive(Debug, Clone, Default)]
+pub struct ConfigCheck {
+    /// the port config cannot work like this
+    pub errors: Vec<String>,
+    /// the port config works, but probably not as intended
+    pub warnings: Vec<String>,
+}
+
+impl ConfigCheck {
+    fn extend(&mut self, other: ConfigCheck, prefix: &str) {
+        self.errors.extend(
+            other
+                .errors
+                .into_iter()
+                .map(|e| format!("{}: {}", prefix, e)),
+        );
+        self.warnings.extend(
+            other
+                .warnings
+                .into_iter()
+                .map(|w| format!("{}: {}", prefix, w)),
+        );
+    }
+}
+
+/// Checks a port config without a switch: lane uniqueness, the speeds per lane count and the
+/// feasibility of all breakout modes.
+pub fn check_port_config(config: &[PhysicalPortConfig]) -> ConfigCheck {
+    let mut ret = ConfigCheck::default();
+
+    // every lane must only be used once across all ports
+    let mut lane_users: HashMap<u32, usize> = HashMap::new();
+    for (i, pc) in config.iter().enumerate() {
+        if pc.lanes.is_empty() {
+            ret.errors.push(format!("entry {}: no lanes", i));
+            continue;
+        }
+        for lane in pc.lanes.iter() {
+            match lane_users.get(lane) {
+                Some(j) if *j == i => ret.errors.push(format!(
+                    "entry {}: lane {} is listed more than once",
+                    i, lane
+                )),
+                Some(j) => ret.errors.push(format!(
+                    "entry {}: lane {} is already used by entry {}",
+                    i, lane, j
+                )),
+                None => {
+                    lane_users.insert(*lane, i);
+                }
+            }
+        }
+        ret.extend(check_entry(pc), &format!("entry {}", i));
+    }
+    ret
+}
+
+fn check_entry(pc: &PhysicalPortConfig) -> ConfigCheck {
+    let mut ret = ConfigCheck::default();
+
+    // every breakout mode that we would guess for the port must be possible with its lanes,
+    // and it needs a sane speed
+    let modes = pc.get_supported_breakout_modes();
+    if modes.is_empty() {
+        ret.errors
+            .push(format!("unsupported number of lanes: {}", pc.lanes.len()));
+    }
+    for mode in modes.iter() {
+        if let Err(e) = split_lanes(*mode, &pc.lanes) {
+            ret.errors
+                .push(format!("breakout mode {} is not feasible: {}", mode, e));
+        }
+        match pc.speed.get(mode.lanes_per_port()) {
+            Some(speed) => {
+                if let Some(e) = check_speed(speed, mode.lanes_per_port()) {
+                    ret.errors
+                        .push(format!("speed for breakout mode {}: {}", mode, e));
+                }
+            }
+            None => ret.warnings.push(format!(
+                "no speed for breakout mode {}: a default speed is going to be used",
+                mode
+            )),
+        }
+    }
+
+    // speeds for lane counts that the port does not have are never used
+    for (num_lanes, speed) in [
+        (1, pc.speed.one_lane),
+        (2, pc.speed.two_lanes),
+        (4, pc.speed.four_lanes),
+        (8, pc.speed.eight_lanes),
+    ] {
+        if speed.is_some() && num_lanes > pc.lanes.len() {
+            ret.warnings.push(format!(
+                "speed for {} lane(s) is set, but the port only has {} lane(s)",
+                num_lanes,
+                pc.lanes.len()
+            ));
+        }
+    }
+
+    if let Some(mode) = pc.breakout_mode {
+        if let Err(e) = split_lanes(mode, &pc.lanes) {
+            ret.errors.push(format!(
+                "fixed breakout mode {} is not feasible: {}",
+                mode, e
+            ));
+        }
+    }
+    if let Some(speed) = pc.fixed_speed {
+        let num_lanes = pc
+            .breakout_mode
+            .map(|mode| mode.lanes_per_port())
+            .unwrap_or(pc.lanes.len());
+        if let Some(e) = check_speed(speed, num_lanes) {
+            ret.errors.push(format!("fixed speed: {}", e));
+        }
+    }
+    if let Some(mtu) = pc.mtu {
+        if !MTU_RANGE.contains(&mtu) {
+            ret.errors.push(format!(
+                "MTU {} is out of range ({}-{})",
+                mtu,
+                MTU_RANGE.start(),
+                MTU_RANGE.end()
+            ));
+        }
+    }
+    if pc.admin_down && pc.discovery == Some(true) {
+        ret.errors
+            .push("discovery is enabled, but the port is admin down".to_string());
+    }
+    ret
+}
+
+/// checks if a speed is a known speed for a port with `num_lanes` lanes, and describes the problem if not
+pub fn check_speed(speed: u32, num_lanes: usize) -> Option<String> {
+    let num_lanes = num_lanes.max(1) as u32;
+    if speed == 0 {
+        return Some("speed must not be 0".to_string());
+    }
+    if !speed.is_multiple_of(num_lanes) || !KNOWN_LANE_SPEEDS.contains(&(speed / num_lanes)) {
+        return Some(format!(
+            "{} on {} lane(s) is not a known speed per lane",
+            speed, num_lanes
+        ));
+    }
+    None
+}
+
+/// Compares the port config against the lanes of the ports of the switch. Only the lane sets
+/// have to match: the daemon sorts the ports of the switch by the port config, so neither the
+/// order of the entries nor the order of the lanes within an entry matters.
+pub fn compare_port_config(
+    config: &[PhysicalPortConfig],
+    switch_lanes: &[Vec<u32>],
+) -> Vec<String> {
+    let mut ret = Vec::new();
+    if config.len() != switch_lanes.len() {
+        ret.push(format!(
+            "the port config has {} entries, but the switch has {} ports",
+            config.len(),
+            switch_lanes.len()
+        ));
+    }
+    for (i, pc) in config.iter().enumerate() {
+        if !switch_lanes
+            .iter()
+            .any(|lanes| same_lanes(lanes, &pc.lanes))
+        {
+            ret.push(format!(
+                "entry {}: no port on the switch with lanes {:?}",
+                i, pc.lanes
+            ));
+        }
+    }
+    for (j, lanes) in switch_lanes.iter().enumerate() {
+        if !config.iter().any(|pc| same_lanes(&pc.lanes, lanes)) {
+            ret.push(format!(
+                "port {}: lanes {:?} are missing from the port config",
+                j, lanes
+            ));
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::port_config::PhysicalPortConfigBreakoutMode;
+    use crate::port_config::PhysicalPortConfigFECMode;
+
+    fn config(contents: &str) -> Vec<PhysicalPortConfig> {
+        PhysicalPortConfig::parse(contents).unwrap()
+    }
+
+    #[test]
+    fn valid_config() {
+        let config = config(
+            r#"[
+                {"lanes": [1, 2, 3, 4], "speed": {"OneLane": 25000, "TwoLanes": 50000, "FourLanes": 100000}},
+                {"lanes": [5], "speed": {"OneLane": 10000}, "mtu": 1500}
+            ]"#,
+        );
+        let check = check_port_config(&config);
+        assert!(check.errors.is_empty(), "{:?}", check.errors);
+        assert!(check.warnings.is_empty(), "{:?}", check.warnings);
+    }
+
+    #[test]
+    fn example_config_from_docs() {
+        let path =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../docs/port_config.json");
+        let config = PhysicalPortConfig::from_file(&path).unwrap();
+        assert_eq!(config.len(), 34);
+        for (i, pc) in config.iter().enumerate() {
+            let check = check_entry(pc);
+            assert!(check.errors.is_empty(), "entry {}: {:?}", i, check.errors);
+        }
+
+        // the static per-port overrides
+        assert_eq!(
+            config[0].breakout_mode,
+            Some(PhysicalPortConfigBreakoutMode::FourLanes)
+        );
+        assert_eq!(config[0].fixed_speed, Some(100000));
+        assert_eq!(config[0].fec, Some(PhysicalPortConfigFECMode::RS));
+        assert_eq!(config[0].auto_neg, Some(false));
+        assert_eq!(config[0].mtu, Some(9100));
+        assert_eq!(config[0].alias.as_deref(), Some("Ethernet0"));
+        assert!(!config[0].discovery_enabled());
+        assert_eq!(config[1].preferred_fec, Some(PhysicalPortConfigFECMode::RS));
+        assert!(config[1].discovery_enabled());
+        assert_eq!(
+            config[2].breakout_mode,
+            Some(PhysicalPortConfigBreakoutMode::OneLane)
+        );
+        assert_eq!(config[2].auto_neg, Some(true));
+        assert!(config[31].admin_down);
+        assert!(!config[31].discovery_enabled());
+        assert!(config[32].discovery_enabled());
+    }
+
+    #[test]
+    fn missing_and_unused_speeds_are_warnings() {
+        let config = config(
+            r#"[
+                {"lanes": [1, 2, 3, 4], "speed": {"FourLanes": 100000, "EightLanes": 400000}}
+            ]"#,
+        );
+        let check = check_port_config(&config);
+        assert!(check.errors.is_empty(), "{:?}", check.errors);
+        assert_eq!(
+            check.warnings,
+            vec![
+                "entry 0: no speed for breakout mode OneLane: a default speed is going to be used",
+                "entry 0: no speed for breakout mode TwoLanes: a default speed is going to be used",
+                "entry 0: speed for 8 lane(s) is set, but the port only has 4 lane(s)",
+            ]
+        );
+    }
+
+    #[test]
+    fn lane_errors() {
+        let config = config(
+            r#"[
+                {"lanes": [1, 1], "speed": {"OneLane": 25000, "TwoLanes": 50000}},
+                {"lanes": [1], "speed": {"OneLane": 25000}},
+                {"lanes": [], "speed": {}},
+                {"lanes": [2, 3, 4], "speed": {"OneLane": 25000}}
+            ]"#,
+        );
+        let check = check_port_config(&config);
+        assert_eq!(
+            check.errors,
+            vec![
+                "entry 0: lane 1 is listed more than once",
+                "entry 1: lane 1 is already used by entry 0",
+                "entry 2: no lanes",
+                "entry 3: unsupported number of lanes: 3",
+            ]
+        );
+    }
+
+    #[test]
+    fn setting_errors() {
+        let config = config(
+            r#"[
+                {"lanes": [1], "speed": {"OneLane": 33000}, "mtu": 10000},
+                {"lanes": [2, 3, 4, 5], "speed": {"OneLane": 25000, "TwoLanes": 50000, "FourLanes": 100000},
+                 "breakout_mode": "TwoLanes", "fixed_speed": 60000},
+                {"lanes": [6], "speed": {"OneLane": 25000}, "admin_down": true, "discovery": true}
+            ]"#,
+        );
+        let check = check_port_config(&config);
+        assert!(check.warnings.is_empty(), "{:?}", check.warnings);
+        assert_eq!(
+            check.errors,
+            vec![
+                "entry 0: speed for breakout mode OneLane: 33000 on 1 lane(s) is not a known speed per lane",
+                "entry 0: MTU 10000 is out of range (68-9216)",
+                "entry 1: fixed speed: 60000 on 2 lane(s) is not a known speed per lane",
+                "entry 2: discovery is enabled, but the port is admin down",
+            ]
+        );
+    }
+
+    #[test]
+    fn compare_against_switch() {
+        let config = config(
+            r#"[
+                {"lanes": [5, 6, 7, 8], "speed": {}},
+                {"lanes": [4, 3, 2, 1], "speed": {}},
+                {"lanes": [9], "speed": {}}
+            ]"#,
+        );
+        let switch_lanes = vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8], vec![10]];
+        assert_eq!(
+            compare_port_config(&config, &switch_lanes),
+            vec![
+                "entry 2: no port on the switch with lanes [9]",
+                "port 2: lanes [10] are missing from the port config",
+            ]
+        );
+        assert_eq!(
+            compare_port_config(&config[..2], &switch_lanes[..1]),
+            vec![
+                "the port config has 2 entries, but the switch has 1 ports",
+                "entry 0: no port on the switch with lanes [5, 6, 7, 8]",
+            ]
+        );
+        // the order of the entries and of the lanes does not matter
+        assert!(compare_port_config(&config[..2], &switch_lanes[..2]).is_empty());
+    }
+}
@@ -1,3 +1,5 @@
+use onie_sai_common::port_config::check::check_port_config;
+use onie_sai_common::port_config::PhysicalPortConfig;
 use onie_sai_rpc::onie_sai;
 use onie_sai_rpc::onie_sai_ttrpc;
 use onie_sai_rpc::onie_sai_ttrpc::OnieSaiClient;
@@ -7,13 +9,15 @@ use ttrpc::Client;
 
 use std::io::stdin;
 use std::io::stdout;
-use std::io::BufRead;
 use std::io::Read;
 use std::io::Write;
 use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
 use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 
 use anyhow::Context as AnyhowContext;
 use clap::{Args, Parser, Subcommand, ValueEnum};
@@ -69,17 +73,28 @@ enum Commands {
     Version,
 
     /// lists all ports and their details
-    Ports,
+    Ports(PortsArgs),
 
     /// lists all routes that are installed directed to the CPU
     Routes,
 
+    /// applies settings manually to a port which disables auto-discovery for it
+    /// use this to force a known-good configuration when auto-discovery does not bring up a port
+    SetPort(SetPortArgs),
+
     /// gets auto-discovery status of onie-said
     /// or you can enable/disable it within onie-said
     AutoDiscovery(AutoDiscoveryArgs),
 
+    /// restarts port auto-discovery from scratch for a port or all ports where it is enabled
+    Rediscover(RediscoverArgs),
+
     /// runs the SAI vendor shell
-    Shell,
+    /// or runs a single command in it non-interactively, and prints its output
+    Shell(ShellArgs),
+
+    /// generates a SAI debug dump and writes it to the given file
+    SAIDump(SAIDumpArgs),
 
     /// shuts down onie-said (equals sending a SIGTERM to the process)
     Shutdown,
@@ -91,14 +106,131 @@ enum Commands {
     /// Information about LLDP on a given device.
     LLDP(LLDPArgs),
 
+    /// status of the switch: shows if the switch was torn down after a failure or shutdown request from the ASIC
+    SwitchStatus,
+
+    /// status of the reconciliation loop: shows how many divergences from the desired state were found and repaired
+    ReconcileStatus,
+
+    /// shows the effective port discovery profile of onie-said
+    DiscoveryProfile,
+
+    /// validates a port config file, and compares it against the ports of the switch
+    ValidatePortConfig(ValidatePortConfigArgs),
+
     /// Retrieves the network configuration for a given device as received over LLDP over the interface.
     /// NOTE: This command is specific to the Hedgehog Fabric implementation of LLDP packets that are sent from SONiC switches and Hedgehog Fabric control nodes.
     LLDPNetworkConfig(LLDPNetworkConfigArgs),
 }
 
+#[derive(Args)]
+struct PortsArgs {
+    /// shows the discovery state and history of all ports
+    #[arg(long)]
+    discovery: bool,
+}
+
+#[derive(Args)]
+struct SetPortArgs {
+    /// the index of the physical port
+    id: u32,
+
+    /// the index of the logical port within the physical port: the settings apply to all logical ports if not set
+    #[arg(long, short)]
+    logical_port: Option<u32>,
+
+    /// the breakout mode: this is applied first, and recreates all logical ports
+    #[arg(long, value_enum)]
+    breakout_mode: Option<BreakoutMode>,
+
+    #[arg(long)]
+    admin_state: Option<bool>,
+
+    /// the speed in Mbps
+    #[arg(long)]
+    speed: Option<u32>,
+
+    #[arg(long)]
+    auto_neg: Option<bool>,
+
+    #[arg(long, value_enum)]
+    fec: Option<FECMode>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum BreakoutMode {
+    OneLane,
+    TwoLanes,
+    FourLanes,
+    EightLanes,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum FECMode {
+    None,
+    RS,
+    FC,
+}
+
+impl FECMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FECMode::None => "None",
+            FECMode::RS => "RS",
+            FECMode::FC => "FC",
+        }
+    }
+}
+
 #[derive(Args)]
 struct AutoDiscoveryArgs {
     enable: Option<bool>,
+
+    /// also try to break out ports during auto-discovery
+    #[arg(long)]
+    with_breakout: Option<bool>,
+
+    /// the index of the physical port: applies to all ports if not set
+    #[arg(long, short)]
+    port: Option<u32>,
+}
+
+#[derive(Args)]
+struct RediscoverArgs {
+    /// the index of the physical port: all ports are rediscovered if not set
+    #[arg(long, short)]
+    port: Option<u32>,
+}
+
+#[derive(Args)]
+struct ValidatePortConfigArgs {
+    /// the port_config.json to validate
+    file: PathBuf,
+
+    /// only checks the port config itself without comparing it against the ports of the switch:
+    /// this does not need a running onie-said
+    #[arg(long)]
+    local: bool,
+}
+
+#[derive(Args)]
+struct ShellArgs {
+    /// the command to run in the shell, the shell will be interactive if not set
+    #[arg(long, short)]
+    command: Option<String>,
+
+    /// how long to wait for the command to finish (only used with --command)
+    #[arg(long, short)]
+    timeout_secs: Option<u32>,
+
+    /// closes the interactive shell after it was idle for this long
+    #[arg(long)]
+    idle_timeout_secs: Option<u32>,
+}
+
+#[derive(Args)]
+struct SAIDumpArgs {
+    file: PathBuf,
 }
 
 #[derive(Args)]
@@ -145,7 +277,7 @@ fn app() -> anyhow::Result<()> {
             println!("onie-said version: {}", resp.onie_said_version);
             println!("SAI version: {}", resp.sai_version);
         }
-        Commands::Ports => {
+        Commands::Ports(args) => {
             let osc = connect(&cli.address)?;
             let req = onie_sai::PortListRequest::new();
             log::info!("making request to onie-said: {:?}...", req);
@@ -153,6 +285,9 @@ fn app() -> anyhow::Result<()> {
                 .port_list(default_ctx(), &req)
                 .context("request to onie-said failed")?;
             log::info!("response from onie-said: {:?}", resp);
+            if args.discovery {
+                print_port_discovery(&resp.port_list);
+            }
         }
         Commands::Routes => {
             let osc = connect(&cli.address)?;
@@ -163,11 +298,46 @@ fn app() -> anyhow::Result<()> {
                 .context("request to onie-said failed")?;
             log::info!("response from onie-said: {:?}", resp);
         }
+        Commands::SetPort(args) => {
+            let osc = connect(&cli.address)?;
+            let req = onie_sai::SetPortRequest {
+                id: args.id,
+                logical_port: args.logical_port,
+                breakout_mode: args.breakout_mode.map(|v| format!("{:?}", v)),
+                admin_state: args.admin_state,
+                speed: args.speed,
+                auto_neg: args.auto_neg,
+                fec: args.fec.map(|v| v.as_str().to_string()),
+                ..Default::default()
+            };
+            log::info!("making request to onie-said: {:?}...", req);
+            let resp = osc
+                .set_port(default_ctx(), &req)
+                .context("request to onie-said failed")?;
+            log::info!("response from onie-said: {:?}", resp);
+            if let Some(port) = resp.port.into_option() {
+                println!("Port {}: auto discovery: off", port.id);
+                for lp in port.ports.iter() {
+                    println!(
+                        "  Logical Port {} (lanes: {:?}): admin state: {}, speed: {}, auto negotiation: {}, FEC: {}, oper status: {}",
+                        lp.oid,
+                        lp.hw_lanes,
+                        if lp.admin_state { "up" } else { "down" },
+                        lp.speed,
+                        lp.auto_neg,
+                        lp.fec,
+                        if lp.oper_status { "up" } else { "down" }
+                    );
+                }
+            }
+        }
         Commands::AutoDiscovery(v) => {
             let osc = connect(&cli.address)?;
             log::info!("auto discovery args: {:?}", v.enable);
             let req = onie_sai::AutoDiscoveryRequest {
                 enable: v.enable,
+                enable_with_breakout: v.with_breakout,
+                port: v.port,
                 ..Default::default()
             };
             log::info!("making request to onie-said: {:?}...", req);
@@ -175,14 +345,62 @@ fn app() -> anyhow::Result<()> {
                 .auto_discovery(default_ctx(), &req)
                 .context("request to onie-said failed")?;
             log::info!("response from onie-said: {:?}", resp);
+            let target = match v.port {
+                Some(port) => format!("port {}", port),
+                None => "onie-said".to_string(),
+            };
+            println!(
+                "{}: auto-discovery is {} (port breakout discovery: {})",
+                target,
+                if resp.enabled { "on" } else { "off" },
+                if resp.enabled_with_breakout {
+                    "on"
+                } else {
+                    "off"
+                }
+            );
+        }
+        Commands::Rediscover(v) => {
+            let osc = connect(&cli.address)?;
+            let req = onie_sai::RediscoverRequest {
+                port: v.port,
+                ..Default::default()
+            };
+            log::info!("making request to onie-said: {:?}...", req);
+            let resp = osc
+                .rediscover(default_ctx(), &req)
+                .context("request to onie-said failed")?;
+            log::info!("response from onie-said: {:?}", resp);
             println!(
-                "onie-said: auto-discovery is {}",
-                if resp.enabled { "on" } else { "off" }
+                "onie-said: restarted auto-discovery for ports: {:?}",
+                resp.ports
             );
         }
-        Commands::Shell => {
+        Commands::Shell(args) => {
             let osc = connect(&cli.address)?;
-            shell_command(osc)?;
+            match args.command {
+                None => shell_command(osc, args.idle_timeout_secs)?,
+                Some(command) => {
+                    let req = onie_sai::ShellExecRequest {
+                        command: command,
+                        timeout_secs: args.timeout_secs,
+                        ..Default::default()
+                    };
+                    log::info!("making request to onie-said: {:?}...", req);
+                    let resp = osc
+                        .shell_exec(default_ctx(), &req)
+                        .context("request to onie-said failed")?;
+                    log::info!("response from onie-said: {:?}", resp);
+                    print!("{}", resp.output);
+                    if resp.timed_out {
+                        return Err(anyhow::anyhow!("shell command timed out"));
+                    }
+                }
+            }
+        }
+        Commands::SAIDump(args) => {
+            let osc = connect(&cli.address)?;
+            sai_dump_command(osc, &args.file)?;
         }
         Commands::Shutdown => {
             let osc = connect(&cli.address)?;
@@ -228,6 +446,112 @@ fn app() -> anyhow::Result<()> {
                 thread::sleep(Duration::from_millis(1000));
             }
         }
+        Commands::SwitchStatus => {
+            let osc = connect(&cli.address)?;
+            let req = onie_sai::SwitchStatusRequest::new();
+            log::info!("making request to onie-said: {:?}...", req);
+            let resp = osc
+                .switch_status(default_ctx(), &req)
+                .context("request to onie-said failed")?;
+            log::info!("response from onie-said: {:?}", resp);
+            println!(
+                "onie-said: switch is {}",
+                if resp.running { "running" } else { "torn down" }
+            );
+            println!("onie-said: switch operational status: {}", resp.oper_status);
+            println!(
+                "onie-said: switch shutdown requested: {}",
+                resp.shutdown_requested
+            );
+        }
+        Commands::ReconcileStatus => {
+            let osc = connect(&cli.address)?;
+            let req = onie_sai::ReconcileStatusRequest::new();
+            log::info!("making request to onie-said: {:?}...", req);
+            let resp = osc
+                .reconcile_status(default_ctx(), &req)
+                .context("request to onie-said failed")?;
+            log::info!("response from onie-said: {:?}", resp);
+            if resp.enabled {
+                println!(
+                    "onie-said: reconciliation loop enabled (interval: {}ms)",
+                    resp.interval_ms
+                );
+            } else {
+                println!("onie-said: reconciliation loop disabled");
+            }
+            println!("onie-said: reconciliation runs: {}", resp.runs);
+            println!("onie-said: divergences found: {}", resp.drift_count);
+            println!("onie-said: divergences repaired: {}", resp.repair_count);
+            for unrepaired in resp.unrepaired.iter() {
+                println!("onie-said: not repaired: {}", unrepaired);
+            }
+        }
+        Commands::DiscoveryProfile => {
+            let osc = connect(&cli.address)?;
+            let req = onie_sai::DiscoveryProfileRequest::new();
+            log::info!("making request to onie-said: {:?}...", req);
+            let resp = osc
+                .discovery_profile(default_ctx(), &req)
+                .context("request to onie-said failed")?;
+            log::info!("response from onie-said: {:?}", resp);
+            println!("onie-said: poll interval: {}ms", resp.poll_interval_ms);
+            println!("onie-said: start dwell time: {}ms", resp.start_dwell_ms);
+            println!("onie-said: speed dwell time: {}ms", resp.speed_dwell_ms);
+            println!("onie-said: FEC dwell time: {}ms", resp.fec_dwell_ms);
+            println!(
+                "onie-said: auto negotiation dwell time: {}ms",
+                resp.auto_neg_dwell_ms
+            );
+            println!("onie-said: strategies: {}", resp.strategies.join(", "));
+            println!("onie-said: try FEC modes: {}", resp.fec);
+            match resp.max_retries {
+                Some(v) => println!("onie-said: max retries: {}", v),
+                None => println!("onie-said: max retries: unlimited"),
+            }
+        }
+        Commands::ValidatePortConfig(args) => {
+            let contents = std::fs::read_to_string(&args.file)
+                .context(format!("failed to read {}", args.file.display()))?;
+            let config = PhysicalPortConfig::parse(&contents)
+                .context(format!("invalid port config {}", args.file.display()))?;
+            let check = check_port_config(&config);
+            for w in check.warnings.iter() {
+                println!("warning: {}", w);
+            }
+            for e in check.errors.iter() {
+                println!("error: {}", e);
+            }
+
+            // only the comparison against the ports of the switch needs onie-said
+            let mismatches = if args.local {
+                Vec::new()
+            } else {
+                let osc = connect(&cli.address)?;
+                let req = onie_sai::ValidatePortConfigRequest {
+                    contents: contents,
+                    ..Default::default()
+                };
+                log::info!("making request to onie-said...");
+                let resp = osc
+                    .validate_port_config(default_ctx(), &req)
+                    .context("request to onie-said failed")?;
+                log::info!("response from onie-said: {:?}", resp);
+                resp.mismatches
+            };
+            for m in mismatches.iter() {
+                println!("mismatch: {}", m);
+            }
+            if !check.errors.is_empty() || !mismatches.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "{}: {} error(s), {} mismatch(es) with the switch",
+                    args.file.display(),
+                    check.errors.len(),
+                    mismatches.len()
+                ));
+            }
+            println!("{}: OK", args.file.display());
+        }
         Commands::LLDP(args) => {
             let osc = connect(&cli.address)?;
             let req = onie_sai::LLDPStatusRequest {
@@ -275,6 +599,7 @@ fn app() -> anyhow::Result<()> {
                         );
                     }
                     println!("onie_lldp_{}_is_hh=\"{}\"", dev, network_config.is_hh);
+                    println!("onie_lldp_{}_applied=\"{}\"", dev, resp.applied);
                     break;
                 }
                 wait_secs -= 1;
@@ -300,10 +625,48 @@ fn default_ctx() -> Context {
     ctx
 }
 
-const SHELL_PROMPT: &str = "sai-shell> ";
 const SHELL_SOCKET: &str = "/run/onie-saictl-shell.socket";
 
-fn shell_command(osc: OnieSaiClient) -> anyhow::Result<()> {
+/// puts the terminal into raw mode for as long as it lives, so that the shell on the other side
+/// is in control of line editing, echoing, etc.pp.
+struct RawTerminal {
+    orig: libc::termios,
+}
+
+impl RawTerminal {
+    fn new() -> Option<Self> {
+        if unsafe { libc::isatty(libc::STDIN_FILENO) } != 1 {
+            return None;
+        }
+        let mut orig: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut orig) } < 0 {
+            return None;
+        }
+        let mut raw = orig;
+        unsafe { libc::cfmakeraw(&mut raw) };
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) } < 0 {
+            return None;
+        }
+        Some(Self { orig })
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.orig) };
+    }
+}
+
+/// returns the (rows, cols) of the terminal, or None if stdout is not a terminal
+fn window_size() -> Option<(u32, u32)> {
+    let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
+    if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut ws) } < 0 {
+        return None;
+    }
+    Some((ws.ws_row as u32, ws.ws_col as u32))
+}
+
+fn shell_command(osc: OnieSaiClient, idle_timeout_secs: Option<u32>) -> anyhow::Result<()> {
     // start listener that listens on SHELL_SOCKET that onie-said will connect to
     let _ = std::fs::remove_file(SHELL_SOCKET);
     let listener = UnixListener::bind(SHELL_SOCKET).context(format!(
@@ -312,120 +675,228 @@ fn shell_command(osc: OnieSaiClient) -> anyhow::Result<()> {
     ))?;
 
     // now send request to onie-said to start shell
-    let rpc_thread = thread::spawn(move || {
-        let req = onie_sai::ShellRequest {
-            socket: SHELL_SOCKET.to_string(),
-            ..Default::default()
-        };
-        log::info!("making request to onie-said: {:?}...", req);
-        let resp = osc
-            .shell(default_ctx(), &req)
-            .expect("request to onie-said failed");
-        log::info!("response from onie-said: {:?}", resp);
-    });
+    // onie-said connects to our socket before it responds, and returns immediately after
+    let (rows, cols) = window_size().unwrap_or((0, 0));
+    let req = onie_sai::ShellRequest {
+        socket: SHELL_SOCKET.to_string(),
+        rows: rows,
+        cols: cols,
+        idle_timeout_secs: idle_timeout_secs,
+        ..Default::default()
+    };
+    log::info!("making request to onie-said: {:?}...", req);
+    let resp = osc.shell(default_ctx(), &req);
+    log::info!("response from onie-said: {:?}", resp);
+    let resp = match resp {
+        Ok(resp) => resp,
+        Err(ttrpc::Error::RpcStatus(status)) if status.code() == ttrpc::Code::UNAVAILABLE => {
+            let _ = std::fs::remove_file(SHELL_SOCKET);
+            return Err(anyhow::anyhow!(
+                "the shell is busy, somebody else is using it: {}",
+                status.message()
+            ));
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(SHELL_SOCKET);
+            return Err(e).context("request to onie-said failed");
+        }
+    };
+    let session_id = resp.session_id;
 
-    // wait for onie-said to connect
+    // onie-said has connected already
     let (mut conn, _) = listener
         .accept()
         .context("failed to accept incoming connection")?;
+    let _ = std::fs::remove_file(SHELL_SOCKET);
+    let mut conn_writer = conn.try_clone().context("failed to clone socket")?;
 
-    // set the connection to nonblocking
-    conn.set_nonblocking(true)
-        .context("failed to set socket to nonblocking")?;
-
-    // Clone the connection to handle input and output separately
-    let mut conn_reader = conn.try_clone().context("failed to clone socket")?;
+    // from here on the shell on the other side is in control of the terminal
+    let raw_terminal = RawTerminal::new();
 
-    // Spawn a separate thread to handle input from the socket and send it to stdout
+    // this thread copies everything from the socket to stdout until onie-said closes the session
     let (stdout_thread_tx, stdout_thread_rx) = mpsc::channel::<()>();
-    let stdout_thread = thread::spawn(move || {
+    thread::spawn(move || {
         let mut buffer = [0; 1024];
-        let mut need_to_write_prompt = true;
-        let mut need_to_exit_thread = false;
         loop {
-            if let Ok(_) = stdout_thread_rx.try_recv() {
-                need_to_exit_thread = true;
-            }
-            match conn_reader.read(&mut buffer) {
+            match conn.read(&mut buffer) {
+                Ok(0) => break,
                 Ok(n) => {
-                    if n == 0 {
-                        // EOF
-                        break;
-                    }
-                    // Write received data to stdout - ignore errors here
-                    {
-                        let mut stdout = stdout().lock();
-                        let _ = stdout.write_all(&buffer[0..n]);
-                        let _ = stdout.flush();
-                    }
-                    need_to_write_prompt = true;
-                }
-                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                    // Non-blocking mode, no data available yet
-                    if need_to_exit_thread {
-                        break;
-                    }
-                    if need_to_write_prompt {
-                        write_prompt();
-                        need_to_write_prompt = false;
-                    }
-                    thread::sleep(Duration::from_millis(10));
-                    continue;
+                    let mut stdout = stdout().lock();
+                    let _ = stdout.write_all(&buffer[0..n]);
+                    let _ = stdout.flush();
                 }
                 Err(e) => {
-                    log::error!(
-                        "Error reading from socket, closing stdout writer thread: {:?}",
-                        e
-                    );
+                    log::error!("Error reading from socket: {:?}", e);
                     break;
                 }
             }
         }
         log::debug!("stdout thread exiting");
+        let _ = stdout_thread_tx.send(());
     });
 
-    // now read input from stdin and send it to the socket
-    // NOTE: mheese: don't ask me about the voodoo below.
-    // This is the only way I could get this to work with the initialization prompt already displayed
-    // It is somewhat similar to what bcmshell.py is doin in SONiC, but not identical. They only need
-    // to echo once, we need to echo twice. Also, the thread sleep in between the echoes seems to be essential.
-    let stdin = stdin().lock();
-    for _ in 1..=2 {
-        conn.write_all("echo\n".as_bytes())
-            .context("failed to write to socket")?;
-        conn.flush().context("failed to flush write to socket")?;
-        thread::sleep(Duration::from_millis(100));
-    }
+    // this thread copies everything from stdin to the socket
+    // NOTE: it blocks on stdin, so we are not going to wait for it when the session is over
+    thread::spawn(move || {
+        let mut stdin = stdin().lock();
+        let mut buffer = [0; 1024];
+        loop {
+            match stdin.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => {
+                    if conn_writer.write_all(&buffer[0..n]).is_err() {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+        // no more input: tell onie-said that we are done
+        let _ = conn_writer.shutdown(std::net::Shutdown::Write);
+        log::debug!("stdin thread exiting");
+    });
 
-    for line in stdin.lines() {
-        let mut line = line.context("failed to read from stdin")?;
-        line.push('\n');
-        conn.write_all(line.as_bytes())
-            .context("failed to write to socket")?;
-        if line == "quit\n" {
-            break;
+    // wait for the session to end, and forward terminal size changes in the meantime
+    let mut last_size = (rows, cols);
+    loop {
+        match stdout_thread_rx.recv_timeout(Duration::from_millis(250)) {
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            _ => break,
+        }
+        if let Some(size) = window_size() {
+            if size != last_size {
+                last_size = size;
+                let req = onie_sai::ShellResizeRequest {
+                    session_id: session_id,
+                    rows: size.0,
+                    cols: size.1,
+                    ..Default::default()
+                };
+                if let Err(e) = osc.shell_resize(default_ctx(), &req) {
+                    log::debug!("failed to resize shell: {:?}", e);
+                }
+            }
         }
-        conn.flush().context("failed to flush write to socket")?;
     }
+
+    // restore the terminal before we print anything
+    drop(raw_terminal);
+    println!();
     log::debug!("shell command finished");
+    Ok(())
+}
 
-    // sync with threads before returning
+const SAI_DUMP_SOCKET: &str = "/run/onie-saictl-sai-dump.socket";
+
+fn sai_dump_command(osc: OnieSaiClient, path: &PathBuf) -> anyhow::Result<()> {
+    // start listener that listens on SAI_DUMP_SOCKET that onie-said will connect to
+    let _ = std::fs::remove_file(SAI_DUMP_SOCKET);
+    let listener = UnixListener::bind(SAI_DUMP_SOCKET).context(format!(
+        "failed to bind to SAI dump socket at {}",
+        SAI_DUMP_SOCKET
+    ))?;
+    listener
+        .set_nonblocking(true)
+        .context("failed to set listener to nonblocking")?;
+
+    // now send request to onie-said to generate the dump
+    let (rpc_tx, rpc_rx) = mpsc::channel();
+    let rpc_thread = thread::spawn(move || {
+        let req = onie_sai::SAIDumpRequest {
+            socket: SAI_DUMP_SOCKET.to_string(),
+            ..Default::default()
+        };
+        log::info!("making request to onie-said: {:?}...", req);
+        let resp = osc.sai_dump(default_ctx(), &req);
+        log::info!("response from onie-said: {:?}", resp);
+        let _ = rpc_tx.send(resp);
+    });
+
+    // wait for onie-said to connect
+    // if the request finishes before that, then generating the dump failed
+    let mut conn = loop {
+        match listener.accept() {
+            Ok((conn, _)) => break conn,
+            Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                if let Ok(resp) = rpc_rx.try_recv() {
+                    let _ = std::fs::remove_file(SAI_DUMP_SOCKET);
+                    resp.context("request to onie-said failed")?;
+                    return Err(anyhow::anyhow!(
+                        "onie-said finished the request without sending the dump"
+                    ));
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+            Err(e) => return Err(e).context("failed to accept incoming connection"),
+        }
+    };
+    conn.set_nonblocking(false)
+        .context("failed to set socket to blocking")?;
+
+    // write everything that we receive to the file
+    let mut file = std::fs::File::create(path).context(format!("failed to create {:?}", path))?;
+    let size = std::io::copy(&mut conn, &mut file)
+        .context(format!("failed to write SAI dump to {:?}", path))?;
+    file.flush()
+        .context(format!("failed to flush SAI dump to {:?}", path))?;
+
+    // sync with the RPC thread before returning
     rpc_thread
         .join()
         .map_err(|e| anyhow::anyhow!("RPC thread paniced: {:?}", e))?;
-    log::debug!("RPC thread exited");
-
-    // this thread might be dead, so the channel might be closed already
-    // the error can be ignored
-    let _ = stdout_thread_tx.send(());
-    stdout_thread
-        .join()
-        .map_err(|e| anyhow::anyhow!("stdout thread paniced: {:?}", e))?;
+    let _ = std::fs::remove_file(SAI_DUMP_SOCKET);
+    let resp = rpc_rx
+        .recv()
+        .context("failed to receive response from RPC thread")?
+        .context("request to onie-said failed")?;
+    if resp.size != size {
+        return Err(anyhow::anyhow!(
+            "SAI dump is incomplete: received {} bytes, but onie-said sent {} bytes",
+            size,
+            resp.size
+        ));
+    }
+    println!("SAI dump written to {} ({} bytes)", path.display(), size);
     Ok(())
 }
 
-fn write_prompt() {
-    let mut stdout = stdout().lock();
-    let _ = write!(stdout, "{}", SHELL_PROMPT);
-    let _ = stdout.flush();
+fn print_port_discovery(ports: &[onie_sai::Port]) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    for port in ports {
+        println!(
+            "Port {}: auto discovery: {}, restarts: {}, state: {}",
+            port.id,
+            if port.auto_discovery { "on" } else { "off" },
+            port.auto_discovery_counter,
+            port.discovery_state.as_deref().unwrap_or("-")
+        );
+        print_discovery_history(now, "  ", &port.discovery_history);
+        for lp in port.ports.iter() {
+            println!(
+                "  Logical Port {} (lanes: {:?}): state: {}, attempts: {}",
+                lp.oid,
+                lp.hw_lanes,
+                lp.discovery_state.as_deref().unwrap_or("-"),
+                lp.discovery_attempts
+            );
+            print_discovery_history(now, "    ", &lp.discovery_history);
+        }
+    }
+}
+
+fn print_discovery_history(now: u64, indent: &str, history: &[onie_sai::DiscoveryHistoryEntry]) {
+    for entry in history {
+        let ago = now.saturating_sub(entry.timestamp_ms) as f64 / 1000.0;
+        println!(
+            "{}[{:>8.1}s ago] {} -> {} ({})",
+            indent,
+            ago,
+            entry.from.as_deref().unwrap_or("-"),
+            entry.to,
+            entry.details
+        );
+    }
 }
@@ -1,20 +1,15 @@
 mod lldp;
 mod processor;
 mod rpc;
+mod syseeprom;
 
 use std::env;
 use std::ffi::CString;
 use std::fs::File;
 use std::io::BufRead;
 use std::io::BufReader;
-use std::io::ErrorKind;
-use std::io::Read;
-use std::io::Write;
-use std::os::fd::AsRawFd;
-use std::os::fd::FromRawFd;
 use std::path::Path;
 use std::path::PathBuf;
-use std::process::Stdio;
 use std::sync::OnceLock;
 use std::thread;
 use std::time::Duration;
@@ -24,13 +19,28 @@ use clap::{Parser, ValueEnum};
 use log::LevelFilter;
 
 use macaddr::MacAddr6;
+use onie_sai_common::port_config::check::check_port_config;
 
 use sai::SAI;
 
 use crate::processor::netlink;
+use crate::processor::port::discovery::profile::DiscoveryProfile;
+use crate::processor::port::discovery::profile::DiscoveryProfileError;
+use crate::processor::port::discovery::profile::DiscoveryStrategy;
+use crate::processor::port::hostif_naming::HostIfNaming;
+use crate::processor::port::hostif_naming::HostIfNamingScheme;
+use crate::processor::port::mac::PortMacMode;
+use crate::processor::port::mac::PortMacSettings;
+use crate::processor::port::mac::PortMacSettingsError;
+use crate::processor::port::media_settings::MediaSettings;
+use crate::processor::port::sonic_config;
 use crate::processor::port::PhysicalPortConfig;
+use crate::processor::warm_boot::WarmBoot;
+use crate::processor::warm_boot::WarmBootState;
 use crate::processor::PlatformContextHolder;
+use crate::processor::ProcessExit;
 use crate::processor::Processor;
+use crate::syseeprom::SysEeprom;
 
 use ctrlc;
 use std::sync::mpsc::channel;
@@ -42,9 +52,25 @@ struct Cli {
     #[arg(long, value_enum, default_value_t=LogLevel::Warn)]
     log_level: LogLevel,
 
-    /// The default MAC address to use in the switch. Pass the correct MAC address from your ONIE syseeprom here.
-    #[arg(long, default_value_t=MacAddr6::new(0xee, 0xba, 0x4a, 0xb9, 0xb1, 0x24))]
-    mac_addr: MacAddr6,
+    /// Overrides the MAC address of the switch which is otherwise read from the base MAC address of the ONIE syseeprom
+    #[arg(long)]
+    mac_addr: Option<MacAddr6>,
+
+    /// The ONIE TlvInfo system EEPROM: usually a symlink to the sysfs file of the EEPROM
+    #[arg(long, default_value = arg_syseeprom_file())]
+    syseeprom_file: PathBuf,
+
+    /// The port MAC settings of the platform: if the ports share the MAC address, or get their own MAC address derived from it. Defaults are used if it does not exist.
+    #[arg(long, default_value = arg_port_mac_settings_file())]
+    port_mac_settings_file: PathBuf,
+
+    /// Overrides the port MAC mode from the port MAC settings
+    #[arg(long, value_enum)]
+    port_mac_mode: Option<PortMacMode>,
+
+    /// Overrides the number of MAC addresses reserved for the device from the port MAC settings and the ONIE syseeprom
+    #[arg(long)]
+    mac_addr_count: Option<u64>,
 
     /// Whether to enable port auto discovery
     #[arg(long, default_value = "true", default_missing_value = "true")]
@@ -54,6 +80,18 @@ struct Cli {
     #[arg(long, default_value = "false", default_missing_value = "true")]
     auto_discovery_with_breakout: Option<Option<bool>>,
 
+    /// Whether to program the IP address and routes which are learned through LLDP onto the host interfaces
+    #[arg(long, default_value = "false", default_missing_value = "true")]
+    lldp_apply_network_config: Option<Option<bool>>,
+
+    /// Whether to sync the neighbors and routes of the host interfaces from the kernel to the switch, so that it forwards traffic for them
+    #[arg(long, default_value = "false", default_missing_value = "true")]
+    route_sync: Option<Option<bool>>,
+
+    /// How often the host interfaces, router interfaces and routes get compared against SAI and the kernel, and repaired if they diverged. 0 disables the reconciliation loop, which is the default.
+    #[arg(long, default_value_t = 0)]
+    reconcile_interval_ms: u64,
+
     /// The platform to use: this should always be auto-detected.
     #[arg(long, default_value = arg_platform())]
     platform: String,
@@ -63,10 +101,66 @@ struct Cli {
 
     #[arg(long, default_value = arg_port_config_file())]
     port_config_file: PathBuf,
+
+    /// Only checks the port config for problems, and exits without initializing SAI
+    #[arg(long)]
+    check_config: bool,
+
+    /// The SONiC device directory of the platform. The port config is taken from the SONiC
+    /// platform.json, port_config.ini or config_db.json in it if the port config file does not exist.
+    #[arg(long, default_value = arg_sonic_device_dir())]
+    sonic_device_dir: PathBuf,
+
+    /// The SONiC media settings file of the platform with the serdes tuning values for transceivers
+    #[arg(long, default_value = arg_media_settings_file())]
+    media_settings_file: PathBuf,
+
+    /// The port discovery profile with the timings and strategies of the port auto discovery. Defaults are used if it does not exist.
+    #[arg(long, default_value = arg_discovery_profile_file())]
+    discovery_profile_file: PathBuf,
+
+    /// Overrides the poll interval of the port auto discovery in milliseconds from the discovery profile
+    #[arg(long)]
+    discovery_poll_interval_ms: Option<u64>,
+
+    /// Overrides how often port auto discovery gets retried for a port from the discovery profile
+    #[arg(long)]
+    discovery_max_retries: Option<u64>,
+
+    /// Overrides the order of the port auto discovery strategies from the discovery profile
+    #[arg(long, value_enum, value_delimiter = ',')]
+    discovery_strategies: Option<Vec<DiscoveryStrategy>>,
+
+    /// How the host interfaces of the ports get named. Names must fit into the 15 characters of a Linux interface name.
+    #[arg(long, value_enum, default_value_t=HostIfNamingScheme::Index)]
+    hostif_naming: HostIfNamingScheme,
+
+    /// The template for the template host interface naming scheme, e.g. "eth{port}-{subport}"
+    #[arg(long, required_if_eq("hostif_naming", "template"))]
+    hostif_name_template: Option<String>,
+
+    /// Whether to enable warm restarts: the state is saved on shutdown, and restored on the next start without reinitializing the ASIC
+    #[arg(long, default_value = "false", default_missing_value = "true")]
+    warm_boot: Option<Option<bool>>,
+
+    /// The file where SAI saves its state to for warm restarts
+    #[arg(long, default_value = "/run/onie-said/sai-warm-boot.bin")]
+    warm_boot_sai_state_file: PathBuf,
+
+    /// The file where onie-said saves its state to for warm restarts
+    #[arg(long, default_value = "/run/onie-said/warm-boot.json")]
+    warm_boot_state_file: PathBuf,
+
+    /// Whether to automatically reinitialize SAI after the switch was torn down because of a failure or shutdown request from the ASIC
+    #[arg(long, default_value = "false", default_missing_value = "true")]
+    auto_reinit: Option<Option<bool>>,
 }
 
 static PLATFORM: OnceLock<String> = OnceLock::new();
 
+/// the MAC address that we fall back to if there is no syseeprom
+const DEFAULT_MAC_ADDR: MacAddr6 = MacAddr6::new(0xee, 0xba, 0x4a, 0xb9, 0xb1, 0x24);
+
 fn arg_platform() -> String {
     PLATFORM
         .get_or_init(|| {
@@ -103,8 +197,169 @@ fn arg_port_config_file() -> String {
     format!("/etc/platform/{}/port_config.json", arg_platform())
 }
 
+fn arg_sonic_device_dir() -> String {
+    format!("/usr/share/sonic/device/{}", arg_platform())
+}
+
+fn arg_media_settings_file() -> String {
+    format!("/etc/platform/{}/media_settings.json", arg_platform())
+}
+
+fn arg_syseeprom_file() -> String {
+    format!("/etc/platform/{}/syseeprom", arg_platform())
+}
+
+fn arg_port_mac_settings_file() -> String {
+    format!("/etc/platform/{}/port_mac.json", arg_platform())
+}
+
+fn arg_discovery_profile_file() -> String {
+    format!("/etc/platform/{}/discovery_profile.json", arg_platform())
+}
+
 impl Cli {
-    fn sai_profile(&self) -> anyhow::Result<Vec<(CString, CString)>> {
+    /// our own port config file takes precedence, and the SONiC port config is the fallback
+    fn ports_config(&self) -> Option<Vec<PhysicalPortConfig>> {
+        if self.port_config_file.exists() {
+            return PhysicalPortConfig::from_file(&self.port_config_file);
+        }
+        log::info!(
+            "no port config file at {}. Trying SONiC port config from {}...",
+            self.port_config_file.display(),
+            self.sonic_device_dir.display()
+        );
+        sonic_config::detect(&self.sonic_device_dir)
+    }
+
+    fn discovery_profile(&self) -> anyhow::Result<DiscoveryProfile> {
+        let mut ret = match DiscoveryProfile::from_file(&self.discovery_profile_file) {
+            Ok(profile) => profile,
+            Err(DiscoveryProfileError::IOError(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                log::info!(
+                    "no discovery profile at {}. Using the default discovery profile.",
+                    self.discovery_profile_file.display()
+                );
+                DiscoveryProfile::default()
+            }
+            Err(e) => {
+                return Err(e).context(format!(
+                    "failed to load discovery profile from {}",
+                    self.discovery_profile_file.display()
+                ))
+            }
+        };
+
+        // command line arguments take precedence over the profile file
+        if let Some(v) = self.discovery_poll_interval_ms {
+            ret.poll_interval_ms = v;
+        }
+        if let Some(v) = self.discovery_max_retries {
+            ret.max_retries = Some(v);
+        }
+        if let Some(v) = &self.discovery_strategies {
+            ret.strategies = v.clone();
+        }
+
+        ret.validate().context("invalid discovery profile")?;
+        log::info!("using discovery profile: {:?}", ret);
+        Ok(ret)
+    }
+
+    /// the interval of the reconciliation loop, `None` if it is disabled
+    fn reconcile_interval(&self) -> Option<Duration> {
+        match self.reconcile_interval_ms {
+            0 => None,
+            v => Some(Duration::from_millis(v)),
+        }
+    }
+
+    /// reads the ONIE syseeprom: failing to read it is not fatal as long as a MAC address is passed
+    fn syseeprom(&self) -> Option<SysEeprom> {
+        match SysEeprom::from_file(&self.syseeprom_file) {
+            Ok(v) => {
+                log::info!(
+                    "syseeprom: product name: {:?}, part number: {:?}, serial number: {:?}, platform name: {:?}, base MAC: {:?}, number of MACs: {:?}",
+                    v.product_name(),
+                    v.part_number(),
+                    v.serial_number(),
+                    v.platform_name(),
+                    v.base_mac().map(MacAddr6::from),
+                    v.num_macs()
+                );
+                Some(v)
+            }
+            Err(e) => {
+                log::warn!(
+                    "failed to read syseeprom from {}: {}",
+                    self.syseeprom_file.display(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// the MAC address from the command line takes precedence over the syseeprom
+    fn mac_addr(&self, syseeprom: Option<&SysEeprom>) -> MacAddr6 {
+        if let Some(v) = self.mac_addr {
+            return v;
+        }
+        match syseeprom.and_then(|v| v.base_mac()) {
+            Some(v) => MacAddr6::from(v),
+            None => {
+                log::warn!(
+                    "no base MAC address in syseeprom, using the default MAC address {}",
+                    DEFAULT_MAC_ADDR
+                );
+                DEFAULT_MAC_ADDR
+            }
+        }
+    }
+
+    fn port_mac_settings(&self, syseeprom: Option<&SysEeprom>) -> anyhow::Result<PortMacSettings> {
+        let mut ret = match PortMacSettings::from_file(&self.port_mac_settings_file) {
+            Ok(settings) => settings,
+            Err(PortMacSettingsError::IOError(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                log::info!(
+                    "no port MAC settings at {}. Using the default port MAC settings.",
+                    self.port_mac_settings_file.display()
+                );
+                PortMacSettings::default()
+            }
+            Err(e) => {
+                return Err(e).context(format!(
+                    "failed to load port MAC settings from {}",
+                    self.port_mac_settings_file.display()
+                ))
+            }
+        };
+
+        // command line arguments take precedence over the settings file
+        if let Some(v) = self.port_mac_mode {
+            ret.mode = v;
+        }
+        if let Some(v) = self.mac_addr_count {
+            ret.count = Some(v);
+        }
+
+        // the syseeprom knows how many MAC addresses are reserved for the device
+        if ret.count.is_none() {
+            ret.count = syseeprom.and_then(|v| v.num_macs()).map(u64::from);
+        }
+
+        ret.validate().context("invalid port MAC settings")?;
+        log::info!("using port MAC settings: {:?}", ret);
+        Ok(ret)
+    }
+
+    fn hostif_naming(&self) -> anyhow::Result<HostIfNaming> {
+        let ret = HostIfNaming::new(self.hostif_naming, self.hostif_name_template.clone())
+            .context("invalid host interface naming")?;
+        log::info!("using host interface naming: {:?}", ret);
+        Ok(ret)
+    }
+
+    fn sai_profile(&self, warm_start: bool) -> anyhow::Result<Vec<(CString, CString)>> {
         let init_config_file =
             self.init_config_file
                 .as_os_str()
@@ -113,10 +368,95 @@ impl Cli {
                     "init config file is not a valid unicode string"
                 ))?;
 
-        Ok(vec![(
+        let mut ret = vec![(
             CString::from_vec_with_nul(sai::SAI_KEY_INIT_CONFIG_FILE.to_vec())?,
             CString::new(init_config_file)?,
-        )])
+        )];
+
+        if self.warm_boot.flatten().unwrap_or_default() {
+            let warm_boot_sai_state_file = self
+                .warm_boot_sai_state_file
+                .as_os_str()
+                .to_str()
+                .ok_or(anyhow::anyhow!(
+                    "warm boot SAI state file is not a valid unicode string"
+                ))?;
+
+            // 0: cold boot, 1: warm boot
+            ret.push((
+                CString::from_vec_with_nul(sai::SAI_KEY_BOOT_TYPE.to_vec())?,
+                CString::new(if warm_start { "1" } else { "0" })?,
+            ));
+            ret.push((
+                CString::from_vec_with_nul(sai::SAI_KEY_WARM_BOOT_READ_FILE.to_vec())?,
+                CString::new(warm_boot_sai_state_file)?,
+            ));
+            ret.push((
+                CString::from_vec_with_nul(sai::SAI_KEY_WARM_BOOT_WRITE_FILE.to_vec())?,
+                CString::new(warm_boot_sai_state_file)?,
+            ));
+        }
+
+        Ok(ret)
+    }
+
+    fn warm_boot(&self) -> Option<WarmBoot> {
+        if !self.warm_boot.flatten().unwrap_or_default() {
+            return None;
+        }
+
+        // we can only warm boot if both SAI and we have saved our state on the last shutdown
+        let state = if self.warm_boot_state_file.exists() && self.warm_boot_sai_state_file.exists()
+        {
+            match WarmBootState::from_file(&self.warm_boot_state_file) {
+                Ok(state) => {
+                    log::info!(
+                        "warm boot: found state in {:?}, performing warm boot",
+                        self.warm_boot_state_file
+                    );
+                    Some(state)
+                }
+                Err(e) => {
+                    log::error!(
+                        "warm boot: failed to read state from {:?}, performing cold boot: {:?}",
+                        self.warm_boot_state_file,
+                        e
+                    );
+                    None
+                }
+            }
+        } else {
+            log::info!("warm boot: no saved state found, performing cold boot");
+            None
+        };
+
+        // the state is only valid for exactly one start, so we remove it now
+        // this makes sure that we never restore from a stale state after a crash
+        if state.is_some() {
+            if let Err(e) = std::fs::remove_file(&self.warm_boot_state_file) {
+                log::error!(
+                    "warm boot: failed to remove state file {:?}: {:?}",
+                    self.warm_boot_state_file,
+                    e
+                );
+            }
+        }
+
+        // SAI will not create the directory for its state file
+        if let Some(parent) = self.warm_boot_sai_state_file.parent() {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                log::error!(
+                    "warm boot: failed to create directory {:?} for SAI state file: {:?}",
+                    parent,
+                    e
+                );
+            }
+        }
+
+        Some(WarmBoot {
+            state_file: self.warm_boot_state_file.clone(),
+            state,
+        })
     }
 }
 
@@ -149,24 +489,51 @@ impl From<LogLevel> for LevelFilter {
     }
 }
 
+/// checks the port config that the daemon would use, and prints all problems with it
+/// NOTE: comparing it against the ports of the switch requires SAI, use `onie-saictl validate-port-config` for that
+fn check_config(cli: &Cli) -> anyhow::Result<()> {
+    let ports_config = cli
+        .ports_config()
+        .ok_or(anyhow::anyhow!("no valid port config found"))?;
+    let check = check_port_config(&ports_config);
+    for w in check.warnings.iter() {
+        println!("warning: {}", w);
+    }
+    for e in check.errors.iter() {
+        println!("error: {}", e);
+    }
+    if !check.errors.is_empty() {
+        return Err(anyhow::anyhow!(
+            "port config has {} error(s)",
+            check.errors.len()
+        ));
+    }
+    println!("port config OK: {} physical ports", ports_config.len());
+    Ok(())
+}
+
 pub fn main() -> onie_sai_common::App {
     // parse flags and initialize logger
-    // NOTE: we need to do this before we call the wrapper
-    // as it will eat help and anything else otherwise
     let cli = Cli::parse();
+    // NOTE: stdout belongs to the vendor shell while a shell session is open, so we must only log to stderr
     env_logger::builder()
         .filter_level(LevelFilter::from(cli.log_level))
+        .target(env_logger::Target::Stderr)
         .init();
 
-    // NOTE: wrapper will call app(). See below for details.
-    onie_sai_common::App(wrapper(cli))
+    onie_sai_common::App(app(cli))
 }
 
-fn app(cli: Cli, stdin_write: File, stdout_read: File) -> anyhow::Result<()> {
+fn app(cli: Cli) -> anyhow::Result<()> {
     // validation of some of the arguments
     if cli.platform.is_empty() {
         return Err(anyhow::anyhow!("no platform detected"));
     }
+    let hostif_naming = cli.hostif_naming()?;
+
+    if cli.check_config {
+        return check_config(&cli);
+    }
 
     // initialize signal handling
     let (ctrlc_tx, ctrlc_rx) = channel();
@@ -225,40 +592,32 @@ fn app(cli: Cli, stdin_write: File, stdout_read: File) -> anyhow::Result<()> {
     };
 
     // try to read our port config file
-    let ports_config = PhysicalPortConfig::from_file(&cli.port_config_file);
+    let ports_config = cli.ports_config();
+
+    // try to read the media settings file for serdes tuning
+    let media_settings = MediaSettings::from_file(&cli.media_settings_file);
+
+    // load the discovery profile for the port auto discovery
+    let discovery_profile = cli.discovery_profile()?;
+
+    // the MAC addresses come from the syseeprom unless they are overridden
+    let syseeprom = cli.syseeprom();
+    let mac_addr = cli.mac_addr(syseeprom.as_ref());
+    log::info!("using MAC address {}", mac_addr);
+    let port_mac_settings = cli.port_mac_settings(syseeprom.as_ref())?;
 
     // get SAI API version
     if let Ok(version) = SAI::api_version() {
         log::info!("SAI version: {}", version);
     }
 
-    // construct our profile from the CLI arguments and initialize SAI
-    let profile = cli.sai_profile()?;
-    let sai_api = SAI::new(profile).context("failed to initialize SAI")?;
-    log::info!("successfully initialized SAI");
-
-    if let Err(e) = SAI::log_set_all(sai::LogLevel::Info) {
-        log::error!("failed to set log level for all APIs: {:?}", e);
-    }
-
-    // this initializes the switch, and prepares the system for receiving processing requests either from RPC, or the other threads
-    let proc = Processor::new(
-        &sai_api,
-        cli.mac_addr.into_array(),
-        ports_config,
-        cli.auto_discovery.flatten().unwrap_or_default(),
-        cli.auto_discovery_with_breakout
-            .flatten()
-            .unwrap_or_default(),
-        platform_ctx,
-        stdin_write,
-        stdout_read,
-    )
-    .context("failed to initialize ONIE SAI processor")?;
+    // the processor channel: it is shared by all processors that we create
+    // so that all the threads below survive a reinitialization of SAI
+    let (proc_tx, proc_rx) = channel();
 
     // move the signal handling to its own thread
     // send a shutdown request to the processor when we receive it
-    let ctrlc_proc_tx = proc.get_sender();
+    let ctrlc_proc_tx = proc_tx.clone();
     thread::spawn(move || {
         log::info!("ONIE SAI daemon started. Waiting for termination signal...");
         ctrlc_rx
@@ -275,300 +634,115 @@ fn app(cli: Cli, stdin_write: File, stdout_read: File) -> anyhow::Result<()> {
     });
 
     // initialize the ttrpc server
-    let rpc_server = rpc::start_rpc_server(proc.get_sender())?;
+    let rpc_server = rpc::start_rpc_server(proc_tx.clone())?;
 
     // initialize netlink address monitor
-    let _nl_monitor = netlink::netlink_addr_monitor(proc.get_sender())?;
+    let _nl_monitor = netlink::netlink_addr_monitor(proc_tx.clone())?;
+
+    // initialize netlink link monitor
+    let _nl_link_monitor = netlink::netlink_link_monitor(proc_tx.clone())?;
+
+    // initialize netlink neighbor and route monitor if route sync is enabled
+    let _nl_route_monitor = match cli.route_sync.flatten().unwrap_or_default() {
+        true => Some(netlink::netlink_route_monitor(proc_tx.clone())?),
+        false => None,
+    };
 
     // initialize auto discovery poll loop
-    let auto_discovery_proc_tx = proc.get_sender();
+    // the poll interval comes from the discovery profile
+    let auto_discovery_proc_tx = proc_tx.clone();
+    let auto_discovery_poll_interval = discovery_profile.poll_interval();
     thread::spawn(move || loop {
-        // We are going to poll every second
-        // NOTE: this might be too aggressive, we need to look at this again
-        thread::sleep(Duration::from_secs(1));
+        thread::sleep(auto_discovery_poll_interval);
         if let Err(e) = auto_discovery_proc_tx.send(processor::ProcessRequest::AutoDiscoveryPoll) {
             log::error!("failed to send auto discovery poll request: {:?}. Aborting auto discovery poll thread.", e);
             return;
         }
     });
 
-    // this blocks until processing is all done
-    // process consumes the processor, so it will be dropped immediately after
-    // which will trigger the cleanup
-    proc.process();
-
-    // stop ttrpc server as well
-    rpc_server.shutdown();
-
-    log::info!("Success");
-    Ok(())
-}
-
-/// This is the indicator that we are in the client process.
-const CLIENT_PROCESS_INDICATOR: &str =
-    "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
-const PIPE_STDIN_WRITE: &str = "PIPE_STDIN_WRITE";
-const PIPE_STDOUT_READ: &str = "PIPE_STDOUT_READ";
-
-/// The whole purpose of the wrapper is to spawn a child process which runs the actual
-/// application. We do this so that we can send and read from the stdin and stdout of the
-/// process. And we require to do this so that we are able to forward the vendor shell
-/// input and output to the connecting client application through which we want to access
-/// it.
-/// Essentially we are creating additional pipes for stdin and stdout, so that we can
-/// forward the other sides of the pipes to the child process, so that we can effectively
-/// pass back the stdin and stdout of the process. The only reason why we need to do this
-/// is because Linux does not allow write access to stdin and read access from stdout for
-/// its own process.
-/// Yes, this is extremely stupid, and a lot of effort for a debugging tool. Nonetheless,
-/// we would be lost if we don't have access to this shell. And leaving it within the
-/// server application is not acceptable as it would mean that we need to stop and restart
-/// the application every time we want to access the shell. And that might destroy the
-/// state that we want to debug.
-fn wrapper(cli: Cli) -> anyhow::Result<()> {
-    // First of all, check if we are in the client process.
-    // If we are, we read the environment variables which must be set, and we switch to the
-    // actual application then
-    if env::var(CLIENT_PROCESS_INDICATOR).is_ok() {
-        let stdin_write_fd: i32 = env::var(PIPE_STDIN_WRITE)
-            .context("PIPE_STDIN_WRITE not set")?
-            .parse()
-            .context("PIPE_STDIN_WRITE contains and invalid fd (not a number)")?;
-        let stdin_write = unsafe { File::from_raw_fd(stdin_write_fd) };
-        let stdout_read_fd: i32 = env::var(PIPE_STDOUT_READ)
-            .context("PIPE_STDOUT_READ not set")?
-            .parse()
-            .context("PIPE_STDOUT_READ is an invalid fd")?;
-        let stdout_read = unsafe { File::from_raw_fd(stdout_read_fd) };
-        return app(cli, stdin_write, stdout_read);
-    }
-
-    // Get the current program's arguments and environment variables
-    let args: Vec<String> = env::args().collect();
-    let mut env_vars: Vec<(String, String)> = env::vars().collect();
-
-    // Add the indicator environment variable to the environment
-    // this is how the child process knows that it needs to start the real application
-    env_vars.push((
-        CLIENT_PROCESS_INDICATOR.to_string(),
-        std::process::id().to_string(),
-    ));
-
-    // Create the pipe for stdin now
-    // - we push the write end of the pipe to the client process, so that the child can write to its own stdin
-    // - we keep the read end of the pipe for ourselves, so that we can write back to the stdin of the child
-    let mut fds = vec![0i32; 2];
-    let ret = unsafe { libc::pipe(fds.as_mut_ptr()) };
-    if ret < 0 {
-        return Err(anyhow::anyhow!(
-            "wrapper: failed to create dedicate pipe for stdin pump: {}",
-            std::io::Error::last_os_error()
-        ));
-    }
-    let ret = unsafe { libc::fcntl(fds[0], libc::F_SETFL, libc::O_NONBLOCK) };
-    if ret < 0 {
-        return Err(anyhow::anyhow!(
-            "wrapper: failed to set dedicated pipe fd[0] for stdin to non-blocking: {}",
-            std::io::Error::last_os_error()
-        ));
-    }
-    let ret = unsafe { libc::fcntl(fds[1], libc::F_SETFL, libc::O_NONBLOCK) };
-    if ret < 0 {
-        return Err(anyhow::anyhow!(
-            "wrapper: failed to set dedicated pipe fd[1] for stdin to non-blocking: {}",
-            std::io::Error::last_os_error()
-        ));
-    }
-    let mut pipe_stdin_read = unsafe { File::from_raw_fd(fds[0]) };
-    env_vars.push((PIPE_STDIN_WRITE.to_string(), fds[1].to_string()));
-
-    // Create the pipe for stdout now
-    // - we push the read end of the pipe to the client process, so that the child can read from its own stdout
-    // - we keep the write end of the pipe for ourselves, so that we can read from the stdout of the child, and write it back over the pipe
-    let mut fds = vec![0i32; 2];
-    let ret = unsafe { libc::pipe(fds.as_mut_ptr()) };
-    if ret < 0 {
-        return Err(anyhow::anyhow!(
-            "wrapper: failed to create dedicate pipe for stdin pump: {}",
-            std::io::Error::last_os_error()
-        ));
-    }
-    let ret = unsafe { libc::fcntl(fds[0], libc::F_SETFL, libc::O_NONBLOCK) };
-    if ret < 0 {
-        return Err(anyhow::anyhow!(
-            "wrapper: failed to set dedicated pipe fd[0] for stdout to non-blocking: {}",
-            std::io::Error::last_os_error()
-        ));
-    }
-    let ret = unsafe { libc::fcntl(fds[1], libc::F_SETFL, libc::O_NONBLOCK) };
-    if ret < 0 {
-        return Err(anyhow::anyhow!(
-            "wrapper: failed to set dedicated pipe fd[1] for stdout to non-blocking: {}",
-            std::io::Error::last_os_error()
-        ));
-    }
-    env_vars.push((PIPE_STDOUT_READ.to_string(), fds[0].to_string()));
-    let mut pipe_stdout_write = unsafe { File::from_raw_fd(fds[1]) };
-
-    // We spawn a new process with the same executable, arguments, and our modified environment variables now
-    // NOTE: it is extremely important that we inherit stderr because all of our logging is done through it
-    let mut child_process = std::process::Command::new(&args[0])
-        .args(&args[1..])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::inherit())
-        .envs(env_vars)
-        .spawn()
-        .context("wrapper: failed to spawn child process")?;
-
-    // get the stdin and stdout handles for the child process
-    // we will use them in our data pumping threads below
-    let mut child_stdin = child_process
-        .stdin
-        .take()
-        .context("wrapper: failed to open stdin of child process")?;
-    let child_stdin_fd = child_stdin.as_raw_fd();
-    let ret = unsafe { libc::fcntl(child_stdin_fd, libc::F_SETFL, libc::O_NONBLOCK) };
-    if ret < 0 {
-        return Err(anyhow::anyhow!(
-            "wrapper: failed to set child stdin fd to non-blocking: {}",
-            std::io::Error::last_os_error()
-        ));
-    }
-    let mut child_stdout = child_process
-        .stdout
-        .take()
-        .context("wrapper: failed to open stdout of child process")?;
-    let child_stdout_fd = child_stdout.as_raw_fd();
-    let ret = unsafe { libc::fcntl(child_stdout_fd, libc::F_SETFL, libc::O_NONBLOCK) };
-    if ret < 0 {
-        return Err(anyhow::anyhow!(
-            "wrapper: failed to set child stdout fd to non-blocking: {}",
-            std::io::Error::last_os_error()
-        ));
-    }
-
-    // this thread will read from the stdout of the child process and write it back to the child process
-    // on the dedicated pipe, where the child can read it from the fd which is in PIPE_STDOUT_READ of its process
-    let (tx, rx) = std::sync::mpsc::channel::<bool>();
-    thread::spawn(move || {
-        let mut buf = [0u8; 1024];
-        let mut send_to_child = false;
-        loop {
-            // now check if we need to flip to send to the child
-            // because the shell was enabled or vice versa
-            if let Ok(shell_enable) = rx.try_recv() {
-                send_to_child = shell_enable;
-                log::debug!("wrapper: stdout thread: shell_enable {}", shell_enable);
-            }
-
-            let n = match child_stdout.read(&mut buf) {
-                Ok(n) => n,
-                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
-                    // Non-blocking mode, no data available yet
-                    // NOTE: I believe child_stdout will always be a blocking read, so this is useless
-                    thread::sleep(Duration::from_millis(10));
-                    continue;
-                }
-                Err(e) => {
-                    log::error!(
-                        "wrapper: failed to read from stdout of child process: {:?}",
-                        e
-                    );
-                    continue;
-                }
-            };
-            if n == 0 {
-                // EOF, end thread
-                break;
+    // initialize reconciliation loop
+    let reconcile_interval = cli.reconcile_interval();
+    if let Some(interval) = reconcile_interval {
+        let reconcile_proc_tx = proc_tx.clone();
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+            if let Err(e) = reconcile_proc_tx.send(processor::ProcessRequest::ReconcilePoll) {
+                log::error!("failed to send reconcile poll request: {:?}. Aborting reconciliation loop thread.", e);
+                return;
             }
+        });
+    }
 
-            if send_to_child {
-                if let Err(e) = pipe_stdout_write.write_all(&buf[..n]) {
-                    log::error!(
-                        "wrapper: failed to write to dedicated pipe for stdout: {:?}",
-                        e
-                    );
-                    continue;
-                }
-            } else {
-                let mut stdout = std::io::stdout().lock();
-                let _ = stdout.write_all(&buf[..n]);
-                let _ = stdout.flush();
-            }
+    // check if we are going to warm boot
+    let mut warm_boot = cli.warm_boot();
+    let auto_reinit = cli.auto_reinit.flatten().unwrap_or_default();
+    let mut reinit_count = 0u32;
+    loop {
+        let warm_start = warm_boot
+            .as_ref()
+            .map(|wb| wb.state.is_some())
+            .unwrap_or_default();
+
+        // construct our profile from the CLI arguments and initialize SAI
+        let profile = cli.sai_profile(warm_start)?;
+        let sai_api = SAI::new(profile).context("failed to initialize SAI")?;
+        log::info!("successfully initialized SAI");
+
+        if let Err(e) = SAI::log_set_all(sai::LogLevel::Info) {
+            log::error!("failed to set log level for all APIs: {:?}", e);
         }
-    });
-
-    // this thread will read on the read end of the dedicated pipe for the child process where the child
-    // can write to its own stdin and it will write it to the stdin of the child process
-    thread::spawn(move || {
-        let mut buf = [0u8; 1024];
-        loop {
-            let n = match pipe_stdin_read.read(&mut buf) {
-                Ok(n) => n,
-                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
-                    // Non-blocking mode, no data available yet
-                    thread::sleep(Duration::from_millis(10));
-                    continue;
-                }
-                Err(e) => {
-                    log::error!(
-                        "wrapper: failed to read from dedicated pipe for stdin: {:?}",
-                        e
-                    );
-                    continue;
-                }
-            };
-            if n == 0 {
-                // EOF, end thread
-                break;
-            }
 
-            // check if we got the shell enable markers
-            if &buf[..n] == b"SAI_SHELL_ENABLE".as_slice() {
-                // we received the shell enable command, send it to the other thread
-                log::debug!("shell: stdin thread: SAI_SHELL_ENABLE received");
-                if let Err(e) = tx.send(true) {
-                    log::error!(
-                        "wrapper: failed to send shell enable command to stdout thread: {:?}",
-                        e
-                    );
-                }
-                continue;
-            } else if &buf[..n] == b"SAI_SHELL_DISABLE".as_slice() {
-                // we received the shell disable command, send it to the other thread
-                log::debug!("shell: stdin thread: SAI_SHELL_DISABLE received");
-                if let Err(e) = tx.send(false) {
-                    log::error!(
-                        "wrapper: failed to send shell disable command to other thread: {:?}",
-                        e
-                    );
-                }
-                continue;
-            }
+        // the warm boot state is only valid for the first initialization
+        let proc_warm_boot = warm_boot.clone();
+        if let Some(wb) = warm_boot.as_mut() {
+            wb.state = None;
+        }
 
-            // apart from those markers we send everything to the child process
-            if let Err(e) = child_stdin.write_all(&buf[..n]) {
-                log::error!(
-                    "wrapper: failed to write to stdin of child process: {:?}",
-                    e
+        // this initializes the switch, and prepares the system for receiving processing requests either from RPC, or the other threads
+        let proc = Processor::new(
+            &sai_api,
+            mac_addr.into_array(),
+            port_mac_settings.clone(),
+            ports_config.clone(),
+            media_settings.clone(),
+            discovery_profile.clone(),
+            hostif_naming.clone(),
+            cli.auto_discovery.flatten().unwrap_or_default(),
+            cli.auto_discovery_with_breakout
+                .flatten()
+                .unwrap_or_default(),
+            cli.lldp_apply_network_config.flatten().unwrap_or_default(),
+            cli.route_sync.flatten().unwrap_or_default(),
+            reconcile_interval,
+            platform_ctx.clone(),
+            proc_warm_boot,
+            proc_tx.clone(),
+        )
+        .context("failed to initialize ONIE SAI processor")?;
+
+        // this blocks until processing is all done
+        // process consumes the processor, so it will be dropped immediately after
+        // which will trigger the cleanup
+        match proc.process(&proc_rx, auto_reinit) {
+            ProcessExit::Shutdown => break,
+            ProcessExit::Reinit => {
+                reinit_count += 1;
+                log::warn!(
+                    "switch was torn down, reinitializing SAI (reinitialization count: {})...",
+                    reinit_count
                 );
-                continue;
             }
         }
-    });
 
-    // Check if the process was created successfully
-    // Wait for the child process to complete
-    let status = child_process
-        .wait()
-        .context("wrapper: child process did not run")?;
-    if !status.success() {
-        return Err(anyhow::anyhow!(
-            "wrapper: child process did not exit successfully: exit status: {:?}",
-            status.code()
-        ));
+        // SAI gets uninitialized here when it goes out of scope
+        // NOTE: give the ASIC a moment before we try to initialize it again
+        drop(sai_api);
+        thread::sleep(Duration::from_secs(5));
     }
 
+    // stop ttrpc server as well
+    rpc_server.shutdown();
+
+    log::info!("Success");
     Ok(())
 }
@@ -2,6 +2,7 @@ use std::io::Cursor;
 use std::io::Read;
 use std::net::IpAddr;
 use std::str::FromStr;
+use std::time::Duration;
 
 use etherparse::ReadError;
 use ipnet::IpNet;
@@ -140,6 +141,30 @@ impl LLDPSocket {
         }
     }
 
+    /// sets how long `recv_packet()` blocks at most before it fails with `ErrorKind::WouldBlock`,
+    /// `None` blocks forever
+    pub fn set_recv_timeout(&self, timeout: Option<Duration>) -> Result<(), std::io::Error> {
+        let timeout = timeout.unwrap_or_default();
+        let opt = libc::timeval {
+            tv_sec: timeout.as_secs() as libc::time_t,
+            tv_usec: timeout.subsec_micros() as libc::suseconds_t,
+        };
+        let opt_ptr = &opt as *const libc::timeval as *const libc::c_void;
+        let ret = unsafe {
+            libc::setsockopt(
+                self.sockfd,
+                libc::SOL_SOCKET,
+                libc::SO_RCVTIMEO,
+                opt_ptr,
+                std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+            )
+        };
+        if ret < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
     pub fn recv_packet(&self) -> Result<Vec<u8>, std::io::Error> {
         let mut buffer = [0u8; 65536];
         let ret = unsafe {
@@ -168,6 +193,7 @@ const IANA_ADDRESS_FAMILY_NUMBER_IP: u8 = 1; // IP (IP version 4)
 #[allow(dead_code)]
 const IANA_ADDRESS_FAMILY_NUMBER_IP6: u8 = 2; // IP6 (IP version 6)
 
+const LLDP_TLV_TYPE_TTL: u8 = 3;
 const LLDP_TLV_TYPE_SYSTEM_NAME: u8 = 5;
 const LLDP_TLV_TYPE_SYSTEM_DESCRIPTION: u8 = 6;
 const LLDP_TLV_TYPE_MGMT_ADDRESS: u8 = 8;
@@ -681,6 +707,16 @@ impl LLDPTLVs {
         Self(parse_lldp(data))
     }
 
+    /// the number of seconds for which the information of the neighbor is valid,
+    /// 0 means that the neighbor is shutting down
+    pub fn get_ttl(&self) -> Option<u16> {
+        self.0
+            .iter()
+            .find(|tlv| tlv.typ == LLDP_TLV_TYPE_TTL)
+            .and_then(|tlv| <[u8; 2]>::try_from(tlv.value.as_slice()).ok())
+            .map(u16::from_be_bytes)
+    }
+
     pub fn to_strings(&self) -> Vec<String> {
         let mut ret = Vec::new();
         for tlv in self.0.iter() {
@@ -1142,4 +1178,22 @@ mod tests {
         assert!(found_system_name);
         assert!(found_mud_url);
     }
+
+    #[test]
+    fn test_ttl() {
+        for (pkt, ttl) in [
+            (&PACKET_SWITCH_1[..], 120),
+            (&PACKET_SWITCH_2[..], 20),
+            (&PACKET_CONTROL_1[..], 120),
+        ] {
+            let tlvs = LLDPTLVs::try_from(pkt).unwrap();
+            assert_eq!(tlvs.get_ttl(), Some(ttl));
+        }
+
+        // a TTL TLV with a wrong length is ignored
+        let tlvs = LLDPTLVs::parse_lldp(&[0x06, 0x01, 0x00]);
+        assert_eq!(tlvs.get_ttl(), None);
+        let tlvs = LLDPTLVs::parse_lldp(&[0x06, 0x02, 0x00, 0x00]);
+        assert_eq!(tlvs.get_ttl(), Some(0));
+    }
 }
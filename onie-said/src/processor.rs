@@ -1,23 +1,25 @@
 pub(crate) mod netlink;
 pub(crate) mod port;
+pub(crate) mod reconcile;
+pub(crate) mod shell;
+pub(crate) mod warm_boot;
 
 use std::fs::File;
-use std::io::ErrorKind;
-use std::io::Read;
 use std::io::Write;
 use std::net::IpAddr;
 use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::str::FromStr;
-use std::sync::mpsc;
-use std::sync::mpsc::channel;
 use std::sync::mpsc::Receiver;
 use std::sync::mpsc::Sender;
-use std::thread;
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::anyhow;
 use ipnet::IpNet;
+use onie_sai_common::port_config::check::check_port_config;
+use onie_sai_common::port_config::check::compare_port_config;
 use onie_sai_rpc::onie_sai;
 use onie_sai_rpc::wrap_message_field;
 use sai::bridge;
@@ -29,16 +31,21 @@ use sai::hostif::trap::TrapType;
 use sai::hostif::HostIf;
 use sai::hostif::HostIfAttribute;
 use sai::hostif::HostIfType;
+use sai::port::BreakoutModeType;
+use sai::port::FECMode;
 use sai::port::OperStatus;
 use sai::port::PortID;
+use sai::route::NextHopID;
 use sai::route::RouteEntry;
 use sai::route::RouteEntryAttribute;
 use sai::router_interface::RouterInterfaceAttribute;
 use sai::router_interface::RouterInterfaceType;
 use sai::switch::Switch;
 use sai::switch::SwitchAttribute;
+use sai::switch::SwitchOperStatus;
 use sai::ObjectID;
 use sai::PacketAction;
+use sai::Status;
 use sai::SAI;
 
 use anyhow::Context;
@@ -52,8 +59,22 @@ use crate::lldp::NetworkConfig;
 use crate::processor::port::SortPortsByLanes;
 
 use self::port::discovery::logicalport::Event::PortUp;
+use self::port::discovery::profile::DiscoveryProfile;
+use self::port::hostif_naming::HostIfNaming;
+use self::port::mac::PortMacSettings;
+use self::port::media_settings::MediaSettings;
+use self::port::LogicalPortSettings;
 use self::port::PhysicalPort;
 use self::port::PhysicalPortConfig;
+use self::port::PortError;
+use self::reconcile::Drift;
+use self::reconcile::ReconcileStats;
+use self::shell::ShellError;
+use self::shell::ShellMode;
+use self::shell::ShellSession;
+use self::warm_boot::PhysicalPortState;
+use self::warm_boot::WarmBoot;
+use self::warm_boot::WarmBootState;
 
 #[derive(Clone)]
 pub(crate) struct PlatformContextHolder<'a> {
@@ -85,11 +106,35 @@ pub(crate) enum ProcessError {
     #[error("Shell Command IO Error")]
     ShellIOError(anyhow::Error),
 
+    #[error("shell is busy: shell session {0} is still active")]
+    ShellBusy(u64),
+
+    #[error("no active shell session with ID {0}")]
+    NoSuchShellSession(u64),
+
+    #[error("shell is unavailable: the switch has been torn down")]
+    ShellUnavailable,
+
+    #[error("SAI Dump IO Error")]
+    SAIDumpIOError(anyhow::Error),
+
     #[error("failed to get interface: {0}")]
     GetInterfaceError(std::io::Error),
 
     #[error("failed to get interface index for '{0}'")]
     NoSuchInterfaceError(String),
+
+    #[error("no physical port with index {0}")]
+    NoSuchPort(u32),
+
+    #[error("auto discovery is disabled for physical port {0}")]
+    AutoDiscoveryDisabled(u32),
+
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+
+    #[error("port operation failed: {0}")]
+    PortError(#[from] PortError),
 }
 
 pub(crate) enum ProcessRequest {
@@ -134,8 +179,14 @@ pub(crate) enum ProcessRequest {
     LogicalPortStateChange((PortID, OperStatus)),
     NetlinkAddrAdded((u32, IpAddr)),
     NetlinkAddrRemoved((u32, IpAddr)),
+    NetlinkLinkChanged(u32),
+    NetlinkLinkRemoved(u32),
+    NetlinkNeighborChanged(netlink::Neighbor),
+    NetlinkRouteChanged(netlink::Route),
+    NetlinkRouteRemoved(netlink::Route),
     LLDPTLVsReceived((u32, LLDPTLVs)),
     LLDPNetworkConfigReceived((u32, NetworkConfig)),
+    LLDPNeighborExpired(u32),
     LLDPStatus(
         (
             onie_sai::LLDPStatusRequest,
@@ -148,35 +199,121 @@ pub(crate) enum ProcessRequest {
             Sender<Result<onie_sai::LLDPNetworkConfigResponse, ProcessError>>,
         ),
     ),
+    SwitchStateChange(SwitchOperStatus),
+    SwitchShutdownRequest,
+    SwitchStatus(
+        (
+            onie_sai::SwitchStatusRequest,
+            Sender<Result<onie_sai::SwitchStatusResponse, ProcessError>>,
+        ),
+    ),
+    ReconcilePoll,
+    ReconcileStatus(
+        (
+            onie_sai::ReconcileStatusRequest,
+            Sender<Result<onie_sai::ReconcileStatusResponse, ProcessError>>,
+        ),
+    ),
+    DiscoveryProfile(
+        (
+            onie_sai::DiscoveryProfileRequest,
+            Sender<Result<onie_sai::DiscoveryProfileResponse, ProcessError>>,
+        ),
+    ),
+    ValidatePortConfig(
+        (
+            onie_sai::ValidatePortConfigRequest,
+            Sender<Result<onie_sai::ValidatePortConfigResponse, ProcessError>>,
+        ),
+    ),
+    Rediscover(
+        (
+            onie_sai::RediscoverRequest,
+            Sender<Result<onie_sai::RediscoverResponse, ProcessError>>,
+        ),
+    ),
+    SetPort(
+        (
+            onie_sai::SetPortRequest,
+            Sender<Result<onie_sai::SetPortResponse, ProcessError>>,
+        ),
+    ),
+    ShellExec(
+        (
+            onie_sai::ShellExecRequest,
+            Sender<Result<onie_sai::ShellExecResponse, ProcessError>>,
+        ),
+    ),
+    ShellResize(
+        (
+            onie_sai::ShellResizeRequest,
+            Sender<Result<onie_sai::ShellResizeResponse, ProcessError>>,
+        ),
+    ),
+    SAIDump(
+        (
+            onie_sai::SAIDumpRequest,
+            Sender<Result<onie_sai::SAIDumpResponse, ProcessError>>,
+        ),
+    ),
+}
+
+/// the reason why the processor stopped processing requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProcessExit {
+    /// a regular shutdown was requested
+    Shutdown,
+    /// the switch was torn down, and SAI needs to be reinitialized
+    Reinit,
 }
 
 pub(crate) struct Processor<'a, 'b> {
     auto_discovery: bool,
     auto_discovery_with_breakout: bool,
+    lldp_apply_network_config: bool,
+    /// if the kernel neighbors and routes of the host interfaces are synced to SAI
+    route_sync: bool,
+    reconcile_interval: Option<Duration>,
+    reconcile_stats: ReconcileStats,
     switch: Switch<'a>,
     virtual_router: VirtualRouter<'a>,
     routes: Vec<RouteEntry<'a>>,
+    /// route entries which we failed to remove: the reconciliation loop retries to remove them
+    stale_routes: Vec<RouteEntry<'a>>,
     cpu_port_id: PortID,
     cpu_hostif: HostIf<'a>,
     ports: Vec<PhysicalPort<'a, 'b>>,
-    rx: Receiver<ProcessRequest>,
+    discovery_profile: Arc<DiscoveryProfile>,
+    warm_boot_state_file: Option<PathBuf>,
+    switch_oper_status: SwitchOperStatus,
+    switch_shutdown_requested: bool,
+    torn_down: bool,
     tx: Sender<ProcessRequest>,
-    stdin_write: File,
-    stdout_read: File,
+    shell_session: Option<ShellSession<'a>>,
+    shell_session_counter: u64,
 }
 
 impl<'a, 'b> Processor<'a, 'b> {
     pub(crate) fn new(
         sai_api: &'a SAI,
         mac_address: sai_mac_t,
+        mac_settings: PortMacSettings,
         ports_config: Option<Vec<PhysicalPortConfig>>,
+        media_settings: Option<MediaSettings>,
+        discovery_profile: DiscoveryProfile,
+        hostif_naming: HostIfNaming,
         auto_discovery: bool,
         auto_discovery_with_breakout: bool,
+        lldp_apply_network_config: bool,
+        route_sync: bool,
+        reconcile_interval: Option<Duration>,
         platform_ctx: PlatformContextHolder<'b>,
-        stdin_write: File,
-        stdout_read: File,
+        warm_boot: Option<WarmBoot>,
+        tx: Sender<ProcessRequest>,
     ) -> anyhow::Result<Self> {
         // now create switch
+        // NOTE: on a warm boot, SAI restores the switch with all its objects
+        // because of the boot type in the profile
         let switch: Switch<'a> = sai_api
             .switch_create(vec![
                 SwitchAttribute::InitSwitch(true),
@@ -185,9 +322,9 @@ impl<'a, 'b> Processor<'a, 'b> {
             .context("failed to create switch")?;
         log::info!("successfully created switch: {:?}", switch);
 
-        // the processor channel
-        let (tx, rx) = channel();
         let psc_cb_tx = tx.clone();
+        let ssc_cb_tx = tx.clone();
+        let ssr_cb_tx = tx.clone();
 
         // port state change callback
         switch
@@ -207,6 +344,59 @@ impl<'a, 'b> Processor<'a, 'b> {
             }))
             .context("failed to set port state change callback")?;
 
+        // switch state change and shutdown request callbacks
+        // NOTE: not every SAI implementation supports them, so we don't fail if we cannot set them
+        if let Err(e) = switch.set_switch_state_change_callback(Box::new(
+            move |switch_id, switch_oper_status| {
+                let oper_status = SwitchOperStatus::from(switch_oper_status);
+                log::warn!(
+                    "Switch State Change Event: switch_id = {:#x}, oper_status = {}",
+                    switch_id,
+                    oper_status
+                );
+                if let Err(e) = ssc_cb_tx.send(ProcessRequest::SwitchStateChange(oper_status)) {
+                    log::error!("Switch State Change Event: failed to submit switch state change event to processor (switch_id = {:#x}, oper_status = {}): {}", switch_id, oper_status, e);
+                }
+            },
+        )) {
+            log::warn!("failed to set switch state change callback: {:?}", e);
+        }
+        if let Err(e) = switch.set_switch_shutdown_request_callback(Box::new(move |switch_id| {
+            log::warn!("Switch Shutdown Request Event: switch_id = {:#x}", switch_id);
+            if let Err(e) = ssr_cb_tx.send(ProcessRequest::SwitchShutdownRequest) {
+                log::error!("Switch Shutdown Request Event: failed to submit switch shutdown request to processor (switch_id = {:#x}): {}", switch_id, e);
+            }
+        })) {
+            log::warn!("failed to set switch shutdown request callback: {:?}", e);
+        }
+
+        // the discovery profile and the host interface naming are shared by all physical ports
+        let discovery_profile = Arc::new(discovery_profile);
+        let hostif_naming = Arc::new(hostif_naming);
+        let mac_settings = Arc::new(mac_settings);
+
+        // on a warm boot all the objects that we would create below exist already
+        // so we need to restore our state from the switch and the warm boot state instead
+        let warm_boot_state_file = warm_boot.as_ref().map(|wb| wb.state_file.clone());
+        if let Some(state) = warm_boot.and_then(|wb| wb.state) {
+            return Self::new_from_warm_boot_state(
+                switch,
+                mac_address,
+                mac_settings,
+                ports_config,
+                media_settings,
+                discovery_profile,
+                hostif_naming,
+                lldp_apply_network_config,
+                route_sync,
+                reconcile_interval,
+                platform_ctx,
+                state,
+                warm_boot_state_file,
+                tx,
+            );
+        }
+
         // remove default bridge ports
         let default_bridge = switch
             .get_default_bridge()
@@ -433,11 +623,17 @@ impl<'a, 'b> Processor<'a, 'b> {
             default_virtual_router
         );
 
+        // the media settings are shared by all physical ports
+        let media_settings = media_settings.map(Arc::new);
+
         // get ports now
         let ports = switch
             .get_ports()
             .context(format!("failed to get port list from switch {}", switch))?;
 
+        // every lane gets its own MAC address slot in per-port MAC mode
+        // so the slots of a physical port start after the lanes of all previous ports
+        let mut mac_slot = 0u64;
         let mut ports = match ports_config {
             None => {
                 // create the ports without port config
@@ -446,15 +642,24 @@ impl<'a, 'b> Processor<'a, 'b> {
                     .into_iter()
                     .enumerate()
                     .map(|(i, port)| {
-                        PhysicalPort::from_port(
+                        let ret = PhysicalPort::from_port(
                             platform_ctx.clone(),
                             switch.clone(),
                             default_virtual_router.clone(),
                             mac_address,
+                            mac_settings.clone(),
+                            mac_slot,
                             i,
                             port,
                             None,
-                        )
+                            media_settings.clone(),
+                            discovery_profile.clone(),
+                            hostif_naming.clone(),
+                        );
+                        if let Ok(p) = &ret {
+                            mac_slot += p.lanes.len() as u64;
+                        }
+                        ret
                     })
                     .scan(&mut err, until_err)
                     .collect::<Vec<PhysicalPort>>();
@@ -474,15 +679,24 @@ impl<'a, 'b> Processor<'a, 'b> {
                     .zip(ports_config.into_iter())
                     .enumerate()
                     .map(|(i, (port, port_config))| {
-                        PhysicalPort::from_port(
+                        let ret = PhysicalPort::from_port(
                             platform_ctx.clone(),
                             switch.clone(),
                             default_virtual_router.clone(),
                             mac_address,
+                            mac_settings.clone(),
+                            mac_slot,
                             i,
                             port,
                             Some(port_config),
-                        )
+                            media_settings.clone(),
+                            discovery_profile.clone(),
+                            hostif_naming.clone(),
+                        );
+                        if let Ok(p) = &ret {
+                            mac_slot += p.lanes.len() as u64;
+                        }
+                        ret
                     })
                     .scan(&mut err, until_err)
                     .collect();
@@ -494,6 +708,10 @@ impl<'a, 'b> Processor<'a, 'b> {
         // if auto-discovery is enabled on startup (the default), we are going to start it now
         if auto_discovery {
             for port in ports.iter_mut() {
+                // ports which are pinned by the port config stay as they are
+                if !port.discovery_allowed() {
+                    continue;
+                }
                 port.enable_auto_discovery(auto_discovery_with_breakout);
 
                 // we also need to deal with the initial discovery process wait thingy
@@ -509,16 +727,169 @@ impl<'a, 'b> Processor<'a, 'b> {
         Ok(Processor {
             auto_discovery: auto_discovery,
             auto_discovery_with_breakout: auto_discovery_with_breakout,
+            lldp_apply_network_config: lldp_apply_network_config,
+            route_sync: route_sync,
+            reconcile_interval: reconcile_interval,
+            reconcile_stats: ReconcileStats::default(),
             switch: switch,
             virtual_router: default_virtual_router,
             routes: Vec::new(),
+            stale_routes: Vec::new(),
+            cpu_port_id: cpu_port_id,
+            cpu_hostif: cpu_intf,
+            ports: ports,
+            discovery_profile: discovery_profile,
+            warm_boot_state_file: warm_boot_state_file,
+            switch_oper_status: SwitchOperStatus::Up,
+            switch_shutdown_requested: false,
+            torn_down: false,
+            tx: tx,
+            shell_session: None,
+            shell_session_counter: 0,
+        })
+    }
+
+    fn new_from_warm_boot_state(
+        switch: Switch<'a>,
+        mac_address: sai_mac_t,
+        mac_settings: Arc<PortMacSettings>,
+        ports_config: Option<Vec<PhysicalPortConfig>>,
+        media_settings: Option<MediaSettings>,
+        discovery_profile: Arc<DiscoveryProfile>,
+        hostif_naming: Arc<HostIfNaming>,
+        lldp_apply_network_config: bool,
+        route_sync: bool,
+        reconcile_interval: Option<Duration>,
+        platform_ctx: PlatformContextHolder<'b>,
+        state: WarmBootState,
+        warm_boot_state_file: Option<PathBuf>,
+        tx: Sender<ProcessRequest>,
+    ) -> anyhow::Result<Self> {
+        log::info!("warm boot: restoring state from switch {}...", switch);
+
+        // get CPU port and its host interface
+        let cpu_port = switch.get_cpu_port().context("failed to get CPU port")?;
+        let cpu_port_id = PortID::from(cpu_port);
+        let cpu_intf: HostIf<'a> = switch.get_hostif(state.cpu_hostif).context(format!(
+            "failed to restore host interface {:#x} for CPU port {}",
+            state.cpu_hostif, cpu_port_id
+        ))?;
+
+        // get the default virtual router
+        let default_virtual_router: VirtualRouter<'a> = switch
+            .get_default_virtual_router()
+            .context("failed to get default virtual router")?;
+
+        // restore our routes: if we cannot restore one, we simply lose track of it
+        // which is fine, as it will be added again on the next netlink address event
+        let mut routes = Vec::with_capacity(state.routes.len());
+        for route in state.routes.iter() {
+            let route = match IpNet::from_str(route.as_str()) {
+                Ok(v) => v,
+                Err(e) => {
+                    log::error!("warm boot: invalid route {route} in warm boot state: {e:?}");
+                    continue;
+                }
+            };
+            match default_virtual_router.get_route_entry(route) {
+                Ok(route_entry) => routes.push(route_entry),
+                Err(e) => log::error!(
+                    "warm boot: failed to restore route entry {route} on virtual router {}: {e:?}",
+                    default_virtual_router
+                ),
+            }
+        }
+
+        // get ports now, and restore our physical ports from them
+        // NOTE: SAI restores the serdes objects of the ports, so we don't need to apply the media settings here,
+        // but we need them for transceivers which get inserted later
+        let media_settings = media_settings.map(Arc::new);
+        let switch_ports = switch
+            .get_ports()
+            .context(format!("failed to get port list from switch {}", switch))?;
+        let mut ports: Vec<PhysicalPort<'a, 'b>> = Vec::with_capacity(state.ports.len());
+        let mut mac_slot = 0u64;
+        for port_state in state.ports.iter() {
+            let port_config = ports_config
+                .as_ref()
+                .and_then(|pc| pc.get(port_state.idx).cloned());
+            let port = PhysicalPort::from_warm_boot_state(
+                platform_ctx.clone(),
+                switch.clone(),
+                default_virtual_router.clone(),
+                mac_address,
+                mac_settings.clone(),
+                mac_slot,
+                port_state,
+                &switch_ports,
+                port_config,
+                media_settings.clone(),
+                discovery_profile.clone(),
+                hostif_naming.clone(),
+            )
+            .context(format!(
+                "failed to restore physical port {} from warm boot state",
+                port_state.idx
+            ))?;
+            mac_slot += port.lanes.len() as u64;
+            ports.push(port);
+        }
+
+        for port in ports.iter_mut() {
+            // continue auto discovery for all ports where it is enabled
+            // ports with a finished discovery will stay as they are
+            if port.auto_discovery {
+                // a port which did not finish discovery will start over
+                // so we need to wait on it again for the initial discovery
+                if port.xcvr_present && port.sm.is_none() {
+                    port.initial_port_discovery = Some(());
+                }
+                port.enable_auto_discovery(port.auto_discovery_with_breakout);
+            }
+
+            // the host interfaces which are up need their LLDP receivers again
+            for log_port in port.ports.iter_mut() {
+                if let Some(hif) = log_port.hif.as_mut() {
+                    if hif.oper_status {
+                        if let Err(e) = hif.set_oper_status(true, tx.clone()) {
+                            log::error!(
+                                "warm boot: failed to restore operational status of host interface {}: {:?}",
+                                hif,
+                                e
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        log::info!(
+            "warm boot: successfully restored {} physical ports and {} routes",
+            ports.len(),
+            routes.len()
+        );
+        Ok(Processor {
+            auto_discovery: state.auto_discovery,
+            auto_discovery_with_breakout: state.auto_discovery_with_breakout,
+            lldp_apply_network_config: lldp_apply_network_config,
+            route_sync: route_sync,
+            reconcile_interval: reconcile_interval,
+            reconcile_stats: ReconcileStats::default(),
+            switch: switch,
+            virtual_router: default_virtual_router,
+            routes: routes,
+            stale_routes: Vec::new(),
             cpu_port_id: cpu_port_id,
             cpu_hostif: cpu_intf,
             ports: ports,
-            rx: rx,
+            discovery_profile: discovery_profile,
+            warm_boot_state_file: warm_boot_state_file,
+            switch_oper_status: SwitchOperStatus::Up,
+            switch_shutdown_requested: false,
+            torn_down: false,
             tx: tx,
-            stdin_write: stdin_write,
-            stdout_read: stdout_read,
+            shell_session: None,
+            shell_session_counter: 0,
         })
     }
 
@@ -526,12 +897,15 @@ impl<'a, 'b> Processor<'a, 'b> {
         self.tx.clone()
     }
 
-    pub(crate) fn process(self) {
+    /// processes requests until a shutdown is requested. If `auto_reinit` is set, this also
+    /// returns after the switch was torn down because of a switch failure or shutdown request,
+    /// so that the caller can reinitialize SAI.
+    pub(crate) fn process(self, rx: &Receiver<ProcessRequest>, auto_reinit: bool) -> ProcessExit {
         let mut p = self;
-        while let Ok(req) = p.rx.recv() {
+        while let Ok(req) = rx.recv() {
             match req {
                 // shut down processor
-                ProcessRequest::Shutdown => return,
+                ProcessRequest::Shutdown => return ProcessExit::Shutdown,
 
                 // all RPC request handling
                 ProcessRequest::Version((r, resp_tx)) => {
@@ -546,6 +920,22 @@ impl<'a, 'b> Processor<'a, 'b> {
                         log::error!("failed to send shell response to rpc server: {:?}", e);
                     };
                 }
+                ProcessRequest::ShellExec((r, resp_tx)) => p.process_shell_exec_request(r, resp_tx),
+                ProcessRequest::ShellResize((r, resp_tx)) => {
+                    let resp = p.process_shell_resize_request(r);
+                    if let Err(e) = resp_tx.send(resp) {
+                        log::error!(
+                            "failed to send shell resize response to rpc server: {:?}",
+                            e
+                        );
+                    };
+                }
+                ProcessRequest::SAIDump((r, resp_tx)) => {
+                    let resp = p.process_sai_dump_request(r);
+                    if let Err(e) = resp_tx.send(resp) {
+                        log::error!("failed to send SAI dump response to rpc server: {:?}", e);
+                    };
+                }
                 ProcessRequest::PortList((r, resp_tx)) => {
                     let resp = p.process_port_list_request(r);
                     if let Err(e) = resp_tx.send(resp) {
@@ -590,9 +980,52 @@ impl<'a, 'b> Processor<'a, 'b> {
                         );
                     };
                 }
+                ProcessRequest::SwitchStatus((r, resp_tx)) => {
+                    let resp = p.process_switch_status_request(r);
+                    if let Err(e) = resp_tx.send(resp) {
+                        log::error!("failed to send switch status response to rpc server: {e:?}");
+                    };
+                }
+                ProcessRequest::ReconcileStatus((r, resp_tx)) => {
+                    let resp = p.process_reconcile_status_request(r);
+                    if let Err(e) = resp_tx.send(resp) {
+                        log::error!(
+                            "failed to send reconcile status response to rpc server: {e:?}"
+                        );
+                    };
+                }
+                ProcessRequest::Rediscover((r, resp_tx)) => {
+                    let resp = p.process_rediscover_request(r);
+                    if let Err(e) = resp_tx.send(resp) {
+                        log::error!("failed to send rediscover response to rpc server: {e:?}");
+                    };
+                }
+                ProcessRequest::SetPort((r, resp_tx)) => {
+                    let resp = p.process_set_port_request(r);
+                    if let Err(e) = resp_tx.send(resp) {
+                        log::error!("failed to send set port response to rpc server: {e:?}");
+                    };
+                }
+                ProcessRequest::DiscoveryProfile((r, resp_tx)) => {
+                    let resp = p.process_discovery_profile_request(r);
+                    if let Err(e) = resp_tx.send(resp) {
+                        log::error!(
+                            "failed to send discovery profile response to rpc server: {e:?}"
+                        );
+                    };
+                }
+                ProcessRequest::ValidatePortConfig((r, resp_tx)) => {
+                    let resp = p.process_validate_port_config_request(r);
+                    if let Err(e) = resp_tx.send(resp) {
+                        log::error!(
+                            "failed to send validate port config response to rpc server: {e:?}"
+                        );
+                    };
+                }
 
                 // internal events
                 ProcessRequest::AutoDiscoveryPoll => p.process_auto_discovery_poll(),
+                ProcessRequest::ReconcilePoll => p.process_reconcile_poll(),
                 ProcessRequest::LogicalPortStateChange((port_id, port_state)) => {
                     p.process_logical_port_state_change(port_id, port_state)
                 }
@@ -602,14 +1035,47 @@ impl<'a, 'b> Processor<'a, 'b> {
                 ProcessRequest::NetlinkAddrRemoved((if_idx, ip)) => {
                     p.process_netlink_addr_removed(if_idx, ip)
                 }
+                ProcessRequest::NetlinkLinkChanged(if_idx) => {
+                    p.process_netlink_link_changed(if_idx)
+                }
+                ProcessRequest::NetlinkLinkRemoved(if_idx) => {
+                    p.process_netlink_link_removed(if_idx)
+                }
+                ProcessRequest::NetlinkNeighborChanged(neighbor) => {
+                    p.process_netlink_neighbor_changed(neighbor)
+                }
+                ProcessRequest::NetlinkRouteChanged(route) => {
+                    p.process_netlink_route_changed(route)
+                }
+                ProcessRequest::NetlinkRouteRemoved(route) => {
+                    p.process_netlink_route_removed(route)
+                }
                 ProcessRequest::LLDPTLVsReceived((if_idx, tlvs)) => {
                     p.process_lldp_tlvs_received(if_idx, tlvs)
                 }
                 ProcessRequest::LLDPNetworkConfigReceived((if_idx, config)) => {
                     p.process_lldp_network_config_received(if_idx, config)
                 }
+                ProcessRequest::LLDPNeighborExpired(if_idx) => {
+                    p.process_lldp_neighbor_expired(if_idx)
+                }
+                ProcessRequest::SwitchStateChange(oper_status) => {
+                    if p.process_switch_state_change(oper_status) && auto_reinit {
+                        return ProcessExit::Reinit;
+                    }
+                }
+                ProcessRequest::SwitchShutdownRequest => {
+                    if p.process_switch_shutdown_request() && auto_reinit {
+                        return ProcessExit::Reinit;
+                    }
+                }
             }
+
+            // router interfaces get (re)created all over the place: from discovery, from RPC
+            // requests and from the reconciliation loop, so we check for them after every request
+            p.process_route_resync();
         }
+        ProcessExit::Shutdown
     }
 
     fn process_version_request(
@@ -627,165 +1093,154 @@ impl<'a, 'b> Processor<'a, 'b> {
     }
 
     fn process_shell_request(
-        &self,
+        &mut self,
         req: onie_sai::ShellRequest,
     ) -> Result<onie_sai::ShellResponse, ProcessError> {
-        let mut conn = UnixStream::connect(&req.socket.as_str())
+        self.check_shell_available()?;
+        let conn = UnixStream::connect(&req.socket.as_str())
             .context(format!(
                 "failed to connect to socket at {}",
                 &req.socket.as_str()
             ))
             .map_err(|e| ProcessError::ShellIOError(e))?;
-        conn.set_nonblocking(true)
-            .context("failed to set non-blocking mode")
-            .map_err(|e| ProcessError::ShellIOError(e))?;
-        let mut conn_writer = conn
-            .try_clone()
-            .context("failed to clone stream")
-            .map_err(|e| ProcessError::ShellIOError(e))?;
+        let idle_timeout = Duration::from_secs(
+            req.idle_timeout_secs
+                .map(|v| v as u64)
+                .unwrap_or(shell::SHELL_DEFAULT_IDLE_TIMEOUT_SECS),
+        );
+        let session = self.start_shell_session(ShellMode::Interactive {
+            conn: conn,
+            rows: req.rows as u16,
+            cols: req.cols as u16,
+            idle_timeout: idle_timeout,
+        })?;
+        Ok(onie_sai::ShellResponse {
+            session_id: session,
+            ..Default::default()
+        })
+    }
 
-        // enable shell IO
-        thread::sleep(Duration::from_millis(10));
-        let mut stdin_write_enabler = self
-            .stdin_write
-            .try_clone()
-            .context("failed to clone stdin")
-            .map_err(|e| ProcessError::ShellIOError(e))?;
-        stdin_write_enabler
-            .write(b"SAI_SHELL_ENABLE")
-            .context("failed to write shell enable marker to stdin")
-            .map_err(|e| ProcessError::ShellIOError(e))?;
-        stdin_write_enabler
-            .flush()
-            .context("failed to flush shell enable marker to stdin")
-            .map_err(|e| ProcessError::ShellIOError(e))?;
-        thread::sleep(Duration::from_millis(10));
-        log::debug!("shell: SAI_SHELL_ENABLE sent");
-
-        // this thread reads from the connection and writes to the stdin data pump
-        let mut stdin_write = self
-            .stdin_write
-            .try_clone()
-            .context("failed to clone stdin")
-            .map_err(|e| ProcessError::ShellIOError(e))?;
-        let (stdin_thread_tx, stdin_thread_rx) = mpsc::channel::<()>();
-        let stdin_thread = thread::spawn(move || {
-            let mut buf = [0u8; 1024];
-            let mut need_to_exit_thread = false;
-            loop {
-                if let Ok(_) = stdin_thread_rx.try_recv() {
-                    need_to_exit_thread = true;
-                }
-                match conn.read(&mut buf) {
-                    Ok(n) => {
-                        if n == 0 {
-                            // EOF
-                            break;
-                        }
-                        if let Err(e) = stdin_write.write_all(buf[..n].as_ref()) {
-                            log::error!("shell: failed to write to stdin: {:?}", e);
-                            break;
-                        }
-                    }
-                    Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
-                        // Non-blocking mode, no data available yet
-                        if need_to_exit_thread {
-                            break;
-                        }
-                        thread::sleep(Duration::from_millis(10));
-                        continue;
-                    }
-                    Err(e) => {
-                        log::error!("shell: failed to read from socket: {:?}", e);
-                        break;
-                    }
-                }
+    fn process_shell_exec_request(
+        &mut self,
+        req: onie_sai::ShellExecRequest,
+        resp_tx: Sender<Result<onie_sai::ShellExecResponse, ProcessError>>,
+    ) {
+        let timeout = Duration::from_secs(
+            req.timeout_secs
+                .map(|v| v as u64)
+                .unwrap_or(shell::SHELL_EXEC_DEFAULT_TIMEOUT_SECS),
+        );
+        let command = req.command.trim().to_string();
+        let ret = if command.is_empty() || command.contains('\n') {
+            Err(ProcessError::ShellIOError(anyhow!(
+                "shell exec: command must be a single non-empty line"
+            )))
+        } else {
+            self.check_shell_available()
+        };
+        if let Err(e) = ret {
+            if let Err(e) = resp_tx.send(Err(e)) {
+                log::error!("failed to send shell exec response to rpc server: {:?}", e);
             }
-            log::debug!("shell: stdin thread exiting");
-        });
-
-        // this thread reads from the stdout data pump and writes to the connection
-        let mut stdout_read = self
-            .stdout_read
-            .try_clone()
-            .context("failed to clone stdout")
-            .map_err(|e| ProcessError::ShellIOError(e))?;
-        let (stdout_thread_tx, stdout_thread_rx) = mpsc::channel::<()>();
-        let stdout_thread = thread::spawn(move || {
-            let mut buf = [0u8; 1024];
-            let mut need_to_exit_thread = false;
-            loop {
-                if let Ok(_) = stdout_thread_rx.try_recv() {
-                    need_to_exit_thread = true;
-                }
-                match stdout_read.read(&mut buf) {
-                    Ok(n) => {
-                        if n == 0 {
-                            // EOF
-                            break;
-                        }
-                        if let Err(e) = conn_writer.write_all(&buf[..n]) {
-                            log::error!("shell: failed to write to socket: {:?}", e);
-                            break;
-                        }
-                    }
-                    Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
-                        // Non-blocking mode, no data available yet
-                        if need_to_exit_thread {
-                            break;
-                        }
-                        thread::sleep(Duration::from_millis(10));
-                        continue;
-                    }
-                    Err(e) => {
-                        log::error!("shell: failed to read from stdout: {:?}", e);
-                        break;
-                    }
+            return;
+        }
+
+        // the session thread sends the response once the command finished
+        if let Err(e) = self.start_shell_session(ShellMode::Exec {
+            command: command,
+            timeout: timeout,
+            resp_tx: resp_tx.clone(),
+        }) {
+            if let Err(e) = resp_tx.send(Err(e)) {
+                log::error!("failed to send shell exec response to rpc server: {:?}", e);
+            }
+        }
+    }
+
+    fn process_shell_resize_request(
+        &self,
+        req: onie_sai::ShellResizeRequest,
+    ) -> Result<onie_sai::ShellResizeResponse, ProcessError> {
+        match self.shell_session.as_ref() {
+            Some(session) if session.id == req.session_id && !session.is_finished() => {
+                session
+                    .resize(req.rows as u16, req.cols as u16)
+                    .map_err(|e| ProcessError::ShellIOError(e.into()))?;
+                Ok(onie_sai::ShellResizeResponse {
+                    ..Default::default()
+                })
+            }
+            _ => Err(ProcessError::NoSuchShellSession(req.session_id)),
+        }
+    }
+
+    /// there can only be one shell at a time, and only while the switch exists
+    fn check_shell_available(&self) -> Result<(), ProcessError> {
+        if self.torn_down {
+            return Err(ProcessError::ShellUnavailable);
+        }
+        match self.shell_session.as_ref() {
+            Some(session) if !session.is_finished() => Err(ProcessError::ShellBusy(session.id)),
+            _ => Ok(()),
+        }
+    }
+
+    fn start_shell_session(&mut self, mode: ShellMode) -> Result<u64, ProcessError> {
+        self.shell_session_counter += 1;
+        let session = ShellSession::start(self.shell_session_counter, &self.switch, mode).map_err(
+            |e| match e {
+                ShellError::SAIError(e) => ProcessError::SAIError(e),
+                e => ProcessError::ShellIOError(e.into()),
+            },
+        )?;
+        let id = session.id;
+        log::info!("shell session {id}: started");
+        self.shell_session = Some(session);
+        Ok(id)
+    }
+
+    /// closes the shell session if there is one
+    /// NOTE: the shell runs inside of SAI, so this blocks until it is gone
+    fn close_shell_session(&mut self) {
+        if let Some(session) = self.shell_session.take() {
+            if !session.is_finished() {
+                match session.quit(Duration::from_secs(5)) {
+                    Ok(_) => log::info!("shell session {} closed", session.id),
+                    Err(e) => log::error!("failed to close shell session {}: {:?}", session.id, e),
                 }
             }
-            log::debug!("shell: stdout thread exiting");
-        });
-
-        // this warning is great because even with default warning level logs
-        // it gives a hint that the processor thread is blocked
-        log::warn!("shell requested, this blocks the processor thread!");
-        self.switch
-            .enable_shell()
-            .map_err(|e| ProcessError::SAIError(e))?;
-
-        // wait for all other threads to exit
-        stdout_thread_tx
-            .send(())
-            .context("failed to send exit to stdout thread")
-            .map_err(|e| ProcessError::ShellIOError(e))?;
-        stdout_thread
-            .join()
-            .map_err(|e| anyhow::anyhow!("stdout thread paniced: {:?}", e))
-            .map_err(|e| ProcessError::ShellIOError(e))?;
-        stdin_thread_tx
-            .send(())
-            .context("failed to send exit to stdin thread")
-            .map_err(|e| ProcessError::ShellIOError(e))?;
-        stdin_thread
-            .join()
-            .map_err(|e| anyhow::anyhow!("stdin thread paniced: {:?}", e))
-            .map_err(|e| ProcessError::ShellIOError(e))?;
+        }
+    }
 
-        // disable shell IO again
-        thread::sleep(Duration::from_millis(10));
-        stdin_write_enabler
-            .write(b"SAI_SHELL_DISABLE")
-            .context("failed to write shell disable marker to stdin")
-            .map_err(|e| ProcessError::ShellIOError(e))?;
-        stdin_write_enabler
-            .flush()
-            .context("failed to flush shell disable marker to stdin")
-            .map_err(|e| ProcessError::ShellIOError(e))?;
-        log::debug!("shell: SAI_SHELL_DISABLE sent");
+    fn process_sai_dump_request(
+        &self,
+        req: onie_sai::SAIDumpRequest,
+    ) -> Result<onie_sai::SAIDumpResponse, ProcessError> {
+        // SAI writes the dump to a file, so we need to generate it locally first
+        let dump_file =
+            std::env::temp_dir().join(format!("onie-said-sai-dump.{}", std::process::id()));
+        let dump_file_name = dump_file
+            .to_str()
+            .ok_or(anyhow!("dump file path is not a valid unicode string"))
+            .map_err(|e| ProcessError::SAIDumpIOError(e))?;
+        log::warn!("SAI dump requested, this blocks the processor thread!");
+        SAI::dbg_generate_dump(dump_file_name).map_err(|e| ProcessError::SAIStatus(e))?;
+        log::info!("SAI dump: generated dump at {}", dump_file_name);
+
+        // now stream it back to the client over the socket it is listening on
+        let ret = stream_file_to_socket(&dump_file, req.socket.as_str());
+        if let Err(e) = std::fs::remove_file(&dump_file) {
+            log::error!(
+                "SAI dump: failed to remove dump file {}: {:?}",
+                dump_file_name,
+                e
+            );
+        }
+        let size = ret.map_err(|e| ProcessError::SAIDumpIOError(e))?;
+        log::info!("SAI dump: sent {} bytes to {}", size, req.socket.as_str());
 
-        // this warning matches the one above, tell the users that we are unblocked again
-        log::warn!("shell finished, processor thread unblocked!");
-        Ok(onie_sai::ShellResponse {
+        Ok(onie_sai::SAIDumpResponse {
+            size: size,
             ..Default::default()
         })
     }
@@ -823,9 +1278,39 @@ impl<'a, 'b> Processor<'a, 'b> {
         &mut self,
         req: onie_sai::AutoDiscoveryRequest,
     ) -> Result<onie_sai::AutoDiscoveryResponse, ProcessError> {
+        // a request for a single port does not change the global setting
+        if let Some(idx) = req.port {
+            let port = self
+                .ports
+                .get_mut(idx as usize)
+                .ok_or(ProcessError::NoSuchPort(idx))?;
+            match (req.enable, req.enable_with_breakout) {
+                (Some(true), enable_with_breakout) => port.enable_auto_discovery(
+                    enable_with_breakout.unwrap_or(port.auto_discovery_with_breakout),
+                ),
+                (Some(false), enable_with_breakout) => {
+                    if let Some(v) = enable_with_breakout {
+                        port.auto_discovery_with_breakout = v;
+                    }
+                    port.disable_auto_discovery();
+                }
+                // breakout discovery can be toggled without touching auto discovery itself
+                (None, Some(enable_with_breakout)) => {
+                    port.set_auto_discovery_with_breakout(enable_with_breakout)
+                }
+                (None, None) => {}
+            }
+            return Ok(onie_sai::AutoDiscoveryResponse {
+                enabled: port.auto_discovery,
+                enabled_with_breakout: port.auto_discovery_with_breakout,
+                ..Default::default()
+            });
+        }
+
         match req.enable {
             None => Ok(onie_sai::AutoDiscoveryResponse {
                 enabled: self.auto_discovery,
+                enabled_with_breakout: self.auto_discovery_with_breakout,
                 ..Default::default()
             }),
             Some(enable) => {
@@ -834,8 +1319,10 @@ impl<'a, 'b> Processor<'a, 'b> {
                     None => self.auto_discovery_with_breakout,
                     Some(v) => v,
                 };
+                self.auto_discovery_with_breakout = enable_with_breakout;
                 if enable {
-                    for port in self.ports.iter_mut() {
+                    // ports which are pinned by the port config can only be enabled individually
+                    for port in self.ports.iter_mut().filter(|p| p.discovery_allowed()) {
                         port.enable_auto_discovery(enable_with_breakout)
                     }
                 } else {
@@ -845,6 +1332,7 @@ impl<'a, 'b> Processor<'a, 'b> {
                 }
                 Ok(onie_sai::AutoDiscoveryResponse {
                     enabled: self.auto_discovery,
+                    enabled_with_breakout: self.auto_discovery_with_breakout,
                     ..Default::default()
                 })
             }
@@ -931,6 +1419,7 @@ impl<'a, 'b> Processor<'a, 'b> {
                         if let Some(ref config) = hif.lldp_network_config {
                             return Ok(onie_sai::LLDPNetworkConfigResponse {
                                 network_config: wrap_message_field(Some(config.clone().into())),
+                                applied: hif.lldp_network_config_applied.as_ref() == Some(config),
                                 ..Default::default()
                             });
                         }
@@ -947,6 +1436,136 @@ impl<'a, 'b> Processor<'a, 'b> {
         })
     }
 
+    fn process_switch_status_request(
+        &self,
+        _: onie_sai::SwitchStatusRequest,
+    ) -> Result<onie_sai::SwitchStatusResponse, ProcessError> {
+        Ok(onie_sai::SwitchStatusResponse {
+            running: !self.torn_down,
+            oper_status: self.switch_oper_status.to_string(),
+            shutdown_requested: self.switch_shutdown_requested,
+            ..Default::default()
+        })
+    }
+
+    fn process_reconcile_status_request(
+        &self,
+        _: onie_sai::ReconcileStatusRequest,
+    ) -> Result<onie_sai::ReconcileStatusResponse, ProcessError> {
+        Ok(onie_sai::ReconcileStatusResponse {
+            enabled: self.reconcile_interval.is_some(),
+            interval_ms: self
+                .reconcile_interval
+                .map_or(0, |interval| interval.as_millis() as u64),
+            runs: self.reconcile_stats.runs,
+            drift_count: self.reconcile_stats.drift_count,
+            repair_count: self.reconcile_stats.repair_count,
+            unrepaired: self.reconcile_stats.unrepaired.clone(),
+            ..Default::default()
+        })
+    }
+
+    fn process_rediscover_request(
+        &mut self,
+        req: onie_sai::RediscoverRequest,
+    ) -> Result<onie_sai::RediscoverResponse, ProcessError> {
+        let mut ports = Vec::new();
+        match req.port {
+            Some(idx) => {
+                let port = self
+                    .ports
+                    .get_mut(idx as usize)
+                    .ok_or(ProcessError::NoSuchPort(idx))?;
+                if !port.rediscover() {
+                    return Err(ProcessError::AutoDiscoveryDisabled(idx));
+                }
+                ports.push(idx);
+            }
+            None => {
+                // ports with auto discovery disabled are simply skipped
+                for port in self.ports.iter_mut() {
+                    if port.rediscover() {
+                        ports.push(port.idx as u32);
+                    }
+                }
+            }
+        }
+        Ok(onie_sai::RediscoverResponse {
+            ports: ports,
+            ..Default::default()
+        })
+    }
+
+    fn process_set_port_request(
+        &mut self,
+        req: onie_sai::SetPortRequest,
+    ) -> Result<onie_sai::SetPortResponse, ProcessError> {
+        let breakout_mode = match req.breakout_mode.as_deref() {
+            None => None,
+            Some("OneLane") => Some(BreakoutModeType::OneLane),
+            Some("TwoLanes") => Some(BreakoutModeType::TwoLanes),
+            Some("FourLanes") => Some(BreakoutModeType::FourLanes),
+            Some("EightLanes") => Some(BreakoutModeType::EightLanes),
+            Some(v) => {
+                return Err(ProcessError::InvalidArgument(format!(
+                    "unknown breakout mode {v}"
+                )))
+            }
+        };
+        let fec = match req.fec.as_deref() {
+            None => None,
+            Some("None") => Some(FECMode::None),
+            Some("RS") => Some(FECMode::RS),
+            Some("FC") => Some(FECMode::FC),
+            Some(v) => {
+                return Err(ProcessError::InvalidArgument(format!(
+                    "unknown FEC mode {v}"
+                )))
+            }
+        };
+        let settings = LogicalPortSettings {
+            admin_state: req.admin_state,
+            speed: req.speed,
+            auto_negotiation: req.auto_neg,
+            fec: fec,
+        };
+
+        let port = self
+            .ports
+            .get_mut(req.id as usize)
+            .ok_or(ProcessError::NoSuchPort(req.id))?;
+        port.set_manually(
+            breakout_mode,
+            req.logical_port.map(|v| v as usize),
+            &settings,
+        )?;
+        Ok(onie_sai::SetPortResponse {
+            port: wrap_message_field(Some((&*port).into())),
+            ..Default::default()
+        })
+    }
+
+    fn process_discovery_profile_request(
+        &self,
+        _: onie_sai::DiscoveryProfileRequest,
+    ) -> Result<onie_sai::DiscoveryProfileResponse, ProcessError> {
+        Ok(self.discovery_profile.as_ref().into())
+    }
+
+    fn process_validate_port_config_request(
+        &self,
+        req: onie_sai::ValidatePortConfigRequest,
+    ) -> Result<onie_sai::ValidatePortConfigResponse, ProcessError> {
+        let config = PhysicalPortConfig::parse(&req.contents)
+            .map_err(|e| ProcessError::InvalidArgument(format!("invalid port config: {}", e)))?;
+        let switch_lanes: Vec<Vec<u32>> = self.ports.iter().map(|p| p.lanes.clone()).collect();
+        Ok(onie_sai::ValidatePortConfigResponse {
+            errors: check_port_config(&config).errors,
+            mismatches: compare_port_config(&config, &switch_lanes),
+            ..Default::default()
+        })
+    }
+
     fn process_auto_discovery_poll(&mut self) {
         log::debug!("auto discovery poll");
         for phy_port in self.ports.iter_mut() {
@@ -954,6 +1573,30 @@ impl<'a, 'b> Processor<'a, 'b> {
         }
     }
 
+    /// compares the desired state against the actual state in SAI and the kernel, and repairs
+    /// all divergences that it can: the host interfaces and router interfaces come first, as the
+    /// routes depend on the host interfaces
+    fn process_reconcile_poll(&mut self) {
+        // there is nothing to repair anymore without reinitializing SAI
+        if self.torn_down {
+            return;
+        }
+        log::debug!("reconcile poll");
+        let sender = self.get_sender();
+        let mut drift = Vec::new();
+        for phy_port in self.ports.iter_mut() {
+            drift.extend(phy_port.reconcile(sender.clone()));
+        }
+        drift.extend(self.reconcile_routes());
+        for d in drift.iter() {
+            match d.repaired {
+                true => log::warn!("reconcile: {d}"),
+                false => log::error!("reconcile: {d}"),
+            }
+        }
+        self.reconcile_stats.record(drift);
+    }
+
     fn process_logical_port_state_change(&mut self, port_id: PortID, port_state: OperStatus) {
         let mut found = false;
         let sender = self.get_sender();
@@ -965,13 +1608,10 @@ impl<'a, 'b> Processor<'a, 'b> {
                     // step port discovery state machine if it is a port up event
                     let oper_status: bool = port_state.into();
                     if oper_status {
-                        match log_port.sm.as_mut() {
-                            Some(sm) => {
-                                *sm = sm.clone().step(&log_port.port, PortUp);
-                            }
-                            None => {
-                                log::debug!("port {} has no discovery state machine", port_id);
-                            }
+                        if log_port.sm.is_some() {
+                            log_port.step_discovery(PortUp);
+                        } else {
+                            log::debug!("port {} has no discovery state machine", port_id);
                         }
                     }
 
@@ -1002,6 +1642,53 @@ impl<'a, 'b> Processor<'a, 'b> {
         }
     }
 
+    /// returns true if the switch was torn down because of the state change
+    fn process_switch_state_change(&mut self, oper_status: SwitchOperStatus) -> bool {
+        let prev_oper_status = self.switch_oper_status;
+        self.switch_oper_status = oper_status;
+        match oper_status {
+            SwitchOperStatus::Down | SwitchOperStatus::Failed => {
+                if self.torn_down {
+                    log::warn!(
+                        "switch {} is {oper_status}, but it was torn down already",
+                        self.switch
+                    );
+                    return false;
+                }
+                log::error!("switch {} changed its operational status from {prev_oper_status} to {oper_status}. Tearing down switch...", self.switch);
+                self.teardown(true);
+                true
+            }
+            SwitchOperStatus::Up if self.torn_down => {
+                // NOTE: we removed all our objects already, and there is no way back without reinitializing SAI
+                log::warn!("switch {} is up again, but it was torn down already. Restart onie-said or enable automatic reinitialization to recover.", self.switch);
+                false
+            }
+            _ => {
+                log::info!("switch {} changed its operational status from {prev_oper_status} to {oper_status}", self.switch);
+                false
+            }
+        }
+    }
+
+    /// returns true if the switch was torn down because of the shutdown request
+    fn process_switch_shutdown_request(&mut self) -> bool {
+        self.switch_shutdown_requested = true;
+        if self.torn_down {
+            log::warn!(
+                "switch {} requested a shutdown, but it was torn down already",
+                self.switch
+            );
+            return false;
+        }
+        log::error!(
+            "switch {} requested a shutdown. Tearing down switch...",
+            self.switch
+        );
+        self.teardown(true);
+        true
+    }
+
     fn process_netlink_addr_added(&mut self, if_idx: u32, ip: IpAddr) {
         let if_name = netlink::get_interface_name(if_idx).unwrap_or("unknown".to_string());
         // find the host interface
@@ -1069,6 +1756,207 @@ impl<'a, 'b> Processor<'a, 'b> {
         }
     }
 
+    fn process_lldp_neighbor_expired(&mut self, if_idx: u32) {
+        // the neighbor is gone, and so is everything that we learned from it
+        for phy_port in self.ports.iter_mut() {
+            for log_port in phy_port.ports.iter_mut() {
+                for hif in log_port.hif.iter_mut() {
+                    if hif.idx == if_idx {
+                        hif.lldp_tlvs = None;
+                        hif.lldp_network_config = None;
+                        if let Err(e) = hif.remove_network_config() {
+                            log::error!(
+                                "host interface {} ({if_idx}): failed to remove LLDP network config: {e:?}",
+                                hif.name
+                            );
+                        }
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    fn process_netlink_link_changed(&mut self, if_idx: u32) {
+        // find the logical port of the host interface
+        // NOTE: this is the case for every interface which is not one of our host interfaces
+        for phy_port in self.ports.iter_mut() {
+            let i = match phy_port
+                .ports
+                .iter()
+                .position(|p| p.hif.as_ref().map_or(false, |hif| hif.idx == if_idx))
+            {
+                Some(i) => i,
+                None => continue,
+            };
+            match netlink::get_link(if_idx) {
+                Ok(link) => phy_port.host_interface_changed(i, &link),
+                Err(e) => {
+                    log::error!("failed to get link of host interface ({if_idx}) after netlink link changed event: {e}");
+                }
+            }
+            return;
+        }
+    }
+
+    fn process_netlink_link_removed(&mut self, if_idx: u32) {
+        // we remove host interfaces ourselves when ports get broken out, and they are gone from
+        // our ports by then, so this only happens if somebody removed one of them from under us
+        for phy_port in self.ports.iter_mut() {
+            for log_port in phy_port.ports.iter_mut() {
+                for hif in log_port.hif.iter_mut() {
+                    if hif.idx == if_idx {
+                        log::warn!(
+                            "host interface {} ({if_idx}) was removed from the kernel",
+                            hif.name
+                        );
+                        // the kernel removed its addresses and routes along with it
+                        hif.lldp_network_config_applied = None;
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    fn process_netlink_neighbor_changed(&mut self, neighbor: netlink::Neighbor) {
+        // find the logical port of the host interface
+        // NOTE: neighbors of interfaces which are not our host interfaces are ignored
+        let cpu_port_id = NextHopID::from(self.cpu_port_id);
+        for phy_port in self.ports.iter_mut() {
+            for log_port in phy_port.ports.iter_mut() {
+                if !log_port
+                    .hif
+                    .as_ref()
+                    .map_or(false, |hif| hif.idx == neighbor.index)
+                {
+                    continue;
+                }
+                let rif_id = match log_port.rif.as_ref() {
+                    Some(rif) => rif.to_id(),
+                    None => return,
+                };
+                match neighbor.mac_address {
+                    Some(mac_address) => {
+                        if let Err(e) =
+                            log_port
+                                .route_sync
+                                .neighbor_changed(rif_id, neighbor.ip, mac_address)
+                        {
+                            log::error!(
+                                "Port {}: failed to sync neighbor {}: {:?}",
+                                log_port.port,
+                                neighbor.ip,
+                                e
+                            );
+                        }
+                    }
+                    None => log_port
+                        .route_sync
+                        .neighbor_removed(neighbor.ip, cpu_port_id),
+                }
+                return;
+            }
+        }
+    }
+
+    fn process_netlink_route_changed(&mut self, route: netlink::Route) {
+        // our own routes for the addresses of the host interfaces always take precedence
+        if self
+            .routes
+            .iter()
+            .any(|route_entry| *route_entry == route.destination)
+        {
+            return;
+        }
+
+        // a route can move between interfaces, so it must be gone from all the other ports first
+        for phy_port in self.ports.iter_mut() {
+            for log_port in phy_port.ports.iter_mut() {
+                if !log_port
+                    .hif
+                    .as_ref()
+                    .map_or(false, |hif| hif.idx == route.index)
+                {
+                    log_port.route_sync.route_removed(&route.destination);
+                }
+            }
+        }
+
+        // NOTE: routes of interfaces which are not our host interfaces are ignored
+        let cpu_port_id = NextHopID::from(self.cpu_port_id);
+        for phy_port in self.ports.iter_mut() {
+            for log_port in phy_port.ports.iter_mut() {
+                if !log_port
+                    .hif
+                    .as_ref()
+                    .map_or(false, |hif| hif.idx == route.index)
+                {
+                    continue;
+                }
+                let rif_id = match log_port.rif.as_ref() {
+                    Some(rif) => rif.to_id(),
+                    None => return,
+                };
+                if let Err(e) = log_port.route_sync.route_changed(
+                    rif_id,
+                    cpu_port_id,
+                    route.destination,
+                    route.gateway,
+                ) {
+                    log::error!(
+                        "Port {}: failed to sync route {}: {:?}",
+                        log_port.port,
+                        route.destination,
+                        e
+                    );
+                }
+                return;
+            }
+        }
+    }
+
+    /// triggers a dump of the neighbors and routes of every host interface whose router interface
+    /// got (re)created: they arrive as regular netlink events then
+    fn process_route_resync(&mut self) {
+        if !self.route_sync || self.torn_down {
+            return;
+        }
+        for phy_port in self.ports.iter_mut() {
+            for log_port in phy_port.ports.iter_mut() {
+                if !log_port.route_resync {
+                    continue;
+                }
+                // NOTE: without the index of the kernel interface, this has to wait for the
+                // reconciliation loop to fix it up
+                if let Some(hif) = log_port.hif.as_ref().filter(|hif| hif.idx != 0) {
+                    log::debug!(
+                        "Port {}: resyncing neighbors and routes of host interface {}",
+                        log_port.port,
+                        hif.name
+                    );
+                    netlink::resync_neighbors_and_routes(self.tx.clone(), hif.idx);
+                    log_port.route_resync = false;
+                }
+            }
+        }
+    }
+
+    fn process_netlink_route_removed(&mut self, route: netlink::Route) {
+        for phy_port in self.ports.iter_mut() {
+            for log_port in phy_port.ports.iter_mut() {
+                if log_port
+                    .hif
+                    .as_ref()
+                    .map_or(false, |hif| hif.idx == route.index)
+                {
+                    log_port.route_sync.route_removed(&route.destination);
+                    return;
+                }
+            }
+        }
+    }
+
     fn process_lldp_network_config_received(&mut self, if_idx: u32, config: NetworkConfig) {
         let if_name = netlink::get_interface_name(if_idx).unwrap_or("unknown".to_string());
         // find the host interface
@@ -1080,6 +1968,11 @@ impl<'a, 'b> Processor<'a, 'b> {
                     if hif.idx == if_idx {
                         found = true;
                         hif.lldp_network_config = Some(config);
+                        if self.lldp_apply_network_config {
+                            if let Err(e) = hif.apply_network_config() {
+                                log::error!("host interface {if_name} ({if_idx}): failed to apply LLDP network config: {e:?}");
+                            }
+                        }
                         break 'outer;
                     }
                 }
@@ -1134,7 +2027,7 @@ impl<'a, 'b> Processor<'a, 'b> {
             .position(|route_entry| *route_entry == route)
         {
             let route_entry = self.routes.remove(idx);
-            match route_entry.remove() {
+            match route_entry.clone().remove() {
                 Ok(_) => {
                     log::info!(
                         "removed route entry {:?} for ourselves from virtual router {}",
@@ -1143,24 +2036,175 @@ impl<'a, 'b> Processor<'a, 'b> {
                     );
                 }
                 Err(e) => {
-                    // NOTE: there is a problem here that cannot really be solved.
-                    // If we cannot remove the route entry, our state is screwed up
-                    // we keep it out of the list because we don't really know what
-                    // the error means anyways.
+                    // NOTE: we keep it out of the list because we don't really know what
+                    // the error means anyways. The reconciliation loop retries to remove it.
                     log::error!(
                         "failed to remove route entry from virtual router {}: {e:?}",
                         self.virtual_router
                     );
+                    self.stale_routes.push(route_entry);
                 }
             }
         }
     }
 }
 
-impl<'a, 'b> Drop for Processor<'a, 'b> {
-    fn drop(&mut self) {
-        // TODO: the `clone()`s here are ugly, but there is no real good other solution (that I know of)
-        log::info!("Shutting down ONIE SAI processor...");
+impl<'a, 'b> Processor<'a, 'b> {
+    /// Reconciles our routes for the addresses of the host interfaces: the desired routes are
+    /// the addresses of the host interfaces in the kernel, and every route entry must exist in SAI.
+    fn reconcile_routes(&mut self) -> Vec<Drift> {
+        let mut ret = Vec::new();
+        let addrs = match netlink::get_addresses() {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("reconcile: failed to get addresses from the kernel: {e}");
+                return ret;
+            }
+        };
+        let hif_idxs: Vec<u32> = self
+            .ports
+            .iter()
+            .flat_map(|phy_port| phy_port.ports.iter())
+            .filter_map(|log_port| log_port.hif.as_ref().map(|hif| hif.idx))
+            .collect();
+        let desired: Vec<IpNet> = addrs
+            .into_iter()
+            .filter(|(idx, _)| hif_idxs.contains(idx))
+            .map(|(_, ip)| ip.into())
+            .collect();
+
+        // route entries which we failed to remove before: they are taken back if they are
+        // desired again, and the check below recreates them if they are gone from SAI after all
+        for route_entry in std::mem::take(&mut self.stale_routes) {
+            let route = IpNet::from(&route_entry);
+            if desired.contains(&route) && !self.routes.iter().any(|r| *r == route) {
+                self.routes.push(route_entry);
+                continue;
+            }
+            let repaired = match route_entry.clone().remove() {
+                Ok(_) => true,
+                Err(e) => {
+                    log::debug!("reconcile: failed to remove stale route entry {route}: {e:?}");
+                    self.stale_routes.push(route_entry);
+                    false
+                }
+            };
+            ret.push(Drift::new(format!("stale route entry {route}"), repaired));
+        }
+
+        // route entries which are gone from SAI
+        // NOTE: only a route entry which SAI does not know is missing: not every platform
+        // supports getting route entries, and we cannot tell anything on any other error
+        let routes: Vec<IpNet> = self.routes.iter().map(IpNet::from).collect();
+        for route in routes.iter() {
+            if !desired.contains(route) {
+                continue;
+            }
+            match self.virtual_router.get_route_entry(*route) {
+                Err(sai::Error::SAI(Status::ItemNotFound)) => {}
+                Ok(_) => continue,
+                Err(e) => {
+                    log::debug!("reconcile: failed to get route entry {route}: {e:?}");
+                    continue;
+                }
+            }
+            if let Some(idx) = self.routes.iter().position(|r| r == route) {
+                self.routes.remove(idx);
+            }
+            self.add_route(*route);
+            ret.push(Drift::new(
+                format!("route entry {route} is missing in SAI"),
+                self.routes.iter().any(|r| r == route),
+            ));
+        }
+
+        // routes for addresses which we have missed
+        for route in desired.iter() {
+            if self.routes.iter().any(|r| r == route) {
+                continue;
+            }
+            self.add_route(*route);
+            ret.push(Drift::new(
+                format!("route entry {route} is missing"),
+                self.routes.iter().any(|r| r == route),
+            ));
+        }
+
+        // routes for addresses which are gone
+        for route in routes.iter() {
+            if desired.contains(route) {
+                continue;
+            }
+            self.remove_route(*route);
+            ret.push(Drift::new(
+                format!("route entry {route} has no address anymore"),
+                !self.stale_routes.iter().any(|r| r == route),
+            ));
+        }
+        ret
+    }
+}
+
+impl<'a, 'b> Processor<'a, 'b> {
+    /// persists our state to the warm boot state file, and shuts down the switch
+    /// so that SAI persists its state as well. All objects are left in place.
+    fn warm_shutdown(&self, state_file: &PathBuf) -> anyhow::Result<()> {
+        let state = WarmBootState {
+            auto_discovery: self.auto_discovery,
+            auto_discovery_with_breakout: self.auto_discovery_with_breakout,
+            cpu_hostif: self.cpu_hostif.to_id().into(),
+            routes: self
+                .routes
+                .iter()
+                .map(|route| IpNet::from(route).to_string())
+                .collect(),
+            ports: self
+                .ports
+                .iter()
+                .map(|port| PhysicalPortState::from(port))
+                .collect(),
+        };
+        state.to_file(state_file).context(format!(
+            "failed to write warm boot state to {:?}",
+            state_file
+        ))?;
+
+        // if any of the following fails, the state file must not be used on the next start
+        let ret = self
+            .switch
+            .set_restart_warm(true)
+            .context("failed to set warm restart on switch")
+            .and_then(|_| {
+                // this step is optional, and not every SAI supports it
+                if let Err(e) = self.switch.set_pre_shutdown(true) {
+                    log::debug!("warm shutdown: pre-shutdown failed: {:?}", e);
+                }
+                self.switch
+                    .clone()
+                    .remove()
+                    .context("failed to remove switch for warm shutdown")
+            });
+        if ret.is_err() {
+            if let Err(e) = std::fs::remove_file(state_file) {
+                log::error!(
+                    "warm shutdown: failed to remove warm boot state file {:?}: {:?}",
+                    state_file,
+                    e
+                );
+            }
+        }
+        ret
+    }
+
+    /// removes all host interfaces that we created, and optionally the switch itself.
+    /// The processor keeps processing requests afterwards, but it has no ports anymore.
+    fn teardown(&mut self, remove_switch: bool) {
+        // our state does not match the switch anymore, so we must never write a warm boot state after this
+        self.warm_boot_state_file = None;
+        self.torn_down = true;
+
+        // the shell runs inside of SAI, so it must be gone before the switch
+        self.close_shell_session();
 
         // removing CPU host interface
         let cpu_hostif_id = self.cpu_hostif.to_id();
@@ -1174,7 +2218,7 @@ impl<'a, 'b> Drop for Processor<'a, 'b> {
         };
 
         // removing host interfaces for all ports
-        for phy_port in self.ports.clone() {
+        for phy_port in std::mem::take(&mut self.ports) {
             for port in phy_port.ports {
                 let port_id = port.port.to_id();
                 if let Some(hif) = port.hif {
@@ -1193,9 +2237,70 @@ impl<'a, 'b> Drop for Processor<'a, 'b> {
                 }
             }
         }
+        self.routes.clear();
+        self.stale_routes.clear();
+
+        if remove_switch {
+            let switch_id = self.switch.to_id();
+            match self.switch.clone().remove() {
+                Ok(_) => log::info!("removed switch {}", switch_id),
+                Err(e) => log::error!("failed to remove switch {}: {:?}", switch_id, e),
+            }
+        }
     }
 }
 
+impl<'a, 'b> Drop for Processor<'a, 'b> {
+    fn drop(&mut self) {
+        // TODO: the `clone()`s here are ugly, but there is no real good other solution (that I know of)
+        log::info!("Shutting down ONIE SAI processor...");
+
+        // the shell must be gone before anything else
+        self.close_shell_session();
+
+        // on a warm shutdown we leave everything in place
+        if let Some(state_file) = self.warm_boot_state_file.clone() {
+            // except for the synced kernel neighbors and routes: they are not part of the warm boot
+            // state, and get synced again from the kernel after the warm boot
+            for phy_port in self.ports.iter_mut() {
+                for log_port in phy_port.ports.iter_mut() {
+                    log_port.route_sync.flush();
+                }
+            }
+            match self.warm_shutdown(&state_file) {
+                Ok(_) => {
+                    log::info!(
+                        "warm shutdown: successfully saved state to {:?}",
+                        state_file
+                    );
+                    return;
+                }
+                Err(e) => log::error!(
+                    "warm shutdown failed, performing cold shutdown instead: {:?}",
+                    e
+                ),
+            }
+        }
+
+        if !self.torn_down {
+            self.teardown(false);
+        }
+    }
+}
+
+fn stream_file_to_socket(path: &PathBuf, socket: &str) -> anyhow::Result<u64> {
+    let mut file = File::open(path).context(format!("failed to open {:?}", path))?;
+    let mut conn = UnixStream::connect(socket)
+        .context(format!("failed to connect to socket at {}", socket))?;
+    let size = std::io::copy(&mut file, &mut conn).context(format!(
+        "failed to write {:?} to socket at {}",
+        path, socket
+    ))?;
+    conn.flush()
+        .context(format!("failed to flush socket at {}", socket))?;
+    Ok(size)
+}
+
 fn until_err<T, E>(err: &mut &mut Result<(), E>, item: Result<T, E>) -> Option<T> {
     match item {
         Ok(item) => Some(item),
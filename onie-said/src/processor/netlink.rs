@@ -1,23 +1,46 @@
+use ipnet::IpNet;
 use libc::RTMGRP_IPV4_IFADDR;
+use libc::RTMGRP_IPV4_ROUTE;
 use libc::RTMGRP_IPV6_IFADDR;
+use libc::RTMGRP_IPV6_ROUTE;
+use libc::RTMGRP_LINK;
+use libc::RTMGRP_NEIGH;
 use netlink_packet_core::constants::NLM_F_ACK;
+use netlink_packet_core::constants::NLM_F_DUMP;
 use netlink_packet_core::constants::NLM_F_REQUEST;
 use netlink_packet_core::NetlinkHeader;
 use netlink_packet_core::NetlinkMessage;
 use netlink_packet_core::NetlinkPayload;
 use netlink_packet_route::rtnl::address::AddressMessage;
 use netlink_packet_route::rtnl::nlas::address::Nla;
+use netlink_packet_route::rtnl::nlas::link::Nla as LinkNla;
+use netlink_packet_route::rtnl::nlas::neighbour::Nla as NeighbourNla;
+use netlink_packet_route::rtnl::nlas::route::Nla as RouteNla;
 use netlink_packet_route::LinkMessage;
+use netlink_packet_route::NeighbourMessage;
+use netlink_packet_route::RouteMessage;
 use netlink_packet_route::RtnlMessage;
-use netlink_packet_utils::DecodeError;
+use netlink_packet_route::NUD_FAILED;
+use netlink_packet_route::NUD_INCOMPLETE;
+use netlink_packet_route::NUD_NONE;
+use netlink_packet_route::RTN_UNICAST;
+use netlink_packet_route::RT_TABLE_MAIN;
 use netlink_sys::protocols::NETLINK_ROUTE;
 use netlink_sys::Socket;
 use netlink_sys::SocketAddr;
+use onie_sai_common::netlink::add_address_and_routes;
+use onie_sai_common::netlink::del_address_and_routes;
+use onie_sai_common::netlink::request_and_ack;
+use onie_sai_common::netlink::send_and_ack;
 use std::ffi::CStr;
 use std::net::IpAddr;
 use std::sync::mpsc::Sender;
 use std::thread;
 
+pub(crate) use onie_sai_common::netlink::SetLinkError;
+
+use crate::lldp::NetworkConfig;
+
 use super::ProcessRequest;
 
 pub(crate) fn netlink_addr_monitor(
@@ -135,6 +158,355 @@ fn convert(am: AddressMessage) -> Result<(u32, IpAddr), AddressMessageConversion
     }
 }
 
+/// returns the addresses of all interfaces in the kernel
+pub(crate) fn get_addresses() -> Result<Vec<(u32, IpAddr)>, SetLinkError> {
+    let mut ret = Vec::new();
+    for msg in dump(RtnlMessage::GetAddress(AddressMessage::default()))? {
+        if let RtnlMessage::NewAddress(v) = msg {
+            match convert(v) {
+                Ok(v) => ret.push(v),
+                Err(e) => log::debug!("failed to convert address message: {e:?}"),
+            }
+        }
+    }
+    Ok(ret)
+}
+
+pub(crate) fn netlink_link_monitor(
+    proc_tx: Sender<ProcessRequest>,
+) -> Result<thread::JoinHandle<()>, std::io::Error> {
+    let mut socket = Socket::new(NETLINK_ROUTE)?;
+    let addr = SocketAddr::new(0, RTMGRP_LINK as u32);
+    socket.bind(&addr)?;
+
+    let mut buf = vec![0; 8192];
+    let mut off = 0;
+
+    Ok(thread::spawn(move || {
+        loop {
+            let size = match socket.recv(&mut &mut buf[..], 0) {
+                Ok(v) => v,
+                Err(e) => {
+                    log::error!(
+                        "netlink link monitor: failed to receive from netlink socket: {e:?}"
+                    );
+                    continue;
+                }
+            };
+
+            // there is no guarantee that a single receive call gives us only one netlink message
+            // so we need to loop and try to deserialize multiple messages
+            loop {
+                let packet: NetlinkMessage<RtnlMessage> = match NetlinkMessage::deserialize(
+                    &buf[off..],
+                ) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        log::error!("netlink link monitor: received invalid netlink message, failed to deserialize: {e:?}");
+                        break;
+                    }
+                };
+
+                // NOTE: we only pass on the interface index, the processor reads the current state
+                // of the link itself as the event might be stale by the time it gets processed
+                match packet.payload {
+                    NetlinkPayload::InnerMessage(RtnlMessage::SetLink(v)) => {
+                        let _ = proc_tx.send(ProcessRequest::NetlinkLinkChanged(v.header.index));
+                    }
+                    NetlinkPayload::InnerMessage(RtnlMessage::NewLink(v)) => {
+                        let _ = proc_tx.send(ProcessRequest::NetlinkLinkChanged(v.header.index));
+                    }
+                    NetlinkPayload::InnerMessage(RtnlMessage::DelLink(v)) => {
+                        let _ = proc_tx.send(ProcessRequest::NetlinkLinkRemoved(v.header.index));
+                    }
+                    NetlinkPayload::Error(e) => {
+                        log::error!(
+                        "netlink link monitor: received error message from netlink socket: {e:?}"
+                    );
+                    }
+                    v => {
+                        log::warn!("netlink link monitor: received unexpected message from netlink socket: {v:?}");
+                    }
+                }
+
+                off += packet.header.length as usize;
+                if off == size || packet.header.length == 0 {
+                    off = 0;
+                    break;
+                }
+            }
+        }
+    }))
+}
+
+/// the state of a link that we sync with the port and the router interface
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Link {
+    pub(crate) index: u32,
+    pub(crate) admin_up: bool,
+    pub(crate) mtu: Option<u32>,
+    pub(crate) mac_address: Option<[u8; 6]>,
+}
+
+impl From<LinkMessage> for Link {
+    fn from(lm: LinkMessage) -> Self {
+        let mut ret = Link {
+            index: lm.header.index,
+            admin_up: lm.header.flags & libc::IFF_UP as u32 != 0,
+            mtu: None,
+            mac_address: None,
+        };
+        for nla in lm.nlas.into_iter() {
+            match nla {
+                LinkNla::Mtu(v) => ret.mtu = Some(v),
+                LinkNla::Address(v) => ret.mac_address = <[u8; 6]>::try_from(v.as_slice()).ok(),
+                _ => {}
+            }
+        }
+        ret
+    }
+}
+
+/// Monitors the neighbors and routes of the kernel. It dumps all existing neighbors and routes
+/// first, so that the processor starts off with the current state of the kernel.
+pub(crate) fn netlink_route_monitor(
+    proc_tx: Sender<ProcessRequest>,
+) -> Result<thread::JoinHandle<()>, std::io::Error> {
+    // NOTE: we subscribe before we dump, so that we cannot miss any changes in between
+    let mut socket = Socket::new(NETLINK_ROUTE)?;
+    let addr = SocketAddr::new(
+        0,
+        (RTMGRP_NEIGH | RTMGRP_IPV4_ROUTE | RTMGRP_IPV6_ROUTE) as u32,
+    );
+    socket.bind(&addr)?;
+
+    let mut buf = vec![0; 8192];
+    let mut off = 0;
+
+    Ok(thread::spawn(move || {
+        dump_neighbors_and_routes(&proc_tx, None);
+
+        loop {
+            let size = match socket.recv(&mut &mut buf[..], 0) {
+                Ok(v) => v,
+                Err(e) => {
+                    log::error!(
+                        "netlink route monitor: failed to receive from netlink socket: {e:?}"
+                    );
+                    continue;
+                }
+            };
+
+            // there is no guarantee that a single receive call gives us only one netlink message
+            // so we need to loop and try to deserialize multiple messages
+            loop {
+                let packet: NetlinkMessage<RtnlMessage> = match NetlinkMessage::deserialize(
+                    &buf[off..],
+                ) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        log::error!("netlink route monitor: received invalid netlink message, failed to deserialize: {e:?}");
+                        break;
+                    }
+                };
+
+                match packet.payload {
+                    NetlinkPayload::InnerMessage(v) => handle_route_monitor_message(&proc_tx, v),
+                    NetlinkPayload::Error(e) => {
+                        log::error!(
+                        "netlink route monitor: received error message from netlink socket: {e:?}"
+                    );
+                    }
+                    v => {
+                        log::warn!("netlink route monitor: received unexpected message from netlink socket: {v:?}");
+                    }
+                }
+
+                off += packet.header.length as usize;
+                if off == size || packet.header.length == 0 {
+                    off = 0;
+                    break;
+                }
+            }
+        }
+    }))
+}
+
+/// Dumps the neighbors and routes of a single interface in the background, and passes them on to
+/// the processor just like the route monitor does. This is needed whenever the router interface
+/// of a host interface gets (re)created, as all events for it were dropped in the meantime.
+pub(crate) fn resync_neighbors_and_routes(proc_tx: Sender<ProcessRequest>, index: u32) {
+    thread::spawn(move || dump_neighbors_and_routes(&proc_tx, Some(index)));
+}
+
+/// dumps all neighbors and routes, or only the ones of the interface `index`, and passes them on
+fn dump_neighbors_and_routes(proc_tx: &Sender<ProcessRequest>, index: Option<u32>) {
+    for req in [
+        RtnlMessage::GetNeighbour(NeighbourMessage::default()),
+        RtnlMessage::GetRoute(RouteMessage::default()),
+    ] {
+        match dump(req) {
+            Ok(msgs) => {
+                for msg in msgs.into_iter() {
+                    if index.map_or(true, |index| message_index(&msg) == Some(index)) {
+                        handle_route_monitor_message(proc_tx, msg);
+                    }
+                }
+            }
+            Err(e) => {
+                log::error!("netlink route monitor: failed to dump neighbors or routes: {e}");
+            }
+        }
+    }
+}
+
+/// the interface index of a neighbor or route message
+fn message_index(msg: &RtnlMessage) -> Option<u32> {
+    match msg {
+        RtnlMessage::NewNeighbour(v) => Some(v.header.ifindex),
+        RtnlMessage::NewRoute(v) => v.nlas.iter().find_map(|nla| match nla {
+            RouteNla::Oif(v) => Some(*v),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+fn handle_route_monitor_message(proc_tx: &Sender<ProcessRequest>, msg: RtnlMessage) {
+    // NOTE: neighbors and routes which are of no interest to us are silently skipped
+    match msg {
+        RtnlMessage::NewNeighbour(v) => {
+            if let Some(v) = Neighbor::from_message(v) {
+                let _ = proc_tx.send(ProcessRequest::NetlinkNeighborChanged(v));
+            }
+        }
+        RtnlMessage::DelNeighbour(v) => {
+            if let Some(mut v) = Neighbor::from_message(v) {
+                v.mac_address = None;
+                let _ = proc_tx.send(ProcessRequest::NetlinkNeighborChanged(v));
+            }
+        }
+        RtnlMessage::NewRoute(v) => {
+            if let Some(v) = Route::from_message(v) {
+                let _ = proc_tx.send(ProcessRequest::NetlinkRouteChanged(v));
+            }
+        }
+        RtnlMessage::DelRoute(v) => {
+            if let Some(v) = Route::from_message(v) {
+                let _ = proc_tx.send(ProcessRequest::NetlinkRouteRemoved(v));
+            }
+        }
+        v => {
+            log::warn!(
+                "netlink route monitor: received unexpected message from netlink socket: {v:?}"
+            );
+        }
+    }
+}
+
+/// a neighbor of a link, the MAC address is not set if the neighbor is not resolved (anymore)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Neighbor {
+    pub(crate) index: u32,
+    pub(crate) ip: IpAddr,
+    pub(crate) mac_address: Option<[u8; 6]>,
+}
+
+impl Neighbor {
+    /// converts a neighbor message, returns `None` for neighbors that we don't sync
+    fn from_message(nm: NeighbourMessage) -> Option<Self> {
+        let mut ip = None;
+        let mut mac_address = None;
+        for nla in nm.nlas.into_iter() {
+            match nla {
+                NeighbourNla::Destination(v) => ip = ip_from_bytes(&v),
+                NeighbourNla::LinkLocalAddress(v) => {
+                    mac_address = <[u8; 6]>::try_from(v.as_slice()).ok()
+                }
+                _ => {}
+            }
+        }
+        // NOTE: link-local neighbors are synced as well, as routes can use them as gateways
+        let ip = ip.filter(|ip| !ip.is_unspecified() && !ip.is_multicast())?;
+        if nm.header.state & (NUD_INCOMPLETE | NUD_FAILED) != 0 || nm.header.state == NUD_NONE {
+            mac_address = None;
+        }
+        Some(Self {
+            index: nm.header.ifindex,
+            ip: ip,
+            mac_address: mac_address,
+        })
+    }
+}
+
+/// a unicast route of the main routing table, the gateway is not set for connected routes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Route {
+    pub(crate) destination: IpNet,
+    pub(crate) gateway: Option<IpAddr>,
+    pub(crate) index: u32,
+}
+
+impl Route {
+    /// converts a route message, returns `None` for routes that we don't sync
+    /// NOTE: multipath routes are not supported, as we don't program next hop groups
+    fn from_message(rm: RouteMessage) -> Option<Self> {
+        if rm.header.kind != RTN_UNICAST {
+            return None;
+        }
+        let mut table = rm.header.table as u32;
+        let mut destination = None;
+        let mut gateway = None;
+        let mut index = None;
+        for nla in rm.nlas.into_iter() {
+            match nla {
+                RouteNla::Table(v) => table = v,
+                RouteNla::Destination(v) => destination = ip_from_bytes(&v),
+                RouteNla::Gateway(v) => gateway = ip_from_bytes(&v),
+                RouteNla::Oif(v) => index = Some(v),
+                RouteNla::MultiPath(_) => return None,
+                _ => {}
+            }
+        }
+        if table != RT_TABLE_MAIN as u32 {
+            return None;
+        }
+        // a default route has no destination attribute
+        let destination = match (destination, rm.header.address_family as i32) {
+            (Some(v), _) => v,
+            (None, libc::AF_INET) => IpAddr::from([0u8; 4]),
+            (None, libc::AF_INET6) => IpAddr::from([0u8; 16]),
+            (None, _) => return None,
+        };
+        if is_link_local(&destination) || destination.is_multicast() {
+            return None;
+        }
+        let destination = IpNet::new(destination, rm.header.destination_prefix_length).ok()?;
+        Some(Self {
+            destination: destination,
+            gateway: gateway,
+            index: index?,
+        })
+    }
+}
+
+fn ip_from_bytes(v: &[u8]) -> Option<IpAddr> {
+    match v.len() {
+        4 => <[u8; 4]>::try_from(v).ok().map(IpAddr::from),
+        16 => <[u8; 16]>::try_from(v).ok().map(IpAddr::from),
+        _ => None,
+    }
+}
+
+/// link-local addresses are the same on every link, so they cannot be programmed as routes, and
+/// neighbors with them must not get a host route
+pub(crate) fn is_link_local(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v) => v.is_link_local(),
+        IpAddr::V6(v) => (v.segments()[0] & 0xffc0) == 0xfe80,
+    }
+}
+
 pub(crate) fn get_interface_name(index: u32) -> Result<String, std::io::Error> {
     let mut buf = [0u8; libc::IFNAMSIZ];
     let ret = unsafe { libc::if_indextoname(index, buf.as_mut_ptr() as *mut libc::c_char) };
@@ -154,38 +526,39 @@ pub(crate) fn get_interface_index(name: &str) -> Result<u32, std::io::Error> {
     Ok(ret)
 }
 
-#[derive(Debug)]
-pub(crate) enum SetLinkError {
-    IOError(std::io::Error),
-    NetlinkDecodeError(DecodeError),
-    UnexpectedNetlinkMessage(u16),
-    NetlinkError(i32),
-}
+pub(crate) fn get_link(index: u32) -> Result<Link, SetLinkError> {
+    let mut socket = Socket::new(NETLINK_ROUTE)?;
+    let sock_addr = socket.bind_auto()?;
+    let port_number = sock_addr.port_number();
+    socket.connect(&SocketAddr::new(0, 0))?;
 
-impl std::error::Error for SetLinkError {}
+    // GET LINK
+    // build request
+    let mut hdr = NetlinkHeader::default();
+    hdr.flags = NLM_F_REQUEST;
+    hdr.port_number = port_number;
+    let mut lm = LinkMessage::default();
+    lm.header.index = index;
+    let mut req = NetlinkMessage::new(hdr, NetlinkPayload::from(RtnlMessage::GetLink(lm)));
+    req.finalize();
 
-impl std::fmt::Display for SetLinkError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SetLinkError::IOError(e) => write!(f, "IO error: {}", e),
-            SetLinkError::NetlinkDecodeError(e) => write!(f, "netlink decoding error: {}", e),
-            SetLinkError::UnexpectedNetlinkMessage(v) => {
-                write!(f, "unexpected netlink message received: {}", v)
-            }
-            SetLinkError::NetlinkError(v) => write!(f, "netlink error: {}", v),
-        }
-    }
-}
+    // serialize the request
+    let mut buf = vec![0u8; req.header.length as usize];
+    req.serialize(buf.as_mut_slice());
 
-impl From<std::io::Error> for SetLinkError {
-    fn from(e: std::io::Error) -> Self {
-        SetLinkError::IOError(e)
-    }
-}
+    // send the request
+    let _ = socket.send(buf.as_slice(), 0)?;
 
-impl From<DecodeError> for SetLinkError {
-    fn from(e: DecodeError) -> Self {
-        SetLinkError::NetlinkDecodeError(e)
+    // receive the response
+    let mut buf = vec![0u8; 8192];
+    let _ = socket.recv(&mut &mut buf[..], 0)?;
+
+    // deserialize the response
+    let resp = <NetlinkMessage<RtnlMessage>>::deserialize(buf.as_slice())?;
+    match resp.payload {
+        NetlinkPayload::InnerMessage(RtnlMessage::GetLink(v)) => Ok(v.into()),
+        NetlinkPayload::InnerMessage(RtnlMessage::NewLink(v)) => Ok(v.into()),
+        v => Err(SetLinkError::UnexpectedNetlinkMessage(v.message_type())),
     }
 }
 
@@ -223,7 +596,7 @@ pub(crate) fn set_link_status(index: u32, oper_status: bool) -> Result<(), SetLi
         NetlinkPayload::InnerMessage(RtnlMessage::GetLink(v)) => v,
         NetlinkPayload::InnerMessage(RtnlMessage::NewLink(v)) => v,
         v => {
-            println!("{v:?}");
+            log::debug!("get link: unexpected netlink message: {v:?}");
             return Err(SetLinkError::UnexpectedNetlinkMessage(v.message_type()));
         }
     };
@@ -248,6 +621,67 @@ pub(crate) fn set_link_status(index: u32, oper_status: bool) -> Result<(), SetLi
     }
     let mut req = NetlinkMessage::new(hdr, NetlinkPayload::from(RtnlMessage::SetLink(lm)));
     req.finalize();
+    send_and_ack(&socket, req)
+}
+
+/// sets the MAC address of the interface
+/// NOTE: most drivers only allow this while the interface is down
+pub(crate) fn set_link_mac_address(index: u32, mac_address: [u8; 6]) -> Result<(), SetLinkError> {
+    let mut socket = Socket::new(NETLINK_ROUTE)?;
+    let sock_addr = socket.bind_auto()?;
+    let port_number = sock_addr.port_number();
+    socket.connect(&SocketAddr::new(0, 0))?;
+
+    // SET LINK - only the address attribute gets changed
+    let mut hdr = NetlinkHeader::default();
+    hdr.flags = NLM_F_REQUEST | NLM_F_ACK;
+    hdr.port_number = port_number;
+    let mut lm = LinkMessage::default();
+    lm.header.index = index;
+    lm.nlas.push(LinkNla::Address(mac_address.to_vec()));
+    let mut req = NetlinkMessage::new(hdr, NetlinkPayload::from(RtnlMessage::SetLink(lm)));
+    req.finalize();
+    send_and_ack(&socket, req)
+}
+
+/// sets the MTU of the interface
+pub(crate) fn set_link_mtu(index: u32, mtu: u32) -> Result<(), SetLinkError> {
+    let mut lm = LinkMessage::default();
+    lm.header.index = index;
+    lm.nlas.push(LinkNla::Mtu(mtu));
+    request_and_ack(RtnlMessage::SetLink(lm), 0)
+}
+
+/// adds the address and all routes of the network config to the interface
+pub(crate) fn add_network_config(index: u32, config: &NetworkConfig) -> Result<(), SetLinkError> {
+    add_address_and_routes(index, &config.ip, &network_config_routes(config))
+}
+
+/// removes all routes and the address of the network config from the interface
+pub(crate) fn del_network_config(index: u32, config: &NetworkConfig) -> Result<(), SetLinkError> {
+    del_address_and_routes(index, &config.ip, &network_config_routes(config))
+}
+
+fn network_config_routes(config: &NetworkConfig) -> Vec<(IpNet, IpAddr)> {
+    config
+        .routes
+        .iter()
+        .flat_map(|route| route.destinations.iter().map(|dst| (*dst, route.gateway)))
+        .collect()
+}
+
+/// sends a dump request on a new socket, and collects all messages until the dump is done
+fn dump(msg: RtnlMessage) -> Result<Vec<RtnlMessage>, SetLinkError> {
+    let mut socket = Socket::new(NETLINK_ROUTE)?;
+    let sock_addr = socket.bind_auto()?;
+    let port_number = sock_addr.port_number();
+    socket.connect(&SocketAddr::new(0, 0))?;
+
+    let mut hdr = NetlinkHeader::default();
+    hdr.flags = NLM_F_REQUEST | NLM_F_DUMP;
+    hdr.port_number = port_number;
+    let mut req = NetlinkMessage::new(hdr, NetlinkPayload::from(msg));
+    req.finalize();
 
     // serialize the request
     let mut buf = vec![0u8; req.header.length as usize];
@@ -256,18 +690,232 @@ pub(crate) fn set_link_status(index: u32, oper_status: bool) -> Result<(), SetLi
     // send the request
     let _ = socket.send(buf.as_slice(), 0)?;
 
-    // receive the response
-    let mut buf = vec![0u8; 4096];
-    let _ = socket.recv(&mut &mut buf[..], 0)?;
+    // receive the responses: a dump spans multiple receive calls which can contain multiple messages
+    let mut ret = Vec::new();
+    let mut buf = vec![0u8; 32768];
+    loop {
+        let size = socket.recv(&mut &mut buf[..], 0)?;
+        let mut off = 0;
+        while off < size {
+            let resp = <NetlinkMessage<RtnlMessage>>::deserialize(&buf[off..size])?;
+            match resp.payload {
+                NetlinkPayload::Done(_) => return Ok(ret),
+                NetlinkPayload::InnerMessage(v) => ret.push(v),
+                NetlinkPayload::Error(err_msg) => {
+                    if let Some(code) = err_msg.code {
+                        return Err(SetLinkError::NetlinkError(code.into()));
+                    }
+                }
+                v => return Err(SetLinkError::UnexpectedNetlinkMessage(v.message_type())),
+            }
+            if resp.header.length == 0 {
+                break;
+            }
+            off += resp.header.length as usize;
+        }
+    }
+}
 
-    // deserialize the response
-    // we are expecting an NLMSG_ERROR message without an error (which equals an ACK)
-    let resp = <NetlinkMessage<RtnlMessage>>::deserialize(&buf.as_slice())?;
-    match resp.payload {
-        NetlinkPayload::Error(err_msg) => match err_msg.code {
-            None => Ok(()),
-            Some(code) => Err(SetLinkError::NetlinkError(code.into())),
-        },
-        v => Err(SetLinkError::UnexpectedNetlinkMessage(v.message_type())),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use netlink_packet_route::NUD_REACHABLE;
+    use netlink_packet_route::NUD_STALE;
+    use netlink_packet_route::RTN_LOCAL;
+    use netlink_packet_route::RT_TABLE_LOCAL;
+
+    fn route_message(destination: Option<IpNet>, gateway: Option<IpAddr>) -> RouteMessage {
+        let mut rm = RouteMessage::default();
+        rm.header.address_family = libc::AF_INET as u8;
+        rm.header.table = RT_TABLE_MAIN;
+        rm.header.kind = RTN_UNICAST;
+        if let Some(destination) = destination {
+            rm.header.destination_prefix_length = destination.prefix_len();
+            rm.nlas
+                .push(RouteNla::Destination(match destination.addr() {
+                    IpAddr::V4(v) => v.octets().to_vec(),
+                    IpAddr::V6(v) => v.octets().to_vec(),
+                }));
+        }
+        if let Some(IpAddr::V4(gateway)) = gateway {
+            rm.nlas.push(RouteNla::Gateway(gateway.octets().to_vec()));
+        }
+        rm.nlas.push(RouteNla::Oif(3));
+        rm
+    }
+
+    fn neighbor_message(ip: IpAddr, state: u16) -> NeighbourMessage {
+        let mut nm = NeighbourMessage::default();
+        nm.header.ifindex = 3;
+        nm.header.state = state;
+        nm.nlas.push(NeighbourNla::Destination(match ip {
+            IpAddr::V4(v) => v.octets().to_vec(),
+            IpAddr::V6(v) => v.octets().to_vec(),
+        }));
+        nm.nlas
+            .push(NeighbourNla::LinkLocalAddress(vec![2, 0, 0, 0, 0, 1]));
+        nm
+    }
+
+    #[test]
+    fn route_from_message() {
+        let rm = route_message(
+            Some("10.1.0.0/16".parse().unwrap()),
+            Some("192.168.1.1".parse().unwrap()),
+        );
+        assert_eq!(
+            Route::from_message(rm),
+            Some(Route {
+                destination: "10.1.0.0/16".parse().unwrap(),
+                gateway: Some("192.168.1.1".parse().unwrap()),
+                index: 3,
+            })
+        );
+
+        // connected route
+        let rm = route_message(Some("192.168.1.0/24".parse().unwrap()), None);
+        assert_eq!(
+            Route::from_message(rm),
+            Some(Route {
+                destination: "192.168.1.0/24".parse().unwrap(),
+                gateway: None,
+                index: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn route_from_message_default_route() {
+        // a default route has no destination attribute
+        let rm = route_message(None, Some("192.168.1.1".parse().unwrap()));
+        assert_eq!(
+            Route::from_message(rm),
+            Some(Route {
+                destination: "0.0.0.0/0".parse().unwrap(),
+                gateway: Some("192.168.1.1".parse().unwrap()),
+                index: 3,
+            })
+        );
+
+        let mut rm = route_message(None, None);
+        rm.header.address_family = libc::AF_INET6 as u8;
+        assert_eq!(
+            Route::from_message(rm).map(|v| v.destination),
+            Some("::/0".parse().unwrap())
+        );
+
+        let mut rm = route_message(None, None);
+        rm.header.address_family = libc::AF_UNSPEC as u8;
+        assert_eq!(Route::from_message(rm), None);
+    }
+
+    #[test]
+    fn route_from_message_other_tables() {
+        let mut rm = route_message(Some("10.1.0.0/16".parse().unwrap()), None);
+        rm.header.table = RT_TABLE_LOCAL;
+        assert_eq!(Route::from_message(rm), None);
+
+        // the table attribute takes precedence over the header for table IDs above 255
+        let mut rm = route_message(Some("10.1.0.0/16".parse().unwrap()), None);
+        rm.nlas.push(RouteNla::Table(1000));
+        assert_eq!(Route::from_message(rm), None);
+
+        let mut rm = route_message(Some("10.1.0.0/16".parse().unwrap()), None);
+        rm.header.table = 0;
+        rm.nlas.push(RouteNla::Table(RT_TABLE_MAIN as u32));
+        assert!(Route::from_message(rm).is_some());
+
+        // only unicast routes
+        let mut rm = route_message(Some("10.1.0.0/16".parse().unwrap()), None);
+        rm.header.kind = RTN_LOCAL;
+        assert_eq!(Route::from_message(rm), None);
+    }
+
+    #[test]
+    fn route_from_message_multipath() {
+        let mut rm = route_message(Some("10.1.0.0/16".parse().unwrap()), None);
+        rm.nlas.push(RouteNla::MultiPath(vec![]));
+        assert_eq!(Route::from_message(rm), None);
+    }
+
+    #[test]
+    fn route_from_message_link_local_and_multicast() {
+        let rm = route_message(Some("169.254.0.0/16".parse().unwrap()), None);
+        assert_eq!(Route::from_message(rm), None);
+
+        let mut rm = route_message(Some("fe80::/64".parse().unwrap()), None);
+        rm.header.address_family = libc::AF_INET6 as u8;
+        assert_eq!(Route::from_message(rm), None);
+
+        let rm = route_message(Some("224.0.0.0/4".parse().unwrap()), None);
+        assert_eq!(Route::from_message(rm), None);
+    }
+
+    #[test]
+    fn route_from_message_without_interface() {
+        let mut rm = route_message(Some("10.1.0.0/16".parse().unwrap()), None);
+        rm.nlas.retain(|nla| !matches!(nla, RouteNla::Oif(_)));
+        assert_eq!(Route::from_message(rm), None);
+    }
+
+    #[test]
+    fn neighbor_from_message() {
+        let nm = neighbor_message("192.168.1.1".parse().unwrap(), NUD_REACHABLE);
+        assert_eq!(
+            Neighbor::from_message(nm),
+            Some(Neighbor {
+                index: 3,
+                ip: "192.168.1.1".parse().unwrap(),
+                mac_address: Some([2, 0, 0, 0, 0, 1]),
+            })
+        );
+
+        // a stale neighbor is still resolved
+        let nm = neighbor_message("192.168.1.1".parse().unwrap(), NUD_STALE);
+        assert!(Neighbor::from_message(nm).unwrap().mac_address.is_some());
+    }
+
+    #[test]
+    fn neighbor_from_message_unresolved() {
+        for state in [NUD_INCOMPLETE, NUD_FAILED, NUD_NONE] {
+            let nm = neighbor_message("192.168.1.1".parse().unwrap(), state);
+            assert_eq!(
+                Neighbor::from_message(nm),
+                Some(Neighbor {
+                    index: 3,
+                    ip: "192.168.1.1".parse().unwrap(),
+                    mac_address: None,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn neighbor_from_message_link_local() {
+        // link-local neighbors are synced, as they can be the gateways of routes
+        let nm = neighbor_message("fe80::1".parse().unwrap(), NUD_REACHABLE);
+        assert_eq!(
+            Neighbor::from_message(nm).map(|v| v.ip),
+            Some("fe80::1".parse().unwrap())
+        );
+
+        let nm = neighbor_message("ff02::1".parse().unwrap(), NUD_REACHABLE);
+        assert_eq!(Neighbor::from_message(nm), None);
+
+        let nm = neighbor_message("0.0.0.0".parse().unwrap(), NUD_REACHABLE);
+        assert_eq!(Neighbor::from_message(nm), None);
+    }
+
+    #[test]
+    fn message_index_of_neighbors_and_routes() {
+        let nm = neighbor_message("192.168.1.1".parse().unwrap(), NUD_REACHABLE);
+        assert_eq!(message_index(&RtnlMessage::NewNeighbour(nm)), Some(3));
+
+        let rm = route_message(Some("10.1.0.0/16".parse().unwrap()), None);
+        assert_eq!(message_index(&RtnlMessage::NewRoute(rm)), Some(3));
+
+        let mut rm = route_message(Some("10.1.0.0/16".parse().unwrap()), None);
+        rm.nlas.clear();
+        assert_eq!(message_index(&RtnlMessage::NewRoute(rm)), None);
     }
 }
@@ -1,12 +1,16 @@
 pub(crate) mod discovery;
+pub(crate) mod hostif_naming;
+pub(crate) mod mac;
+pub(crate) mod media_settings;
+pub(crate) mod route_sync;
+pub(crate) mod sonic_config;
 
-use std::fs::File;
-use std::io::Read;
-use std::path::PathBuf;
 use std::sync::mpsc::Sender;
 use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
 
+use macaddr::MacAddr6;
 use onie_sai_rpc::wrap_message_field;
 use sai::hostif::HostIfAttribute;
 use sai::hostif::HostIfType;
@@ -20,17 +24,31 @@ use sai::virtual_router::VirtualRouter;
 use sai::ObjectID;
 use thiserror::Error;
 
-use serde::{Deserialize, Serialize};
-
 use sai::hostif::HostIf;
 use sai::port::BreakoutModeType;
+use sai::port::FECMode;
 use sai::port::Port;
+use sai::port::PortAttribute;
 
 use crate::lldp::LLDPSocket;
 use crate::lldp::LLDPTLVs;
 use crate::lldp::NetworkConfig;
 use crate::processor::netlink;
 
+use self::discovery::hint::DiscoveryHint;
+use self::discovery::history::DiscoveryHistory;
+use self::discovery::profile::DiscoveryProfile;
+use self::discovery::profile::DiscoveryStrategy;
+use self::hostif_naming::HostIfNameValues;
+use self::hostif_naming::HostIfNaming;
+use self::mac::PortMacMode;
+use self::mac::PortMacSettings;
+use self::media_settings::MediaSettings;
+use self::route_sync::RouteSync;
+use super::reconcile::object_missing;
+use super::reconcile::Drift;
+use super::warm_boot::LogicalPortState;
+use super::warm_boot::PhysicalPortState;
 use super::PlatformContextHolder;
 use super::ProcessRequest;
 
@@ -50,6 +68,27 @@ pub(crate) enum PortError {
 
     #[error("port config unavailable")]
     PortConfigUnavailable,
+
+    #[error("port {0:#x} from warm boot state not found on switch")]
+    WarmBootPortNotFound(u64),
+
+    #[error("physical port {0} has no logical ports in warm boot state")]
+    WarmBootNoPorts(usize),
+
+    #[error("failed to calculate logical ports for breakout mode: {0}")]
+    BreakoutCalculation(discovery::physicalport::CalcError),
+
+    #[error("physical port {0} does not support breakout mode {1}")]
+    BreakoutModeUnsupported(usize, BreakoutModeType),
+
+    #[error("physical port {0} has no logical port {1}")]
+    NoSuchLogicalPort(usize, usize),
+
+    #[error("port {0} does not support speed {1}")]
+    SpeedUnsupported(String, u32),
+
+    #[error("physical port {0} does not support speed {1} in breakout mode {2}: {3}")]
+    SpeedUnsupportedForBreakoutMode(usize, u32, BreakoutModeType, String),
 }
 
 impl From<sai::Error> for PortError {
@@ -64,67 +103,93 @@ impl From<xcvr::Error> for PortError {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub(crate) struct PhysicalPortConfigSpeed {
-    #[serde(rename = "FourLanes")]
-    four_lanes: Option<u32>,
-    #[serde(rename = "TwoLanes")]
-    two_lanes: Option<u32>,
-    #[serde(rename = "OneLane")]
-    one_lane: Option<u32>,
+impl From<discovery::physicalport::CalcError> for PortError {
+    fn from(value: discovery::physicalport::CalcError) -> Self {
+        PortError::BreakoutCalculation(value)
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub(crate) struct PhysicalPortConfig {
-    pub(crate) lanes: Vec<u32>,
-    pub(crate) speed: PhysicalPortConfigSpeed,
+use onie_sai_common::port_config::check::check_speed;
+use onie_sai_common::port_config::same_lanes;
+pub(crate) use onie_sai_common::port_config::PhysicalPortConfig;
+pub(crate) use onie_sai_common::port_config::PhysicalPortConfigBreakoutMode;
+pub(crate) use onie_sai_common::port_config::PhysicalPortConfigFECMode;
+pub(crate) use onie_sai_common::port_config::PhysicalPortConfigSpeed;
+
+/// the MTU of ports and router interfaces if nothing else is configured
+const DEFAULT_MTU: u32 = 9100;
+
+/// converts the breakout mode of a port config into the SAI breakout mode type
+pub(crate) fn breakout_mode_type(mode: PhysicalPortConfigBreakoutMode) -> BreakoutModeType {
+    match mode {
+        PhysicalPortConfigBreakoutMode::OneLane => BreakoutModeType::OneLane,
+        PhysicalPortConfigBreakoutMode::TwoLanes => BreakoutModeType::TwoLanes,
+        PhysicalPortConfigBreakoutMode::FourLanes => BreakoutModeType::FourLanes,
+        PhysicalPortConfigBreakoutMode::EightLanes => BreakoutModeType::EightLanes,
+    }
 }
 
-impl PhysicalPortConfig {
-    #[allow(dead_code)]
-    pub(crate) fn get_default_speed(&self) -> u32 {
-        match self.lanes.len() {
-            1 => match self.speed.one_lane {
-                Some(v) => v,
-                None => {
-                    log::error!(
-                        "Physical Port Config: missing OneLane speed configuration. Returning 10000.",
-                    );
-                    10000
-                }
-            },
-            2 => match self.speed.two_lanes {
-                Some(v) => v,
-                None => {
-                    log::error!(
-                        "Physical Port Config: missing TwoLanes speed configuration. Returning 50000.",
-                    );
-                    50000
-                }
+/// converts the FEC mode of a port config into the SAI FEC mode
+pub(crate) fn fec_mode(mode: PhysicalPortConfigFECMode) -> FECMode {
+    match mode {
+        PhysicalPortConfigFECMode::None => FECMode::None,
+        PhysicalPortConfigFECMode::RS => FECMode::RS,
+        PhysicalPortConfigFECMode::FC => FECMode::FC,
+    }
+}
+
+/// The parts of the port config which need SAI or port discovery. The port config itself lives in
+/// onie-sai-common, so that onie-saictl can check it without SAI.
+pub(crate) trait PhysicalPortConfigExt {
+    fn logical_port_settings(&self) -> LogicalPortSettings;
+    fn discovery_profile(&self, profile: Arc<DiscoveryProfile>) -> Arc<DiscoveryProfile>;
+    fn get_speed_for_breakout_mode_type(&self, breakout_mode_type: BreakoutModeType) -> u32;
+    fn get_supported_breakout_mode_types(&self) -> Vec<BreakoutModeType>;
+    fn get_current_breakout_mode_type(&self) -> BreakoutModeType;
+    fn get_breakout_type_from_lanes(lanes: Vec<u32>) -> BreakoutModeType;
+}
+
+impl PhysicalPortConfigExt for PhysicalPortConfig {
+    /// the settings which get applied to all logical ports of the port
+    fn logical_port_settings(&self) -> LogicalPortSettings {
+        LogicalPortSettings {
+            admin_state: if self.admin_down {
+                Some(false)
+            } else if !self.discovery_enabled() {
+                Some(true)
+            } else {
+                None
             },
-            4 => match self.speed.four_lanes {
-                Some(v) => v,
-                None => {
-                    log::error!(
-                        "Physical Port Config: missing FourLanes speed configuration. Returning 100000.",
-                    );
-                    100000
+            speed: self.fixed_speed,
+            auto_negotiation: self.auto_neg,
+            fec: self.fec.map(fec_mode),
+        }
+    }
+
+    /// adjusts the discovery profile for the settings which are pinned for this port
+    /// NOTE: the profile is shared between all ports, so we only copy it if we need to change it
+    fn discovery_profile(&self, profile: Arc<DiscoveryProfile>) -> Arc<DiscoveryProfile> {
+        if self.fec.is_none() && self.auto_neg.is_none() {
+            return profile;
+        }
+        let mut ret = profile.as_ref().clone();
+        if self.fec.is_some() {
+            ret.fec = false;
+        }
+        match self.auto_neg {
+            Some(true) => ret.strategies = vec![DiscoveryStrategy::AutoNeg],
+            Some(false) => {
+                ret.strategies.retain(|s| *s != DiscoveryStrategy::AutoNeg);
+                if ret.strategies.is_empty() {
+                    ret.strategies = vec![DiscoveryStrategy::Speed];
                 }
-            },
-            _ => {
-                log::error!(
-                    "Physical Port Config: invalid number of lanes: {}. Returning 0.",
-                    self.lanes.len()
-                );
-                0
             }
+            None => {}
         }
+        Arc::new(ret)
     }
 
-    pub(crate) fn get_speed_for_breakout_mode_type(
-        &self,
-        breakout_mode_type: BreakoutModeType,
-    ) -> u32 {
+    fn get_speed_for_breakout_mode_type(&self, breakout_mode_type: BreakoutModeType) -> u32 {
         match breakout_mode_type {
             BreakoutModeType::OneLane => match self.speed.one_lane {
                 Some(v) => v,
@@ -153,6 +218,15 @@ impl PhysicalPortConfig {
                     100000
                 }
             },
+            BreakoutModeType::EightLanes => match self.speed.eight_lanes {
+                Some(v) => v,
+                None => {
+                    log::error!(
+                        "Physical Port Config: missing EightLanes speed configuration. Returning 400000.",
+                    );
+                    400000
+                }
+            },
             _ => {
                 log::error!(
                     "Physical Port Config: invalid breakout mode type: {:?}. Returning 10000.",
@@ -163,73 +237,29 @@ impl PhysicalPortConfig {
         }
     }
 
-    // when the supported breakout mode type SAI property is not available
-    // we will use this function to guess the available breakout modes
-    // NOTE: on Broadcom SAI, this can only work if all possible breakouts
-    // have actually been defined as inactive ports in the config.bcm (or through port flex)
-    pub(crate) fn get_supported_breakout_mode_types(&self) -> Vec<BreakoutModeType> {
-        match self.lanes.len() {
-            1 => {
-                vec![BreakoutModeType::OneLane]
-            }
-            2 => {
-                vec![BreakoutModeType::OneLane, BreakoutModeType::TwoLanes]
-            }
-            4 => {
-                vec![
-                    BreakoutModeType::OneLane,
-                    BreakoutModeType::TwoLanes,
-                    BreakoutModeType::FourLanes,
-                ]
-            }
-            _ => {
-                log::error!("Physical Port Config: invalid number of lanes: {}. Returning no breakout types.", self.lanes.len());
-                vec![]
-            }
-        }
+    fn get_supported_breakout_mode_types(&self) -> Vec<BreakoutModeType> {
+        self.get_supported_breakout_modes()
+            .into_iter()
+            .map(breakout_mode_type)
+            .collect()
     }
 
-    pub(crate) fn get_current_breakout_mode_type(&self) -> BreakoutModeType {
+    fn get_current_breakout_mode_type(&self) -> BreakoutModeType {
         Self::get_breakout_type_from_lanes(self.lanes.clone())
     }
 
-    pub(crate) fn get_breakout_type_from_lanes(lanes: Vec<u32>) -> BreakoutModeType {
+    fn get_breakout_type_from_lanes(lanes: Vec<u32>) -> BreakoutModeType {
         match lanes.len() {
             1 => BreakoutModeType::OneLane,
             2 => BreakoutModeType::TwoLanes,
             4 => BreakoutModeType::FourLanes,
+            8 => BreakoutModeType::EightLanes,
             _ => {
                 log::error!("Physical Port Config: invalid number of lanes: {}. Returning unknown breakout type", lanes.len());
                 BreakoutModeType::Unknown(lanes.len() as i32)
             }
         }
     }
-
-    pub(crate) fn from_file(path: &PathBuf) -> Option<Vec<PhysicalPortConfig>> {
-        let mut file = match File::open(path) {
-            Ok(file) => file,
-            Err(e) => {
-                log::error!("failed to open physical port config file: {:?}", e);
-                return None;
-            }
-        };
-        let mut contents = String::new();
-        match file.read_to_string(&mut contents) {
-            Ok(_) => {}
-            Err(e) => {
-                log::error!("failed to read physical port config file: {:?}", e);
-                return None;
-            }
-        };
-        let config: Vec<PhysicalPortConfig> = match serde_json::from_str(&contents) {
-            Ok(config) => config,
-            Err(e) => {
-                log::error!("failed to parse physical port config file: {:?}", e);
-                return None;
-            }
-        };
-        Some(config)
-    }
 }
 
 pub(crate) trait SortPortsByLanes<'a> {
@@ -246,7 +276,7 @@ impl<'a> SortPortsByLanes<'a> for Vec<PhysicalPortConfig> {
             let mut found = false;
             for port in ports.iter() {
                 let lanes = port.get_hw_lanes()?;
-                if lanes == pc.lanes {
+                if same_lanes(&lanes, &pc.lanes) {
                     found = true;
                     ret.push(port.clone());
                 }
@@ -259,6 +289,15 @@ impl<'a> SortPortsByLanes<'a> for Vec<PhysicalPortConfig> {
     }
 }
 
+/// Settings which get applied manually to a logical port. Everything which is not set stays as it is.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LogicalPortSettings {
+    pub(crate) admin_state: Option<bool>,
+    pub(crate) speed: Option<u32>,
+    pub(crate) auto_negotiation: Option<bool>,
+    pub(crate) fec: Option<FECMode>,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct PhysicalPort<'a, 'b> {
     xcvr_api: PlatformContextHolder<'b>,
@@ -268,6 +307,8 @@ pub(crate) struct PhysicalPort<'a, 'b> {
     pub(crate) ports: Vec<LogicalPort<'a>>,
     pub(crate) lanes: Vec<u32>,
     pub(crate) mac_address: sai_mac_t,
+    pub(crate) mac_settings: Arc<PortMacSettings>,
+    pub(crate) mac_slot: u64,
     pub(crate) auto_discovery: bool,
     pub(crate) auto_discovery_with_breakout: bool,
     pub(crate) auto_discovery_counter: u64,
@@ -277,10 +318,19 @@ pub(crate) struct PhysicalPort<'a, 'b> {
     pub(crate) xcvr_oper_status: Option<bool>,
     pub(crate) xcvr_inserted_type: Option<xcvr::PortType>,
     pub(crate) xcvr_supported_types: Vec<xcvr::PortType>,
+    pub(crate) label: Option<String>,
     pub(crate) sm: Option<discovery::physicalport::DiscoveryStateMachine>,
     pub(crate) oper_status: bool,
     pub(crate) port_config: Option<PhysicalPortConfig>,
+    pub(crate) media_settings: Option<Arc<MediaSettings>>,
+    pub(crate) discovery_profile: Arc<DiscoveryProfile>,
+    pub(crate) hostif_naming: Arc<HostIfNaming>,
+    pub(crate) discovery_hint: Option<DiscoveryHint>,
+    pub(crate) discovery_history: DiscoveryHistory,
     pub(crate) initial_port_discovery: Option<()>,
+    /// if the logical ports should have host interfaces and router interfaces: this is the desired
+    /// state that the reconciliation loop repairs them to
+    pub(crate) hifs_and_rifs: bool,
 }
 
 // just a convenience conversion method for our RPC
@@ -298,6 +348,11 @@ impl From<&PhysicalPort<'_, '_>> for onie_sai_rpc::onie_sai::Port {
             .iter()
             .map(|t| format!("{:?}", t))
             .collect();
+        ret.name = port
+            .port_config
+            .as_ref()
+            .and_then(|pc| pc.alias.clone())
+            .or_else(|| port.label.clone());
         let mut ports: Vec<onie_sai_rpc::onie_sai::LogicalPort> =
             Vec::with_capacity(port.ports.len());
         for p in &port.ports {
@@ -316,10 +371,18 @@ impl From<&PhysicalPort<'_, '_>> for onie_sai_rpc::onie_sai::Port {
             ret_p.oper_speed = p.oper_speed;
             ret_p.supported_speeds = p.supported_speeds.clone();
             ret_p.auto_neg = p.auto_negotiation;
+            ret_p.fec = format!("{:?}", p.fec);
             ret_p.host_intf = wrap_message_field(hif);
+            ret_p.discovery_state = p.sm.as_ref().map(|sm| sm.to_string());
+            ret_p.discovery_attempts = p.discovery_history.attempts();
+            ret_p.discovery_history = p.discovery_history.entries().map(|e| e.into()).collect();
             ports.push(ret_p);
         }
         ret.ports = ports;
+        ret.auto_discovery = port.auto_discovery;
+        ret.auto_discovery_counter = port.auto_discovery_counter;
+        ret.discovery_state = port.sm.as_ref().map(|sm| sm.to_string());
+        ret.discovery_history = port.discovery_history.entries().map(|e| e.into()).collect();
         ret
     }
 }
@@ -330,67 +393,19 @@ impl<'a, 'b> PhysicalPort<'a, 'b> {
         switch: Switch<'a>,
         router: VirtualRouter<'a>,
         mac_address: sai_mac_t,
+        mac_settings: Arc<PortMacSettings>,
+        mac_slot: u64,
         physical_port_index: usize,
         port: Port<'a>,
         port_config: Option<PhysicalPortConfig>,
+        media_settings: Option<Arc<MediaSettings>>,
+        discovery_profile: Arc<DiscoveryProfile>,
+        hostif_naming: Arc<HostIfNaming>,
     ) -> Result<PhysicalPort<'a, 'b>, PortError> {
         // get the transceiver state first
-        // NOTE: we are lenient here with xcvr state, and let nothing here fail.
-        // If the presence check fails, we assume the port is present.
-        let xcvr_present = match xcvr_api.obj.get_presence(physical_port_index as u16) {
-            Ok(v) => v,
-            Err(e) => {
-                log::warn!("Physical Port {}: transceiver presence check failed: {:?}. Assuming transceiver is present.", physical_port_index, e);
-                true
-            }
-        };
-        let xcvr_oper_status = if xcvr_present {
-            match xcvr_api.obj.get_oper_status(physical_port_index as u16) {
-                Ok(v) => Some(v),
-                Err(e) => {
-                    log::warn!(
-                        "Physical Port {}: transceiver oper status check failed: {:?}",
-                        physical_port_index,
-                        e
-                    );
-                    None
-                }
-            }
-        } else {
-            None
-        };
-        let xcvr_inserted_type = if xcvr_present {
-            match xcvr_api
-                .obj
-                .get_inserted_port_type(physical_port_index as u16)
-            {
-                Ok(v) => Some(v),
-                Err(e) => {
-                    log::warn!(
-                        "Physical Port {}: transceiver inserted port type check failed: {:?}",
-                        physical_port_index,
-                        e
-                    );
-                    None
-                }
-            }
-        } else {
-            None
-        };
-        let xcvr_supported_types = match xcvr_api
-            .obj
-            .get_supported_port_types(physical_port_index as u16)
-        {
-            Ok(v) => v,
-            Err(e) => {
-                log::warn!(
-                    "Physical Port {}: transceiver supported port types check failed: {:?}",
-                    physical_port_index,
-                    e
-                );
-                vec![]
-            }
-        };
+        let (xcvr_present, xcvr_oper_status, xcvr_inserted_type, xcvr_supported_types) =
+            xcvr_initial_state(&xcvr_api, physical_port_index);
+        let label = xcvr_port_label(&xcvr_api, physical_port_index);
 
         // get the port attributes that we need for initialization
         // let oper_status = port.get_oper_status()?;
@@ -408,21 +423,14 @@ impl<'a, 'b> PhysicalPort<'a, 'b> {
                 }
             }
         };
-        let supported_breakout_modes = match port.get_supported_breakout_modes() {
-            Ok(v) => v,
-            Err(e) => {
-                log::warn!("Physical Port {}: failed to get supported breakout modes: {:?}. Guessing supported breakout modes from port config", physical_port_index, e);
-                match &port_config {
-                    Some(pc) => pc.get_supported_breakout_mode_types(),
-                    None => {
-                        log::error!("Physical Port {}: no port config available. Port config must be available if it cannot be queried through SAI", physical_port_index);
-                        return Err(PortError::PortConfigUnavailable);
-                    }
-                }
-            }
+        let supported_breakout_modes =
+            get_supported_breakout_modes(physical_port_index, &port, &port_config)?;
+        let discovery_profile = match &port_config {
+            Some(pc) => pc.discovery_profile(discovery_profile),
+            None => discovery_profile,
         };
 
-        Ok(PhysicalPort {
+        let mut ret = PhysicalPort {
             xcvr_api: xcvr_api.clone(),
             switch: switch.clone(),
             router: router.clone(),
@@ -430,6 +438,7 @@ impl<'a, 'b> PhysicalPort<'a, 'b> {
             xcvr_inserted_type: xcvr_inserted_type,
             xcvr_oper_status: xcvr_oper_status,
             xcvr_supported_types: xcvr_supported_types,
+            label: label,
             idx: physical_port_index,
             auto_discovery: false,
             auto_discovery_with_breakout: false,
@@ -439,16 +448,148 @@ impl<'a, 'b> PhysicalPort<'a, 'b> {
             oper_status: false,
             lanes: hw_lanes.clone(),
             mac_address,
+            mac_settings: mac_settings.clone(),
+            mac_slot,
             current_breakout_mode: current_breakout_mode,
             supported_breakout_modes: supported_breakout_modes,
             port_config: port_config,
+            media_settings: media_settings,
+            discovery_profile: discovery_profile,
+            hostif_naming: hostif_naming,
+            discovery_hint: None,
+            discovery_history: DiscoveryHistory::default(),
+            hifs_and_rifs: false,
             ports: vec![LogicalPort::new(
                 switch.clone(),
                 router.clone(),
-                hw_lanes,
-                mac_address,
+                hw_lanes.clone(),
+                logical_port_mac_address(
+                    physical_port_index,
+                    &mac_settings,
+                    mac_address,
+                    mac_slot,
+                    &hw_lanes,
+                    &hw_lanes,
+                ),
                 port,
             )?],
+        };
+
+        // a transceiver which is already present counts as inserted
+        if ret.xcvr_present {
+            ret.apply_media_settings();
+        }
+
+        ret.apply_port_config();
+        Ok(ret)
+    }
+
+    /// restores a physical port from the warm boot state: the logical ports of it are looked up
+    /// from the list of ports from the switch instead of being created, and if discovery was
+    /// finished for the port before, the discovery state machines are restored as done
+    pub(crate) fn from_warm_boot_state(
+        xcvr_api: PlatformContextHolder<'b>,
+        switch: Switch<'a>,
+        router: VirtualRouter<'a>,
+        mac_address: sai_mac_t,
+        mac_settings: Arc<PortMacSettings>,
+        mac_slot: u64,
+        state: &PhysicalPortState,
+        switch_ports: &Vec<Port<'a>>,
+        port_config: Option<PhysicalPortConfig>,
+        media_settings: Option<Arc<MediaSettings>>,
+        discovery_profile: Arc<DiscoveryProfile>,
+        hostif_naming: Arc<HostIfNaming>,
+    ) -> Result<PhysicalPort<'a, 'b>, PortError> {
+        let physical_port_index = state.idx;
+
+        // get the transceiver state first
+        let (xcvr_present, xcvr_oper_status, xcvr_inserted_type, xcvr_supported_types) =
+            xcvr_initial_state(&xcvr_api, physical_port_index);
+        let label = xcvr_port_label(&xcvr_api, physical_port_index);
+
+        // find all logical ports on the switch and restore them
+        let mut ports: Vec<LogicalPort<'a>> = Vec::with_capacity(state.ports.len());
+        for lp_state in state.ports.iter() {
+            let port = switch_ports
+                .iter()
+                .find(|p| u64::from(p.to_id()) == lp_state.oid)
+                .ok_or(PortError::WarmBootPortNotFound(lp_state.oid))?;
+            ports.push(LogicalPort::from_warm_boot_state(
+                switch.clone(),
+                router.clone(),
+                logical_port_mac_address(
+                    physical_port_index,
+                    &mac_settings,
+                    mac_address,
+                    mac_slot,
+                    &state.lanes,
+                    &lp_state.lanes,
+                ),
+                port.clone(),
+                lp_state,
+                discovery_profile.clone(),
+            )?);
+        }
+        let first_port = ports
+            .first()
+            .ok_or(PortError::WarmBootNoPorts(physical_port_index))?;
+
+        // the current breakout mode is defined by the lanes of the logical ports
+        let current_breakout_mode =
+            PhysicalPortConfig::get_breakout_type_from_lanes(first_port.lanes.clone());
+        let supported_breakout_modes =
+            get_supported_breakout_modes(physical_port_index, &first_port.port, &port_config)?;
+        let discovery_profile = match &port_config {
+            Some(pc) => pc.discovery_profile(discovery_profile),
+            None => discovery_profile,
+        };
+
+        let sm = state.discovery_result.map(|success| {
+            discovery::physicalport::DiscoveryStateMachine::restored(
+                physical_port_index,
+                state.auto_discovery_with_breakout,
+                current_breakout_mode,
+                supported_breakout_modes.clone(),
+                success,
+            )
+        });
+
+        log::info!(
+            "Physical Port {}: restored from warm boot state with {} logical port(s)",
+            physical_port_index,
+            ports.len()
+        );
+        Ok(PhysicalPort {
+            xcvr_api: xcvr_api.clone(),
+            switch: switch.clone(),
+            router: router.clone(),
+            xcvr_present: xcvr_present,
+            xcvr_inserted_type: xcvr_inserted_type,
+            xcvr_oper_status: xcvr_oper_status,
+            xcvr_supported_types: xcvr_supported_types,
+            label: label,
+            idx: physical_port_index,
+            auto_discovery: state.auto_discovery,
+            auto_discovery_with_breakout: state.auto_discovery_with_breakout,
+            auto_discovery_counter: state.auto_discovery_counter,
+            initial_port_discovery: None,
+            sm: sm,
+            oper_status: state.oper_status,
+            lanes: state.lanes.clone(),
+            mac_address,
+            mac_settings: mac_settings,
+            mac_slot,
+            current_breakout_mode: current_breakout_mode,
+            supported_breakout_modes: supported_breakout_modes,
+            port_config: port_config,
+            media_settings: media_settings,
+            discovery_profile: discovery_profile,
+            hostif_naming: hostif_naming,
+            discovery_hint: None,
+            discovery_history: DiscoveryHistory::default(),
+            hifs_and_rifs: ports.iter().any(|p| p.hif.is_some() || p.rif.is_some()),
+            ports: ports,
         })
     }
 
@@ -465,6 +606,9 @@ impl<'a, 'b> PhysicalPort<'a, 'b> {
             }
         };
 
+        let inserted = xcvr_present && !self.xcvr_present;
+        self.xcvr_present = xcvr_present;
+
         if xcvr_present {
             match self.xcvr_api.obj.get_oper_status(self.idx as u16) {
                 Ok(v) => {
@@ -490,22 +634,346 @@ impl<'a, 'b> PhysicalPort<'a, 'b> {
                     );
                 }
             };
+        } else {
+            self.xcvr_oper_status = None;
+            self.xcvr_inserted_type = None;
+        }
+
+        // a newly inserted transceiver might need different serdes tuning
+        if inserted {
+            self.apply_media_settings();
         }
 
         // NOTE: supported types don't change for physical ports ever, so no need to recheck again
     }
 
+    /// applies the serdes tuning values from the media settings to all logical ports
+    /// for the transceiver which is currently inserted
+    pub(crate) fn apply_media_settings(&self) {
+        for port in self.ports.iter() {
+            self.apply_media_settings_to_port(port);
+        }
+    }
+
+    fn apply_media_settings_to_port(&self, port: &LogicalPort<'a>) {
+        let media_settings = match &self.media_settings {
+            Some(v) => v,
+            None => return,
+        };
+        if !self.xcvr_present {
+            return;
+        }
+
+        let info = match self.xcvr_api.obj.get_transceiver_info(self.idx as u16) {
+            Ok(v) => v,
+            Err(e) => {
+                log::warn!(
+                    "Physical Port {}: failed to get transceiver info, cannot apply media settings: {:?}",
+                    self.idx,
+                    e
+                );
+                return;
+            }
+        };
+        let settings = match media_settings.lookup(self.idx, &info) {
+            Some(v) => v,
+            None => {
+                log::debug!(
+                    "Physical Port {}: no media settings for transceiver {} {}",
+                    self.idx,
+                    info.manufacturer.trim(),
+                    info.model.trim()
+                );
+                return;
+            }
+        };
+
+        // the position of the logical port lanes within the physical port selects the lane settings
+        let num_lanes = port.lanes.len();
+        let subport = port
+            .lanes
+            .first()
+            .and_then(|lane| self.lanes.iter().position(|l| l == lane))
+            .unwrap_or_default()
+            / num_lanes.max(1);
+        let attrs = MediaSettings::serdes_attributes(settings, subport, num_lanes);
+        if attrs.is_empty() {
+            return;
+        }
+
+        match self.switch.set_port_serdes(&port.port, attrs) {
+            Ok(serdes) => {
+                log::info!(
+                    "Physical Port {}: applied media settings for transceiver {} {} to port {}: {}",
+                    self.idx,
+                    info.manufacturer.trim(),
+                    info.model.trim(),
+                    port.port,
+                    serdes
+                );
+            }
+            Err(e) => {
+                log::error!(
+                    "Physical Port {}: failed to apply media settings to port {}: {:?}",
+                    self.idx,
+                    port.port,
+                    e
+                );
+            }
+        }
+    }
+
+    /// applies the static settings from the port config on startup
+    /// NOTE: nothing here is fatal, the port will simply not be configured as expected
+    fn apply_port_config(&mut self) {
+        let pc = match &self.port_config {
+            Some(pc) => pc.clone(),
+            None => return,
+        };
+
+        if let Some(mode) = pc.breakout_mode.map(breakout_mode_type) {
+            if mode != self.current_breakout_mode {
+                if self.supported_breakout_modes.contains(&mode) {
+                    if let Err(e) = self.break_out(mode) {
+                        log::error!(
+                            "Physical Port {}: failed to break out port to {} from port config: {}",
+                            self.idx,
+                            mode,
+                            e
+                        );
+                    }
+                } else {
+                    log::error!(
+                        "Physical Port {}: breakout mode {} from port config is not supported",
+                        self.idx,
+                        mode
+                    );
+                }
+            }
+        }
+
+        let mtu = self.mtu();
+        let settings = pc.logical_port_settings();
+        for port in self.ports.iter_mut() {
+            match port.port.set_mtu(mtu) {
+                Ok(_) => port.mtu = Some(mtu),
+                Err(e) => log::error!(
+                    "Physical Port {}: failed to set MTU {} on port {}: {:?}",
+                    self.idx,
+                    mtu,
+                    port.port,
+                    e
+                ),
+            }
+            if let Err(e) = port.set_manually(&settings) {
+                log::error!(
+                    "Physical Port {}: failed to apply settings from port config to port {}: {}",
+                    self.idx,
+                    port.port,
+                    e
+                );
+            }
+        }
+
+        // a port which does not get discovered needs its host interfaces right away
+        if !pc.admin_down && !pc.discovery_enabled() {
+            self.create_hifs_and_rifs();
+        }
+        log::info!(
+            "Physical Port {}: applied port config (alias: {:?}, settings: {:?}, discovery: {})",
+            self.idx,
+            pc.alias,
+            settings,
+            pc.discovery_enabled()
+        );
+    }
+
+    /// if the port config allows auto discovery for this port
+    pub(crate) fn discovery_allowed(&self) -> bool {
+        self.port_config
+            .as_ref()
+            .map_or(true, |pc| pc.discovery_enabled())
+    }
+
+    /// breakout discovery is disabled if the port config pins the breakout mode
+    fn breakout_discovery(&self) -> bool {
+        self.auto_discovery_with_breakout
+            && self
+                .port_config
+                .as_ref()
+                .map_or(true, |pc| pc.breakout_mode.is_none())
+    }
+
+    fn fixed_speed(&self) -> Option<u32> {
+        self.port_config.as_ref().and_then(|pc| pc.fixed_speed)
+    }
+
+    fn preferred_fec(&self) -> Option<FECMode> {
+        self.port_config
+            .as_ref()
+            .and_then(|pc| pc.preferred_fec)
+            .map(fec_mode)
+    }
+
+    fn mtu(&self) -> u32 {
+        self.port_config
+            .as_ref()
+            .and_then(|pc| pc.mtu)
+            .unwrap_or(DEFAULT_MTU)
+    }
+
     pub(crate) fn create_hifs_and_rifs(&mut self) {
-        for (i, port) in self.ports.iter_mut().enumerate() {
-            let name = format!("Ethernet{}-{}", self.idx, i);
-            port.create_hif_and_rif(name);
+        self.hifs_and_rifs = true;
+        for i in 0..self.ports.len() {
+            self.create_hif_and_rif(i);
         }
     }
 
-    pub(crate) fn remove_hifs_and_rifs(&mut self) {
-        for port in self.ports.iter_mut() {
-            port.remove_hif_and_rif();
+    /// creates whatever is missing of the host interface and router interface of a logical port
+    fn create_hif_and_rif(&mut self, i: usize) {
+        let mtu = self.mtu();
+        let alias = self.port_config.as_ref().and_then(|pc| pc.alias.as_deref());
+        let values = HostIfNameValues {
+            idx: self.idx,
+            subport: i,
+            num_subports: self.ports.len(),
+            lane: self.ports[i].lanes.first().copied(),
+            label: self.label.as_deref(),
+            alias: alias,
+        };
+        let name = match self.hostif_naming.name(&values) {
+            Ok(v) => v,
+            Err(e) => {
+                let name = hostif_naming::default_name(self.idx, i);
+                log::error!(
+                    "Physical Port {}: failed to name host interface for logical port {}: {}. Falling back to {}",
+                    self.idx,
+                    i,
+                    e,
+                    name
+                );
+                name
+            }
+        };
+        let port = &mut self.ports[i];
+        let had_hif = port.hif.is_some();
+        port.create_hif_and_rif(name, mtu);
+
+        // the kernel would otherwise use the MAC address of the switch for the host interface
+        if !had_hif && self.mac_settings.mode == PortMacMode::PerPort {
+            port.set_hif_mac_address();
+        }
+    }
+
+    pub(crate) fn remove_hifs_and_rifs(&mut self) {
+        self.hifs_and_rifs = false;
+        for port in self.ports.iter_mut() {
+            port.remove_hif_and_rif();
+        }
+    }
+
+    /// Compares the host interfaces and router interfaces of the logical ports against the desired
+    /// state, and repairs them: missing ones get created again. This covers host interfaces whose
+    /// kernel interface is gone, and host interfaces and router interfaces which are gone in SAI.
+    pub(crate) fn reconcile(&mut self, processor_sender: Sender<ProcessRequest>) -> Vec<Drift> {
+        let mut ret = Vec::new();
+        if !self.hifs_and_rifs {
+            return ret;
+        }
+
+        let mut missing = Vec::new();
+        for (i, port) in self.ports.iter_mut().enumerate() {
+            if let Some(hif) = port.hif.as_mut() {
+                match netlink::get_interface_index(hif.name.as_str()) {
+                    Ok(idx) if idx == hif.idx => {}
+                    Ok(idx) => {
+                        // NOTE: this happens if we failed to look up the index on creation
+                        ret.push(Drift::new(
+                            format!(
+                                "Port {}: index of host interface {} was {} instead of {}",
+                                port.port, hif.name, hif.idx, idx
+                            ),
+                            true,
+                        ));
+                        hif.idx = idx;
+                        // all neighbor and route events of the interface were dropped until now
+                        port.route_resync = true;
+                    }
+                    Err(e) => {
+                        log::debug!(
+                            "Port {}: failed to get interface index for {}: {:?}",
+                            port.port,
+                            hif.name,
+                            e
+                        );
+                        missing.push((
+                            i,
+                            format!(
+                                "Port {}: kernel interface of host interface {} is missing",
+                                port.port, hif.name
+                            ),
+                        ));
+                        port.remove_hif();
+                        continue;
+                    }
+                }
+            }
+            // NOTE: getting an attribute is the only way to find out if an object still exists in SAI
+            if let Some(hif) = port.hif.as_ref() {
+                if object_missing(&hif.intf.get_oper_status()) {
+                    log::debug!(
+                        "Port {}: host interface {} ({}) is gone in SAI",
+                        port.port,
+                        hif.name,
+                        hif.intf
+                    );
+                    port.hif = None;
+                }
+            }
+            if let Some(rif) = port.rif.as_ref() {
+                if object_missing(&rif.get_mtu()) {
+                    log::debug!(
+                        "Port {}: router interface {} is gone in SAI",
+                        port.port,
+                        rif
+                    );
+                    // the synced neighbors and routes referenced it
+                    port.route_sync.flush();
+                    port.rif = None;
+                }
+            }
+            let what = match (port.hif.is_none(), port.rif.is_none()) {
+                (true, true) => "host interface and router interface are",
+                (true, false) => "host interface is",
+                (false, true) => "router interface is",
+                (false, false) => continue,
+            };
+            missing.push((i, format!("Port {}: {} missing", port.port, what)));
+        }
+        if missing.is_empty() {
+            return ret;
+        }
+
+        for (i, description) in missing.into_iter() {
+            self.create_hif_and_rif(i);
+            let port = &mut self.ports[i];
+            // a recreated host interface must follow the oper status of the port again
+            if let Some(hif) = port.hif.as_mut() {
+                if port.oper_status && !hif.oper_status {
+                    if let Err(e) = hif.set_oper_status(true, processor_sender.clone()) {
+                        log::error!(
+                            "Port {}: failed to set operational status of host interface {}: {:?}",
+                            port.port,
+                            hif.name,
+                            e
+                        );
+                    }
+                }
+            }
+            let repaired = port.hif.is_some() && port.rif.is_some();
+            ret.push(Drift::new(description, repaired));
         }
+        ret
     }
 
     pub(crate) fn create_port(&mut self, breakout_mode: BreakoutModeType, hw_lanes: Vec<u32>) {
@@ -518,7 +986,15 @@ impl<'a, 'b> PhysicalPort<'a, 'b> {
             .unwrap_or(10000);
 
         // create the port with SAI
-        let port = match self.switch.create_port(hw_lanes.clone(), speed) {
+        // NOTE: we create it fully configured in one go: the port stays down with auto-negotiation
+        // disabled until the discovery state machine decides otherwise
+        let port = match self.switch.create_port_with_attributes(vec![
+            PortAttribute::HwLaneList(hw_lanes.clone()),
+            PortAttribute::Speed(speed),
+            PortAttribute::AdminState(false),
+            PortAttribute::AutoNegMode(false),
+            PortAttribute::MTU(self.mtu()),
+        ]) {
             Ok(port) => port,
             Err(e) => {
                 log::error!(
@@ -538,7 +1014,14 @@ impl<'a, 'b> PhysicalPort<'a, 'b> {
             self.switch.clone(),
             self.router.clone(),
             hw_lanes.clone(),
-            self.mac_address,
+            logical_port_mac_address(
+                self.idx,
+                &self.mac_settings,
+                self.mac_address,
+                self.mac_slot,
+                &self.lanes,
+                &hw_lanes,
+            ),
             port,
         ) {
             Ok(port) => port,
@@ -553,10 +1036,80 @@ impl<'a, 'b> PhysicalPort<'a, 'b> {
             }
         };
 
+        // the new port needs the serdes tuning for the inserted transceiver as well
+        self.apply_media_settings_to_port(&port);
+
+        // discovery does not touch the FEC mode if it is pinned, so it must be set right away
+        if let Some(fec) = self
+            .port_config
+            .as_ref()
+            .and_then(|pc| pc.fec)
+            .map(fec_mode)
+        {
+            if let Err(e) = port.port.set_fec_mode(fec) {
+                log::error!(
+                    "Physical Port {}: failed to set FEC mode {:?} from port config on port {}: {:?}",
+                    self.idx,
+                    fec,
+                    port.port,
+                    e
+                );
+            }
+        }
+
         // add the port to our list of logical ports
         self.ports.push(port);
     }
 
+    /// breaks out the physical port into the logical ports of the breakout mode:
+    /// all current logical ports get removed, and the new ones get created
+    pub(crate) fn break_out(&mut self, breakout_mode: BreakoutModeType) -> Result<(), PortError> {
+        // calculate all the ports that we need to create for the breakout mode by using the lanes of the port
+        let new_ports = discovery::physicalport::calculate_new_ports(&breakout_mode, &self.lanes)?;
+        log::debug!(
+            "Physical Port {}: calculated new ports for breakout mode {}: {:?}",
+            self.idx,
+            breakout_mode,
+            new_ports.clone()
+        );
+
+        // remove all old ports now
+        self.remove_ports();
+
+        // and create new ports from calculation
+        // this function will add the newly created ports to the physical port
+        for new_port_hw_lanes in new_ports.into_iter() {
+            self.create_port(breakout_mode, new_port_hw_lanes);
+        }
+        self.current_breakout_mode = breakout_mode;
+        Ok(())
+    }
+
+    /// derives the breakout mode and speed to try first from the inserted transceiver
+    fn xcvr_discovery_hint(&self) -> Option<DiscoveryHint> {
+        // NOTE: not every platform library can read the transceiver EEPROM, we can still go by the type then
+        let info = match self.xcvr_api.obj.get_transceiver_info(self.idx as u16) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                log::debug!(
+                    "Physical Port {}: failed to get transceiver info for discovery hint: {:?}",
+                    self.idx,
+                    e
+                );
+                None
+            }
+        };
+        DiscoveryHint::from_xcvr(self.lanes.len(), self.xcvr_inserted_type, info.as_ref())
+    }
+
+    /// the speed that the logical ports should try first: this is only the case if the transceiver
+    /// hint applies to the current breakout mode
+    pub(crate) fn preferred_speed(&self) -> Option<u32> {
+        self.discovery_hint
+            .filter(|hint| hint.breakout_mode == self.current_breakout_mode)
+            .map(|hint| hint.speed)
+    }
+
     pub(crate) fn remove_ports(&mut self) {
         log::debug!("Physical Port {}: removing all logical ports...", self.idx);
         let ports = std::mem::take(&mut self.ports);
@@ -567,25 +1120,62 @@ impl<'a, 'b> PhysicalPort<'a, 'b> {
 
     fn initialize_state_machines(&mut self) {
         if self.sm.is_none() {
-            self.sm = Some(discovery::physicalport::DiscoveryStateMachine::new(
+            // the inserted transceiver tells us which breakout mode and speed will most likely work
+            // so we start with them, and discovery falls back to all the other ones if they do not
+            self.discovery_hint = self.xcvr_discovery_hint();
+            if let Some(hint) = self.discovery_hint {
+                log::info!("Physical Port {}: transceiver suggests {}", self.idx, hint);
+                if self.breakout_discovery()
+                    && hint.breakout_mode != self.current_breakout_mode
+                    && self.supported_breakout_modes.contains(&hint.breakout_mode)
+                {
+                    if let Err(e) = self.break_out(hint.breakout_mode) {
+                        log::error!(
+                            "Physical Port {}: failed to break out port to {} as suggested by transceiver: {}",
+                            self.idx,
+                            hint.breakout_mode,
+                            e
+                        );
+                    }
+                }
+            }
+            let sm = discovery::physicalport::DiscoveryStateMachine::new(
                 self.idx,
-                self.auto_discovery_with_breakout,
+                self.breakout_discovery(),
                 self.current_breakout_mode.clone(),
                 self.supported_breakout_modes.clone(),
-            ));
+            );
+            self.discovery_history.record(
+                None,
+                sm.to_string(),
+                self.discovery_details(),
+                sm.is_attempt(),
+            );
+            self.sm = Some(sm);
         }
+        let preferred_speed = self.preferred_speed();
+        let preferred_fec = self.preferred_fec();
+        let fixed_speed = self.fixed_speed();
         for port in self.ports.iter_mut() {
-            if port.sm.is_none() {
-                port.sm = Some(discovery::logicalport::DiscoveryStateMachine::new(
-                    &port.port,
-                    port.supported_speeds.clone(),
-                    port.speed,
-                    port.auto_negotiation,
-                ));
+            if port.sm.is_none() && !port.kernel_admin_down {
+                port.start_discovery(
+                    preferred_speed,
+                    preferred_fec,
+                    fixed_speed,
+                    self.discovery_profile.clone(),
+                );
             }
         }
     }
 
+    /// the port settings which get recorded together with every transition in the discovery history
+    fn discovery_details(&self) -> String {
+        format!(
+            "breakout mode: {}, auto discovery counter: {}",
+            self.current_breakout_mode, self.auto_discovery_counter
+        )
+    }
+
     fn destroy_state_machines(&mut self) {
         if self.sm.is_some() {
             self.sm = None;
@@ -609,6 +1199,34 @@ impl<'a, 'b> PhysicalPort<'a, 'b> {
         }
     }
 
+    /// changes breakout discovery for this port: a running discovery is restarted to pick it up
+    pub(crate) fn set_auto_discovery_with_breakout(&mut self, auto_discovery_with_breakout: bool) {
+        if self.auto_discovery_with_breakout == auto_discovery_with_breakout {
+            return;
+        }
+        self.auto_discovery_with_breakout = auto_discovery_with_breakout;
+        self.rediscover();
+    }
+
+    /// restarts discovery from scratch: this is a no-op if auto discovery is disabled for this port
+    pub(crate) fn rediscover(&mut self) -> bool {
+        if !self.auto_discovery {
+            return false;
+        }
+        log::info!(
+            "Physical Port {}: rediscovery requested. Restarting auto discovery state machine (port breakout discovery: {})",
+            self.idx,
+            self.auto_discovery_with_breakout
+        );
+        self.destroy_state_machines();
+        self.auto_discovery_counter = 0;
+        if self.xcvr_present {
+            self.initialize_state_machines();
+            self.create_hifs_and_rifs();
+        }
+        true
+    }
+
     pub(crate) fn disable_auto_discovery(&mut self) {
         self.auto_discovery = false;
         if self.sm.is_some() {
@@ -617,6 +1235,142 @@ impl<'a, 'b> PhysicalPort<'a, 'b> {
         self.destroy_state_machines();
     }
 
+    /// Applies manual settings to the port: this disables auto discovery for this port as it would
+    /// override the settings otherwise. If a breakout mode is given, it is applied first, and the
+    /// settings are applied to all the new logical ports. If a logical port index is given, the
+    /// settings are only applied to that logical port, otherwise to all of them.
+    pub(crate) fn set_manually(
+        &mut self,
+        breakout_mode: Option<BreakoutModeType>,
+        logical_port: Option<usize>,
+        settings: &LogicalPortSettings,
+    ) -> Result<(), PortError> {
+        // validate everything first, so that we don't leave the port half configured
+        match breakout_mode {
+            Some(mode) if mode != self.current_breakout_mode => {
+                if !self.supported_breakout_modes.contains(&mode) {
+                    return Err(PortError::BreakoutModeUnsupported(self.idx, mode));
+                }
+                // the logical ports do not exist yet, so we can only check against the lanes
+                // which they are going to get
+                let new_ports = discovery::physicalport::calculate_new_ports(&mode, &self.lanes)?;
+                if let Some(i) = logical_port {
+                    if i >= new_ports.len() {
+                        return Err(PortError::NoSuchLogicalPort(self.idx, i));
+                    }
+                }
+                if let (Some(speed), Some(lanes)) = (settings.speed, new_ports.first()) {
+                    if let Some(e) = check_speed(speed, lanes.len()) {
+                        return Err(PortError::SpeedUnsupportedForBreakoutMode(
+                            self.idx, speed, mode, e,
+                        ));
+                    }
+                }
+            }
+            _ => {
+                if let Some(i) = logical_port {
+                    if i >= self.ports.len() {
+                        return Err(PortError::NoSuchLogicalPort(self.idx, i));
+                    }
+                }
+                if let Some(speed) = settings.speed {
+                    for (i, port) in self.ports.iter().enumerate() {
+                        if logical_port.map_or(true, |lp| lp == i)
+                            && !port.supported_speeds.contains(&speed)
+                        {
+                            return Err(PortError::SpeedUnsupported(port.port.to_string(), speed));
+                        }
+                    }
+                }
+            }
+        }
+
+        log::info!(
+            "Physical Port {}: applying manual settings (breakout mode: {:?}, logical port: {:?}, settings: {:?})",
+            self.idx,
+            breakout_mode,
+            logical_port,
+            settings
+        );
+        self.disable_auto_discovery();
+
+        // nobody must wait on the initial discovery of this port any longer
+        self.initial_port_discovery = None;
+
+        if let Some(mode) = breakout_mode {
+            if mode != self.current_breakout_mode {
+                self.break_out(mode)?;
+            }
+        }
+
+        // the logical ports might have been recreated, so we make sure that
+        // the host interfaces and router interfaces exist for them
+        self.create_hifs_and_rifs();
+
+        for (i, port) in self.ports.iter_mut().enumerate() {
+            if logical_port.map_or(true, |lp| lp == i) {
+                port.set_manually(settings)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Syncs the kernel interface of a logical port to the port after it changed. The kernel
+    /// interface is set up and down by us with the oper status of the port, so only a difference
+    /// to that is an admin state change from the outside. Such a change only toggles the admin
+    /// state of this logical port: a port which was set down this way stays out of discovery
+    /// until it gets set up again from the kernel.
+    /// NOTE: setting an interface down from the kernel while the port is down already goes unnoticed
+    pub(crate) fn host_interface_changed(&mut self, logical_port: usize, link: &netlink::Link) {
+        let port = match self.ports.get_mut(logical_port) {
+            Some(port) => port,
+            None => return,
+        };
+        port.sync_from_link(link);
+        let hif_oper_status = match port.hif {
+            Some(ref hif) => hif.oper_status,
+            None => return,
+        };
+        if link.admin_up == hif_oper_status {
+            return;
+        }
+
+        let admin_state = if link.admin_up {
+            // setting it up again is only up to us if it was set down from the kernel before,
+            // or if the port was set down manually while auto discovery is not running
+            // NOTE: scripts which set all interfaces up must not interfere with auto discovery
+            if !port.kernel_admin_down && (self.auto_discovery || port.admin_state) {
+                return;
+            }
+            // auto discovery picks the port up again with the next poll
+            port.kernel_admin_down = false;
+            true
+        } else {
+            if port.kernel_admin_down {
+                return;
+            }
+            // discovery would set the port up again, so it stops for this logical port only
+            port.kernel_admin_down = true;
+            port.sm = None;
+            false
+        };
+        log::info!(
+            "Physical Port {}: host interface of logical port {} was set {} from the kernel",
+            self.idx,
+            logical_port,
+            if admin_state { "up" } else { "down" }
+        );
+        if let Err(e) = port.port.set_admin_state(admin_state) {
+            log::error!(
+                "Physical Port {}: failed to set admin state of logical port {}: {:?}",
+                self.idx,
+                logical_port,
+                e
+            );
+        }
+        port.reconcile_state();
+    }
+
     pub(crate) fn auto_discovery_poll(&mut self) {
         if self.auto_discovery {
             // poll on xcvr state first
@@ -648,27 +1402,33 @@ impl<'a, 'b> PhysicalPort<'a, 'b> {
                         // this means that we have successfully brought up at least one logical port
                         // for this port. However, we should continue to try to bring up the other logical ports
                         // regardless, we consider this port operating
+                        let preferred_speed = self.preferred_speed();
+                        let preferred_fec = self.preferred_fec();
+                        let fixed_speed = self.fixed_speed();
                         for port in self.ports.iter_mut() {
-                            if let Some(sm) = &mut port.sm {
+                            if let Some(sm) = &port.sm {
                                 if sm.is_done() && !sm.is_done_and_success() {
-                                    *sm = discovery::logicalport::DiscoveryStateMachine::new(
-                                        &port.port,
-                                        port.supported_speeds.clone(),
-                                        port.speed,
-                                        port.auto_negotiation,
+                                    port.start_discovery(
+                                        preferred_speed,
+                                        preferred_fec,
+                                        fixed_speed,
+                                        self.discovery_profile.clone(),
                                     );
                                 }
-                                if !sm.is_done() {
-                                    if sm.can_step() {
-                                        *sm = sm.clone().step(
-                                            &port.port,
-                                            discovery::logicalport::Event::NoChange,
-                                        );
-                                        port.reconcile_state();
-                                    }
+                            }
+                            if let Some(sm) = &port.sm {
+                                if !sm.is_done() && sm.can_step() {
+                                    port.step_discovery(discovery::logicalport::Event::NoChange);
                                 }
                             }
                         }
+                    } else if self
+                        .discovery_profile
+                        .max_retries
+                        .map_or(false, |max| self.auto_discovery_counter >= max)
+                    {
+                        // we are out of retries: the state machine stays done, and the port stays down
+                        // until the transceiver gets reinserted which resets the counter
                     } else {
                         // if the state machine was not successful, then we simply start over
                         // and log that
@@ -678,6 +1438,12 @@ impl<'a, 'b> PhysicalPort<'a, 'b> {
                             self.idx,
                             self.auto_discovery_counter
                         );
+                        if self.discovery_profile.max_retries == Some(self.auto_discovery_counter) {
+                            log::warn!(
+                                "Physical Port {}: this is the last auto discovery retry",
+                                self.idx
+                            );
+                        }
                         self.destroy_state_machines();
                         self.initialize_state_machines();
                     }
@@ -685,21 +1451,24 @@ impl<'a, 'b> PhysicalPort<'a, 'b> {
                     // if the state machine is not done yet, we'll call step if we can
                     // first on the logical ports
                     for port in self.ports.iter_mut() {
-                        if let Some(sm) = &mut port.sm {
-                            if !sm.is_done() {
-                                if sm.can_step() {
-                                    *sm = sm
-                                        .clone()
-                                        .step(&port.port, discovery::logicalport::Event::NoChange);
-                                    port.reconcile_state();
-                                }
+                        if let Some(sm) = &port.sm {
+                            if !sm.is_done() && sm.can_step() {
+                                port.step_discovery(discovery::logicalport::Event::NoChange);
                             }
                         }
                     }
 
                     // and then on the physical port state machine
                     if sm.can_step(self) {
-                        self.sm = Some(sm.clone().step(self));
+                        let from = sm.to_string();
+                        let sm = sm.clone().step(self);
+                        self.discovery_history.record(
+                            Some(from),
+                            sm.to_string(),
+                            self.discovery_details(),
+                            sm.is_attempt(),
+                        );
+                        self.sm = Some(sm);
                     }
                 }
             }
@@ -716,13 +1485,26 @@ pub(crate) struct LogicalPort<'a> {
     pub(crate) rif: Option<RouterInterface<'a>>,
     pub(crate) lanes: Vec<u32>,
     pub(crate) mac_address: sai_mac_t,
+    /// the MTU of the port and its router interface, if we have set it
+    pub(crate) mtu: Option<u32>,
     pub(crate) oper_status: bool,
     pub(crate) admin_state: bool,
     pub(crate) auto_negotiation: bool,
+    pub(crate) fec: FECMode,
     pub(crate) speed: u32,
     pub(crate) oper_speed: u32,
     pub(crate) supported_speeds: Vec<u32>,
     pub(crate) sm: Option<discovery::logicalport::DiscoveryStateMachine>,
+    pub(crate) discovery_history: DiscoveryHistory,
+    /// the host interface was set down from the kernel: the port stays admin down and does not
+    /// take part in discovery until it gets set up again
+    /// NOTE: this is separate from the oper status, which we mirror onto the host interface ourselves
+    pub(crate) kernel_admin_down: bool,
+    /// the kernel neighbors and routes of the host interface which are synced to SAI
+    pub(crate) route_sync: RouteSync<'a>,
+    /// the router interface was (re)created, so the neighbors and routes of the host interface
+    /// need to be synced from the kernel again: events for them were dropped without it
+    pub(crate) route_resync: bool,
 }
 
 impl<'a> LogicalPort<'a> {
@@ -736,9 +1518,15 @@ impl<'a> LogicalPort<'a> {
         let oper_status: bool = port.get_oper_status()?.into();
         let admin_state = port.get_admin_state()?;
         let auto_negotiation = port.get_auto_neg_mode()?;
+        // NOTE: not every platform supports reading the FEC mode, so we don't fail for it
+        let fec = port.get_fec_mode().unwrap_or_else(|e| {
+            log::debug!("Port {}: failed to get FEC mode: {:?}", port, e);
+            FECMode::None
+        });
         let speed = port.get_speed()?;
         let oper_speed = port.get_oper_speed()?;
         let supported_speeds = port.get_supported_speeds()?;
+        let route_sync = RouteSync::new(switch.clone(), router.clone());
         Ok(Self {
             switch: switch,
             router: router,
@@ -747,16 +1535,178 @@ impl<'a> LogicalPort<'a> {
             rif: None,
             lanes: hw_lanes,
             mac_address: mac_address,
+            mtu: None,
             oper_status: oper_status,
             admin_state: admin_state,
             auto_negotiation: auto_negotiation,
+            fec: fec,
             speed: speed,
             oper_speed: oper_speed,
             supported_speeds: supported_speeds,
             sm: None,
+            discovery_history: DiscoveryHistory::default(),
+            kernel_admin_down: false,
+            route_sync: route_sync,
+            route_resync: false,
         })
     }
 
+    /// restores a logical port from the warm boot state
+    /// the host interface and router interface are not being created, but looked up instead
+    pub(crate) fn from_warm_boot_state(
+        switch: Switch<'a>,
+        router: VirtualRouter<'a>,
+        mac_address: sai_mac_t,
+        port: Port<'a>,
+        state: &LogicalPortState,
+        discovery_profile: Arc<DiscoveryProfile>,
+    ) -> Result<Self, PortError> {
+        let mut ret = Self::new(
+            switch.clone(),
+            router.clone(),
+            state.lanes.clone(),
+            mac_address,
+            port,
+        )?;
+        if let Some(hif_state) = &state.hif {
+            let intf = switch.get_hostif(hif_state.oid)?;
+            let idx = match netlink::get_interface_index(hif_state.name.as_str()) {
+                Ok(idx) => idx,
+                Err(e) => {
+                    log::error!(
+                        "Port {}: failed to get interface index for {}: {:?}",
+                        ret.port,
+                        hif_state.name,
+                        e
+                    );
+                    0
+                }
+            };
+            ret.hif = Some(HostInterface {
+                intf: intf,
+                name: hif_state.name.clone(),
+                idx: idx,
+                oper_status: hif_state.oper_status,
+                lldp_socket: None,
+                lldp_tlvs: None,
+                lldp_network_config: None,
+                lldp_network_config_applied: None,
+            });
+        }
+        if let Some(rif_oid) = state.rif {
+            ret.rif = Some(router.get_router_interface(rif_oid)?);
+        }
+        ret.sm = state.discovery_result.map(|success| {
+            discovery::logicalport::DiscoveryStateMachine::restored(
+                &ret.port,
+                ret.supported_speeds.clone(),
+                ret.speed,
+                ret.auto_negotiation,
+                ret.fec,
+                success,
+                discovery_profile.clone(),
+            )
+        });
+        Ok(ret)
+    }
+
+    /// applies manual settings to the port: the admin state is applied last, so that the port
+    /// comes up with all the other settings in place
+    pub(crate) fn set_manually(&mut self, settings: &LogicalPortSettings) -> Result<(), PortError> {
+        if let Some(speed) = settings.speed {
+            if !self.supported_speeds.contains(&speed) {
+                return Err(PortError::SpeedUnsupported(self.port.to_string(), speed));
+            }
+        }
+        if let Some(auto_negotiation) = settings.auto_negotiation {
+            self.port.set_auto_neg_mode(auto_negotiation)?;
+        }
+        if let Some(speed) = settings.speed {
+            self.port.set_speed(speed)?;
+        }
+        // FEC modes are only valid for certain speeds, so this needs to happen after setting the speed
+        if let Some(fec) = settings.fec {
+            self.port.set_fec_mode(fec)?;
+        }
+        if let Some(admin_state) = settings.admin_state {
+            self.port.set_admin_state(admin_state)?;
+            // a manual admin state overrides the one from the kernel
+            self.kernel_admin_down = false;
+        }
+        self.reconcile_state();
+        Ok(())
+    }
+
+    /// (re)starts the discovery state machine with the current settings of the port
+    /// if `fixed_speed` is set (and supported), it is the only speed which discovery is going to try
+    pub(crate) fn start_discovery(
+        &mut self,
+        preferred_speed: Option<u32>,
+        preferred_fec: Option<FECMode>,
+        fixed_speed: Option<u32>,
+        profile: Arc<DiscoveryProfile>,
+    ) {
+        let from = self.sm.as_ref().map(|sm| sm.to_string());
+        let supported_speeds = match fixed_speed {
+            Some(speed) if self.supported_speeds.contains(&speed) => vec![speed],
+            Some(speed) => {
+                log::warn!(
+                    "Port {}: fixed speed {} from port config is not supported. Trying all supported speeds instead",
+                    self.port,
+                    speed
+                );
+                self.supported_speeds.clone()
+            }
+            None => self.supported_speeds.clone(),
+        };
+        let sm = discovery::logicalport::DiscoveryStateMachine::new(
+            &self.port,
+            supported_speeds,
+            self.speed,
+            self.auto_negotiation,
+            self.fec,
+            preferred_speed,
+            preferred_fec,
+            profile,
+        );
+        self.reconcile_state();
+        self.discovery_history.record(
+            from,
+            sm.to_string(),
+            self.discovery_details(),
+            sm.is_attempt(),
+        );
+        self.sm = Some(sm);
+    }
+
+    /// steps the discovery state machine, and records the transition in the discovery history
+    pub(crate) fn step_discovery(&mut self, ev: discovery::logicalport::Event) {
+        let sm = match self.sm.take() {
+            Some(sm) => sm,
+            None => return,
+        };
+        let from = sm.to_string();
+        // NOTE: the state machine returns itself if it cannot step yet, which is no attempt
+        let stepped = sm.can_step();
+        let sm = sm.step(&self.port, ev);
+        self.reconcile_state();
+        self.discovery_history.record(
+            Some(from),
+            sm.to_string(),
+            self.discovery_details(),
+            stepped && sm.is_attempt(),
+        );
+        self.sm = Some(sm);
+    }
+
+    /// the port settings which get recorded together with every transition in the discovery history
+    fn discovery_details(&self) -> String {
+        format!(
+            "speed: {}, auto negotiation: {}, FEC: {:?}, oper status: {}",
+            self.speed, self.auto_negotiation, self.fec, self.oper_status
+        )
+    }
+
     pub(crate) fn reconcile_state(&mut self) {
         let _ = self
             .port
@@ -773,6 +1723,9 @@ impl<'a> LogicalPort<'a> {
             .get_auto_neg_mode()
             .map(|v| self.auto_negotiation = v)
             .map_err(|e| log_port_error(&self.port, e));
+        if let Ok(v) = self.port.get_fec_mode() {
+            self.fec = v;
+        }
         let _ = self
             .port
             .get_speed()
@@ -785,7 +1738,7 @@ impl<'a> LogicalPort<'a> {
             .map_err(|e| log_port_error(&self.port, e));
     }
 
-    pub(crate) fn create_hif_and_rif(&mut self, name: String) {
+    pub(crate) fn create_hif_and_rif(&mut self, name: String, mtu: u32) {
         if self.hif.is_none() {
             match self.switch.create_hostif(vec![
                 HostIfAttribute::Name(name.clone()),
@@ -813,6 +1766,19 @@ impl<'a> LogicalPort<'a> {
                             0
                         }
                     };
+                    // the MTU of the kernel interface gets synced to the port and router interface
+                    // so it needs to match them from the start
+                    if idx != 0 {
+                        if let Err(e) = netlink::set_link_mtu(idx, mtu) {
+                            log::error!(
+                                "Port {}: failed to set MTU {} of host interface {}: {}",
+                                self.port,
+                                mtu,
+                                name,
+                                e
+                            );
+                        }
+                    }
                     self.hif = Some(HostInterface {
                         intf: hif,
                         name: name,
@@ -821,6 +1787,7 @@ impl<'a> LogicalPort<'a> {
                         lldp_socket: None,
                         lldp_tlvs: None,
                         lldp_network_config: None,
+                        lldp_network_config_applied: None,
                     })
                 }
                 Err(e) => {
@@ -838,7 +1805,7 @@ impl<'a> LogicalPort<'a> {
                 RouterInterfaceAttribute::SrcMacAddress(self.mac_address),
                 RouterInterfaceAttribute::Type(RouterInterfaceType::Port),
                 RouterInterfaceAttribute::PortID(self.port.to_id().into()),
-                RouterInterfaceAttribute::MTU(9100),
+                RouterInterfaceAttribute::MTU(mtu),
                 RouterInterfaceAttribute::NATZoneID(0),
                 RouterInterfaceAttribute::V4McastEnable(true),
                 RouterInterfaceAttribute::V6McastEnable(true),
@@ -850,6 +1817,8 @@ impl<'a> LogicalPort<'a> {
                         &rif
                     );
                     self.rif = Some(rif);
+                    self.mtu = Some(mtu);
+                    self.route_resync = true;
                 }
                 Err(e) => {
                     log::error!(
@@ -862,7 +1831,82 @@ impl<'a> LogicalPort<'a> {
         }
     }
 
+    /// syncs the MTU of the kernel interface to the port and the router interface,
+    /// and the MAC address of the kernel interface to the router interface
+    pub(crate) fn sync_from_link(&mut self, link: &netlink::Link) {
+        if let Some(mtu) = link.mtu {
+            if self.mtu != Some(mtu) {
+                log::info!(
+                    "Port {}: MTU of host interface changed to {}, updating port and router interface",
+                    self.port,
+                    mtu
+                );
+                if let Err(e) = self.port.set_mtu(mtu) {
+                    log::error!("Port {}: failed to set MTU {}: {:?}", self.port, mtu, e);
+                }
+                if let Some(ref rif) = self.rif {
+                    if let Err(e) = rif.set_mtu(mtu) {
+                        log::error!(
+                            "Port {}: failed to set MTU {} of router interface {}: {:?}",
+                            self.port,
+                            mtu,
+                            rif,
+                            e
+                        );
+                    }
+                }
+                self.mtu = Some(mtu);
+            }
+        }
+        if let (Some(mac_address), Some(rif)) = (link.mac_address, &self.rif) {
+            if self.mac_address != mac_address {
+                log::info!(
+                    "Port {}: MAC address of host interface changed to {}, updating router interface",
+                    self.port,
+                    MacAddr6::from(mac_address)
+                );
+                if let Err(e) = rif.set_src_mac_address(mac_address) {
+                    log::error!(
+                        "Port {}: failed to set MAC address {} of router interface {}: {:?}",
+                        self.port,
+                        MacAddr6::from(mac_address),
+                        rif,
+                        e
+                    );
+                }
+                self.mac_address = mac_address;
+            }
+        }
+    }
+
+    /// sets the MAC address of the router interface for the kernel interface as well
+    pub(crate) fn set_hif_mac_address(&self) {
+        let hif = match &self.hif {
+            Some(hif) => hif,
+            None => return,
+        };
+        match netlink::set_link_mac_address(hif.idx, self.mac_address) {
+            Ok(_) => log::debug!(
+                "Port {}: set MAC address of host interface {} to {}",
+                self.port,
+                hif.name,
+                MacAddr6::from(self.mac_address)
+            ),
+            Err(e) => log::error!(
+                "Port {}: failed to set MAC address of host interface {}: {}",
+                self.port,
+                hif.name,
+                e
+            ),
+        }
+    }
+
     pub(crate) fn remove_hif_and_rif(&mut self) {
+        self.remove_hif();
+        self.remove_rif();
+    }
+
+    fn remove_hif(&mut self) {
         if let Some(hif) = self.hif.take() {
             match hif.intf.remove() {
                 Ok(_) => {
@@ -882,6 +1926,11 @@ impl<'a> LogicalPort<'a> {
                 }
             }
         }
+    }
+
+    fn remove_rif(&mut self) {
+        // the synced neighbors and routes reference the router interface
+        self.route_sync.flush();
         if let Some(rif) = self.rif.take() {
             match rif.remove() {
                 Ok(_) => {
@@ -902,6 +1951,17 @@ impl<'a> LogicalPort<'a> {
         let mut s = self;
         s.remove_hif_and_rif();
         let port_id = s.port.to_id();
+
+        // the serdes object of the media settings references the port, so it must go first
+        match s.port.get_port_serdes() {
+            Ok(Some(serdes)) => match serdes.remove() {
+                Ok(_) => log::debug!("Port {}: successfully removed port serdes", port_id),
+                Err(e) => log::error!("Port {}: failed to remove port serdes: {:?}", port_id, e),
+            },
+            Ok(None) => {}
+            Err(e) => log::debug!("Port {}: failed to get port serdes: {:?}", port_id, e),
+        }
+
         match s.port.remove() {
             Ok(_) => {
                 log::debug!("Port {}: successfully removed", port_id);
@@ -913,6 +1973,149 @@ impl<'a> LogicalPort<'a> {
     }
 }
 
+// gets the MAC address for a logical port of a physical port
+// NOTE: the slot of a logical port is the position of its first lane within the physical port
+fn logical_port_mac_address(
+    physical_port_index: usize,
+    mac_settings: &PortMacSettings,
+    mac_address: sai_mac_t,
+    mac_slot: u64,
+    physical_port_lanes: &[u32],
+    lanes: &[u32],
+) -> sai_mac_t {
+    let pos = lanes
+        .first()
+        .and_then(|lane| physical_port_lanes.iter().position(|v| v == lane))
+        .unwrap_or_default();
+    match mac_settings.mac_address(mac_address, mac_slot + pos as u64) {
+        Some(v) => v,
+        None => {
+            log::error!(
+                "Physical Port {}: no reserved MAC address left for lanes {:?}. Using the base MAC address instead.",
+                physical_port_index,
+                lanes
+            );
+            mac_address
+        }
+    }
+}
+
+// gets the front panel label of a physical port
+// NOTE: this is optional for platform libraries, so we only warn about real failures
+fn xcvr_port_label(
+    xcvr_api: &PlatformContextHolder<'_>,
+    physical_port_index: usize,
+) -> Option<String> {
+    match xcvr_api.obj.get_port_label(physical_port_index as u16) {
+        Ok(v) => Some(v),
+        Err(xcvr::Error::Status(xcvr::Status::Unimplemented)) => None,
+        Err(e) => {
+            log::warn!(
+                "Physical Port {}: transceiver port label check failed: {:?}",
+                physical_port_index,
+                e
+            );
+            None
+        }
+    }
+}
+
+// gets the transceiver state for a physical port
+// NOTE: we are lenient here with xcvr state, and let nothing here fail.
+// If the presence check fails, we assume the port is present.
+fn xcvr_initial_state(
+    xcvr_api: &PlatformContextHolder<'_>,
+    physical_port_index: usize,
+) -> (
+    bool,
+    Option<bool>,
+    Option<xcvr::PortType>,
+    Vec<xcvr::PortType>,
+) {
+    let xcvr_present = match xcvr_api.obj.get_presence(physical_port_index as u16) {
+        Ok(v) => v,
+        Err(e) => {
+            log::warn!("Physical Port {}: transceiver presence check failed: {:?}. Assuming transceiver is present.", physical_port_index, e);
+            true
+        }
+    };
+    let xcvr_oper_status = if xcvr_present {
+        match xcvr_api.obj.get_oper_status(physical_port_index as u16) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                log::warn!(
+                    "Physical Port {}: transceiver oper status check failed: {:?}",
+                    physical_port_index,
+                    e
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let xcvr_inserted_type = if xcvr_present {
+        match xcvr_api
+            .obj
+            .get_inserted_port_type(physical_port_index as u16)
+        {
+            Ok(v) => Some(v),
+            Err(e) => {
+                log::warn!(
+                    "Physical Port {}: transceiver inserted port type check failed: {:?}",
+                    physical_port_index,
+                    e
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let xcvr_supported_types = match xcvr_api
+        .obj
+        .get_supported_port_types(physical_port_index as u16)
+    {
+        Ok(v) => v,
+        Err(e) => {
+            log::warn!(
+                "Physical Port {}: transceiver supported port types check failed: {:?}",
+                physical_port_index,
+                e
+            );
+            vec![]
+        }
+    };
+    (
+        xcvr_present,
+        xcvr_oper_status,
+        xcvr_inserted_type,
+        xcvr_supported_types,
+    )
+}
+
+// gets the supported breakout modes for a physical port from SAI
+// or guesses them from the port config if they cannot be queried
+fn get_supported_breakout_modes(
+    physical_port_index: usize,
+    port: &Port<'_>,
+    port_config: &Option<PhysicalPortConfig>,
+) -> Result<Vec<BreakoutModeType>, PortError> {
+    match port.get_supported_breakout_modes() {
+        Ok(v) => Ok(v),
+        Err(e) => {
+            log::warn!("Physical Port {}: failed to get supported breakout modes: {:?}. Guessing supported breakout modes from port config", physical_port_index, e);
+            match port_config {
+                Some(pc) => Ok(pc.get_supported_breakout_mode_types()),
+                None => {
+                    log::error!("Physical Port {}: no port config available. Port config must be available if it cannot be queried through SAI", physical_port_index);
+                    Err(PortError::PortConfigUnavailable)
+                }
+            }
+        }
+    }
+}
+
 fn log_port_error(port: &Port<'_>, e: sai::Error) {
     log::error!("Port {}: SAI command failed: {:?}", port, e);
 }
@@ -935,6 +2138,8 @@ pub(crate) struct HostInterface<'a> {
     pub(crate) lldp_socket: Option<Arc<LLDPSocket>>,
     pub(crate) lldp_tlvs: Option<LLDPTLVs>,
     pub(crate) lldp_network_config: Option<NetworkConfig>,
+    /// the network config which we have programmed onto the interface ourselves
+    pub(crate) lldp_network_config_applied: Option<NetworkConfig>,
 }
 
 impl<'a> HostInterface<'a> {
@@ -943,6 +2148,16 @@ impl<'a> HostInterface<'a> {
         oper_status: bool,
         processor_sender: Sender<ProcessRequest>,
     ) -> Result<(), HostInterfaceError> {
+        // the neighbor is gone when we take the interface down, and so is its network config
+        if !oper_status {
+            if let Err(e) = self.remove_network_config() {
+                log::error!(
+                    "Host Interface {}: failed to remove LLDP network config: {:?}",
+                    self.name,
+                    e
+                );
+            }
+        }
         self.intf.set_oper_status(oper_status)?;
         netlink::set_link_status(self.idx, oper_status)?;
         self.oper_status = oper_status;
@@ -958,6 +2173,40 @@ impl<'a> HostInterface<'a> {
         Ok(())
     }
 
+    /// programs the address and routes of the LLDP network config onto the interface
+    /// NOTE: a different config that was applied before gets removed first
+    pub(crate) fn apply_network_config(&mut self) -> Result<(), HostInterfaceError> {
+        let config = match self.lldp_network_config {
+            Some(ref config) => config.clone(),
+            None => return Ok(()),
+        };
+        if self.lldp_network_config_applied.as_ref() == Some(&config) {
+            return Ok(());
+        }
+        self.remove_network_config()?;
+        netlink::add_network_config(self.idx, &config)?;
+        log::info!(
+            "Host Interface {}: applied LLDP network config {:?}",
+            self.name,
+            config
+        );
+        self.lldp_network_config_applied = Some(config);
+        Ok(())
+    }
+
+    /// removes the address and routes of an applied LLDP network config from the interface
+    pub(crate) fn remove_network_config(&mut self) -> Result<(), HostInterfaceError> {
+        if let Some(config) = self.lldp_network_config_applied.take() {
+            netlink::del_network_config(self.idx, &config)?;
+            log::info!(
+                "Host Interface {}: removed LLDP network config {:?}",
+                self.name,
+                config
+            );
+        }
+        Ok(())
+    }
+
     fn start_lldp_recv_thread(&mut self, processor_sender: Sender<ProcessRequest>) {
         if self.lldp_socket.is_none() {
             match LLDPSocket::new(self.idx as i32) {
@@ -982,6 +2231,32 @@ impl<'a> HostInterface<'a> {
                                         }
                                     };
 
+                                    // the neighbor is gone if we don't hear from it again within its TTL,
+                                    // and a TTL of 0 means that it is shutting down right now
+                                    let ttl = lldp_tlvs.get_ttl();
+                                    if let Err(e) = socket.set_recv_timeout(
+                                        ttl.map(|v| Duration::from_secs(v as u64)),
+                                    ) {
+                                        log::error!(
+                                            "Host Interface {}: failed to set LLDP receive timeout: {:?}",
+                                            hif_name,
+                                            e
+                                        );
+                                    }
+                                    if ttl == Some(0) {
+                                        log::info!("Host Interface {hif_name}: LLDP neighbor is shutting down");
+                                        if let Err(e) = processor_sender
+                                            .send(ProcessRequest::LLDPNeighborExpired(hif_idx))
+                                        {
+                                            log::error!(
+                                                "Host Interface {}: failed to send LLDP neighbor expiry to processor thread: {:?}",
+                                                hif_name,
+                                                e
+                                            );
+                                        }
+                                        continue;
+                                    }
+
                                     // send the LLDP TLVs to the processor thread
                                     // this is for general LLDP information that we received on this host interface
                                     // which can be queried through onie-saictl
@@ -1016,6 +2291,26 @@ impl<'a> HostInterface<'a> {
                                         }
                                     }
                                 }
+                                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                                    // the receive timeout is the TTL of the last LLDP packet
+                                    log::info!("Host Interface {hif_name}: LLDP neighbor expired");
+                                    if let Err(e) = socket.set_recv_timeout(None) {
+                                        log::error!(
+                                            "Host Interface {}: failed to reset LLDP receive timeout: {:?}",
+                                            hif_name,
+                                            e
+                                        );
+                                    }
+                                    if let Err(e) = processor_sender
+                                        .send(ProcessRequest::LLDPNeighborExpired(hif_idx))
+                                    {
+                                        log::error!(
+                                            "Host Interface {}: failed to send LLDP neighbor expiry to processor thread: {:?}",
+                                            hif_name,
+                                            e
+                                        );
+                                    }
+                                }
                                 Err(e) => {
                                     // an error receiving most likely means that the socket was closed
                                     // we simply abort the thread for now until we know exactly what error to watch for
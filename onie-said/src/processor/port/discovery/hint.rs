@@ -0,0 +1,256 @@
+use sai::port::BreakoutModeType;
+use xcvr::PortType;
+use xcvr::TransceiverInfo;
+
+use crate::processor::port::media_settings::compliance_code;
+
+/// The breakout mode and the speed which we try first for a physical port.
+/// It is derived from the inserted transceiver, so that most ports link on the first attempt.
+/// Discovery falls back to walking all breakout modes and speeds if the port does not come up with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DiscoveryHint {
+    pub(crate) breakout_mode: BreakoutModeType,
+    /// the speed of every logical port in the breakout mode
+    pub(crate) speed: u32,
+}
+
+impl std::fmt::Display for DiscoveryHint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", self.breakout_mode, self.speed)
+    }
+}
+
+impl DiscoveryHint {
+    /// derives the hint from the compliance code of the transceiver first, and from its type otherwise
+    /// `num_lanes` is the number of lanes of the physical port
+    pub(crate) fn from_xcvr(
+        num_lanes: usize,
+        port_type: Option<PortType>,
+        info: Option<&TransceiverInfo>,
+    ) -> Option<Self> {
+        let (speed, lanes) = info
+            .and_then(|info| compliance_code(&info.specification_compliance))
+            .and_then(|code| parse_compliance_code(&code, port_type))
+            .or_else(|| port_type.and_then(port_type_defaults))?;
+        let lanes = host_lanes(speed, lanes, num_lanes);
+
+        // the lanes of the media must fit into the lanes of the physical port
+        if lanes == 0 || lanes > num_lanes || num_lanes % lanes != 0 {
+            return None;
+        }
+        let breakout_mode = match lanes {
+            1 => BreakoutModeType::OneLane,
+            2 => BreakoutModeType::TwoLanes,
+            4 => BreakoutModeType::FourLanes,
+            8 => BreakoutModeType::EightLanes,
+            _ => return None,
+        };
+        Some(DiscoveryHint {
+            breakout_mode: breakout_mode,
+            speed: speed,
+        })
+    }
+}
+
+/// speed and lanes for the transceiver types if we know nothing else about the transceiver
+fn port_type_defaults(port_type: PortType) -> Option<(u32, usize)> {
+    match port_type {
+        PortType::SFP => Some((1000, 1)),
+        PortType::SFPPlus => Some((10000, 1)),
+        PortType::SFP28 => Some((25000, 1)),
+        PortType::QSFP | PortType::QSFPPlus => Some((40000, 4)),
+        PortType::QSFP28 => Some((100000, 4)),
+        PortType::QSFP56 => Some((200000, 4)),
+        PortType::QSFPDD | PortType::OSFP => Some((400000, 8)),
+        _ => None,
+    }
+}
+
+/// The lanes of the compliance code are media lanes. From 400G on, they differ from the lanes
+/// on the port side, which run at 50G (or 100G) per lane: `400GBASE-DR4` needs 8 lanes at 50G.
+fn host_lanes(speed: u32, media_lanes: usize, num_lanes: usize) -> usize {
+    if speed < 400000 {
+        return media_lanes;
+    }
+    [speed / 50000, speed / 100000]
+        .into_iter()
+        .map(|lanes| lanes as usize)
+        .find(|lanes| *lanes <= num_lanes)
+        .unwrap_or(media_lanes)
+}
+
+/// parses speed and lanes from an ethernet compliance code
+/// e.g. `100GBASE-CR4` is 100G on 4 lanes, while `25GBASE-CR CA-L` is 25G on a single lane which means
+/// that it is a breakout cable if it is plugged into a QSFP28 port. Codes without a media type like
+/// `40G Active Cable (XLPPI)` use all the lanes of the transceiver type.
+fn parse_compliance_code(code: &str, port_type: Option<PortType>) -> Option<(u32, usize)> {
+    code.split(|c: char| c.is_whitespace() || c == ',')
+        .find_map(|token| {
+            let digits_len = token.find(|c: char| !c.is_ascii_digit())?;
+            let speed = token[..digits_len].parse::<u32>().ok()?;
+            let rest = token[digits_len..].strip_prefix('G')?;
+            let lanes = match rest.strip_prefix("BASE-") {
+                Some(media) => media
+                    .chars()
+                    .last()
+                    .and_then(|c| c.to_digit(10))
+                    .map(|v| v as usize)
+                    .unwrap_or(1),
+                None => port_type
+                    .and_then(port_type_defaults)
+                    .map(|(_, lanes)| lanes)?,
+            };
+            Some((speed * 1000, lanes))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transceiver(specification_compliance: &str) -> TransceiverInfo {
+        TransceiverInfo {
+            specification_compliance: specification_compliance.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn hint(breakout_mode: BreakoutModeType, speed: u32) -> Option<DiscoveryHint> {
+        Some(DiscoveryHint {
+            breakout_mode: breakout_mode,
+            speed: speed,
+        })
+    }
+
+    #[test]
+    fn parse_compliance_code_known_codes() {
+        assert_eq!(
+            parse_compliance_code("100GBASE-CR4", None),
+            Some((100000, 4))
+        );
+        assert_eq!(parse_compliance_code("10GBASE-SR", None), Some((10000, 1)));
+        assert_eq!(
+            parse_compliance_code("400GBASE-DR4", None),
+            Some((400000, 4))
+        );
+        // a breakout cable
+        assert_eq!(
+            parse_compliance_code("25GBASE-CR CA-L", Some(PortType::QSFP28)),
+            Some((25000, 1))
+        );
+        // no media type: all the lanes of the transceiver type
+        assert_eq!(
+            parse_compliance_code("40G Active Cable (XLPPI)", Some(PortType::QSFPPlus)),
+            Some((40000, 4))
+        );
+    }
+
+    #[test]
+    fn parse_compliance_code_unknown_codes() {
+        assert_eq!(
+            parse_compliance_code("40G Active Cable (XLPPI)", None),
+            None
+        );
+        assert_eq!(parse_compliance_code("1000BASE-T", None), None);
+        assert_eq!(
+            parse_compliance_code("Unknown", Some(PortType::QSFP28)),
+            None
+        );
+        assert_eq!(parse_compliance_code("", Some(PortType::QSFP28)), None);
+    }
+
+    #[test]
+    fn port_type_defaults_lanes() {
+        assert_eq!(port_type_defaults(PortType::SFP), Some((1000, 1)));
+        assert_eq!(port_type_defaults(PortType::SFP28), Some((25000, 1)));
+        assert_eq!(port_type_defaults(PortType::QSFPPlus), Some((40000, 4)));
+        assert_eq!(port_type_defaults(PortType::QSFP28), Some((100000, 4)));
+        assert_eq!(port_type_defaults(PortType::QSFPDD), Some((400000, 8)));
+        assert_eq!(port_type_defaults(PortType::OSFP), Some((400000, 8)));
+        assert_eq!(port_type_defaults(PortType::RJ45), None);
+        assert_eq!(port_type_defaults(PortType::Unknown(42)), None);
+    }
+
+    #[test]
+    fn host_lanes_from_media_lanes() {
+        // below 400G the media lanes are the host lanes
+        assert_eq!(host_lanes(100000, 4, 4), 4);
+        assert_eq!(host_lanes(200000, 4, 8), 4);
+        // 50G lanes first, 100G lanes if the port does not have enough lanes for them
+        assert_eq!(host_lanes(400000, 4, 8), 8);
+        assert_eq!(host_lanes(400000, 4, 4), 4);
+        assert_eq!(host_lanes(800000, 8, 8), 8);
+        // nothing fits: stick with the media lanes, which are going to be rejected
+        assert_eq!(host_lanes(400000, 8, 2), 8);
+    }
+
+    #[test]
+    fn from_xcvr_compliance_code() {
+        assert_eq!(
+            DiscoveryHint::from_xcvr(
+                4,
+                Some(PortType::QSFP28),
+                Some(&transceiver("25GBASE-CR CA-L"))
+            ),
+            hint(BreakoutModeType::OneLane, 25000)
+        );
+        assert_eq!(
+            DiscoveryHint::from_xcvr(
+                4,
+                Some(PortType::QSFPPlus),
+                Some(&transceiver(
+                    "{'10/40G Ethernet Compliance Code': '40GBASE-SR4'}"
+                ))
+            ),
+            hint(BreakoutModeType::FourLanes, 40000)
+        );
+        assert_eq!(
+            DiscoveryHint::from_xcvr(
+                8,
+                Some(PortType::QSFPDD),
+                Some(&transceiver("400GBASE-DR4"))
+            ),
+            hint(BreakoutModeType::EightLanes, 400000)
+        );
+    }
+
+    #[test]
+    fn from_xcvr_port_type_defaults() {
+        assert_eq!(
+            DiscoveryHint::from_xcvr(4, Some(PortType::QSFP28), None),
+            hint(BreakoutModeType::FourLanes, 100000)
+        );
+        // 400G on a 4 lane port runs at 100G per lane
+        assert_eq!(
+            DiscoveryHint::from_xcvr(4, Some(PortType::QSFPDD), None),
+            hint(BreakoutModeType::FourLanes, 400000)
+        );
+        // an unknown compliance code falls back to the transceiver type
+        assert_eq!(
+            DiscoveryHint::from_xcvr(1, Some(PortType::SFP), Some(&transceiver("1000BASE-T"))),
+            hint(BreakoutModeType::OneLane, 1000)
+        );
+    }
+
+    #[test]
+    fn from_xcvr_no_hint() {
+        assert_eq!(DiscoveryHint::from_xcvr(4, None, None), None);
+        assert_eq!(
+            DiscoveryHint::from_xcvr(4, None, Some(&transceiver("Unknown"))),
+            None
+        );
+        assert_eq!(
+            DiscoveryHint::from_xcvr(4, Some(PortType::RJ45), None),
+            None
+        );
+        // the lanes of the media do not fit into the port
+        assert_eq!(
+            DiscoveryHint::from_xcvr(1, Some(PortType::QSFP28), None),
+            None
+        );
+        assert_eq!(
+            DiscoveryHint::from_xcvr(2, Some(PortType::QSFPDD), None),
+            None
+        );
+    }
+}
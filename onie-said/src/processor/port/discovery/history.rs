@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+/// the number of entries that we keep per port: older entries get dropped
+const MAX_ENTRIES: usize = 32;
+
+#[derive(Debug, Clone)]
+pub(crate) struct DiscoveryHistoryEntry {
+    pub(crate) ts: SystemTime,
+    /// not set for the initial state of a state machine
+    pub(crate) from: Option<String>,
+    pub(crate) to: String,
+    /// the port settings after the transition
+    pub(crate) details: String,
+}
+
+/// A bounded history of the state transitions of a discovery state machine.
+/// It answers the question which combinations were tried when a port does not come up.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DiscoveryHistory {
+    entries: VecDeque<DiscoveryHistoryEntry>,
+    /// the number of settings which discovery tried on the port, including the ones of dropped entries
+    attempts: u64,
+}
+
+impl DiscoveryHistory {
+    /// Records a transition. `attempt` is set if the port got new settings to try with it: this is
+    /// recorded and counted even if the state did not change, as it is a retry. All other transitions
+    /// are only recorded if the state changed.
+    pub(crate) fn record(
+        &mut self,
+        from: Option<String>,
+        to: String,
+        details: String,
+        attempt: bool,
+    ) {
+        if !attempt && from.as_ref() == Some(&to) {
+            return;
+        }
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(DiscoveryHistoryEntry {
+            ts: SystemTime::now(),
+            from: from,
+            to: to,
+            details: details,
+        });
+        if attempt {
+            self.attempts += 1;
+        }
+    }
+
+    pub(crate) fn entries(&self) -> impl Iterator<Item = &DiscoveryHistoryEntry> {
+        self.entries.iter()
+    }
+
+    pub(crate) fn attempts(&self) -> u64 {
+        self.attempts
+    }
+}
+
+// just a convenience conversion method for our RPC
+impl From<&DiscoveryHistoryEntry> for onie_sai_rpc::onie_sai::DiscoveryHistoryEntry {
+    fn from(entry: &DiscoveryHistoryEntry) -> Self {
+        onie_sai_rpc::onie_sai::DiscoveryHistoryEntry {
+            timestamp_ms: entry
+                .ts
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            from: entry.from.clone(),
+            to: entry.to.clone(),
+            details: entry.details.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(history: &mut DiscoveryHistory, from: &str, to: &str, attempt: bool) {
+        history.record(
+            Some(from.to_string()),
+            to.to_string(),
+            String::new(),
+            attempt,
+        );
+    }
+
+    #[test]
+    fn record_attempts() {
+        let mut history = DiscoveryHistory::default();
+        history.record(None, "START".to_string(), String::new(), false);
+        record(&mut history, "START", "AUTO_NEG", true);
+        record(&mut history, "AUTO_NEG", "SPEED[1=40000]", true);
+        record(&mut history, "SPEED[1=40000]", "DONE[DOWN]", false);
+        assert_eq!(history.entries().count(), 4);
+        assert_eq!(history.attempts(), 2);
+        assert_eq!(history.entries().next().unwrap().from, None);
+        assert_eq!(history.entries().last().unwrap().to, "DONE[DOWN]");
+    }
+
+    #[test]
+    fn record_dedupe() {
+        let mut history = DiscoveryHistory::default();
+        record(&mut history, "START", "AUTO_NEG", true);
+
+        // the state machine could not step yet
+        record(&mut history, "AUTO_NEG", "AUTO_NEG", false);
+        assert_eq!(history.entries().count(), 1);
+        assert_eq!(history.attempts(), 1);
+
+        // a retry in the same state
+        record(&mut history, "AUTO_NEG", "AUTO_NEG", true);
+        assert_eq!(history.entries().count(), 2);
+        assert_eq!(history.attempts(), 2);
+    }
+
+    #[test]
+    fn record_max_entries() {
+        let mut history = DiscoveryHistory::default();
+        for i in 0..MAX_ENTRIES + 8 {
+            record(
+                &mut history,
+                &format!("SPEED[{i}]"),
+                &format!("SPEED[{}]", i + 1),
+                true,
+            );
+        }
+        assert_eq!(history.entries().count(), MAX_ENTRIES);
+        // the oldest entries are dropped, but they still count as attempts
+        assert_eq!(history.attempts(), MAX_ENTRIES as u64 + 8);
+        assert_eq!(
+            history.entries().next().unwrap().from.as_deref(),
+            Some("SPEED[8]")
+        );
+        assert_eq!(
+            history.entries().last().unwrap().to,
+            format!("SPEED[{}]", MAX_ENTRIES + 8)
+        );
+    }
+}
@@ -1,9 +1,12 @@
-use sai::port::{AutoNegConfigMode, Port};
+use sai::port::{AutoNegConfigMode, FECMode, Port};
 use std::{
     fmt::Display,
+    sync::Arc,
     time::{Duration, SystemTime},
 };
 
+use super::profile::{DiscoveryProfile, DiscoveryStrategy};
+
 pub(crate) trait State
 where
     Self: std::fmt::Debug + Display + Clone + Copy,
@@ -41,25 +44,65 @@ pub(crate) enum DiscoveryStateMachine {
     Start(Discovery<Start>),
     AutoNeg(Discovery<AutoNeg>),
     Speed(Discovery<Speed>),
+    Fec(Discovery<Fec>),
     Done(Discovery<Done>),
 }
 
 impl DiscoveryStateMachine {
     /// initializes a new logical port discovery state machine
+    /// if `preferred_speed` is set (and supported), the port starts out with it instead of the highest supported speed
+    /// if `preferred_fec` is set, every speed is tried with it first if it is valid for the speed
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new<'a>(
         port: &Port<'a>,
         supported_speeds: Vec<u32>,
         speed: u32,
         auto_negotiation: bool,
+        fec: FECMode,
+        preferred_speed: Option<u32>,
+        preferred_fec: Option<FECMode>,
+        profile: Arc<DiscoveryProfile>,
     ) -> Self {
         DiscoveryStateMachine::Start(Discovery::new(
             port,
             supported_speeds,
             speed,
             auto_negotiation,
+            fec,
+            preferred_speed,
+            preferred_fec,
+            profile,
         ))
     }
 
+    /// initializes a logical port discovery state machine which is already done
+    /// this is used when we restore the state after a warm boot, and must not touch the port
+    pub(crate) fn restored<'a>(
+        port: &Port<'a>,
+        supported_speeds: Vec<u32>,
+        speed: u32,
+        auto_negotiation: bool,
+        fec: FECMode,
+        success: bool,
+        profile: Arc<DiscoveryProfile>,
+    ) -> Self {
+        let mut supported_speeds = supported_speeds;
+        supported_speeds.sort();
+        let state = Done { success: success };
+        log::debug!("Port {}: state machine: {}: restored", port, state);
+        DiscoveryStateMachine::Done(Discovery {
+            transition_ts: SystemTime::now(),
+            transition_time: Default::default(),
+            speed: speed,
+            auto_negotiation: auto_negotiation,
+            fec: fec,
+            preferred_fec: None,
+            supported_speeds: supported_speeds,
+            profile: profile,
+            state: state,
+        })
+    }
+
     pub(crate) fn is_done(&self) -> bool {
         match self {
             DiscoveryStateMachine::Done(_) => true,
@@ -74,11 +117,22 @@ impl DiscoveryStateMachine {
         }
     }
 
+    /// if the port tries a combination of settings in this state
+    pub(crate) fn is_attempt(&self) -> bool {
+        match self {
+            DiscoveryStateMachine::AutoNeg(_)
+            | DiscoveryStateMachine::Speed(_)
+            | DiscoveryStateMachine::Fec(_) => true,
+            DiscoveryStateMachine::Start(_) | DiscoveryStateMachine::Done(_) => false,
+        }
+    }
+
     pub(crate) fn can_step(&self) -> bool {
         match self {
             DiscoveryStateMachine::Start(s) => s.can_step(),
             DiscoveryStateMachine::AutoNeg(s) => s.can_step(),
             DiscoveryStateMachine::Speed(s) => s.can_step(),
+            DiscoveryStateMachine::Fec(s) => s.can_step(),
             DiscoveryStateMachine::Done(s) => s.can_step(),
         }
     }
@@ -99,6 +153,9 @@ impl DiscoveryStateMachine {
                 DiscoveryStateMachine::Speed(v) => {
                     DiscoveryStateMachine::Done(Discovery::success(v, port))
                 }
+                DiscoveryStateMachine::Fec(v) => {
+                    DiscoveryStateMachine::Done(Discovery::success(v, port))
+                }
                 DiscoveryStateMachine::Done(v) => {
                     DiscoveryStateMachine::Done(Discovery::success(v, port))
                 }
@@ -111,18 +168,36 @@ impl DiscoveryStateMachine {
                 }
 
                 // if we can step, then we perform the *actual* state transitions right now here
+                // the order of the strategies comes from the discovery profile
                 match self {
-                    DiscoveryStateMachine::Start(v) => {
-                        DiscoveryStateMachine::Speed(v.into_state(port))
+                    DiscoveryStateMachine::Start(v) => into_next_strategy(v, port, None),
+                    // every speed gets tried with all the FEC modes which are valid for it
+                    DiscoveryStateMachine::Speed(v)
+                        if v.profile.fec
+                            && fec_modes_for_speed(v.speed, v.preferred_fec).len() > 1 =>
+                    {
+                        DiscoveryStateMachine::Fec(v.into_state(port))
                     }
                     DiscoveryStateMachine::Speed(v) if v.state.index == 0 => {
-                        DiscoveryStateMachine::AutoNeg(v.into_state(port))
+                        into_next_strategy(v, port, Some(DiscoveryStrategy::Speed))
                     }
                     DiscoveryStateMachine::Speed(v) => {
                         DiscoveryStateMachine::Speed(v.into_state(port))
                     }
+                    DiscoveryStateMachine::Fec(v)
+                        if v.state.index + 1
+                            < fec_modes_for_speed(v.speed, v.preferred_fec).len() =>
+                    {
+                        DiscoveryStateMachine::Fec(v.into_state(port))
+                    }
+                    DiscoveryStateMachine::Fec(v) if v.state.speed_index == 0 => {
+                        into_next_strategy(v, port, Some(DiscoveryStrategy::Speed))
+                    }
+                    DiscoveryStateMachine::Fec(v) => {
+                        DiscoveryStateMachine::Speed(v.into_state(port))
+                    }
                     DiscoveryStateMachine::AutoNeg(v) => {
-                        DiscoveryStateMachine::Done(v.into_state(port))
+                        into_next_strategy(v, port, Some(DiscoveryStrategy::AutoNeg))
                     }
                     DiscoveryStateMachine::Done(_) => self,
                 }
@@ -131,13 +206,29 @@ impl DiscoveryStateMachine {
     }
 }
 
+impl Display for DiscoveryStateMachine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiscoveryStateMachine::Start(v) => v.state.fmt(f),
+            DiscoveryStateMachine::AutoNeg(v) => v.state.fmt(f),
+            DiscoveryStateMachine::Speed(v) => v.state.fmt(f),
+            DiscoveryStateMachine::Fec(v) => v.state.fmt(f),
+            DiscoveryStateMachine::Done(v) => v.state.fmt(f),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Discovery<S: State> {
     transition_ts: SystemTime,
     transition_time: Duration,
     speed: u32,
     auto_negotiation: bool,
+    fec: FECMode,
+    /// FEC discovery starts with this FEC mode if it is valid for the speed
+    preferred_fec: Option<FECMode>,
     supported_speeds: Vec<u32>,
+    profile: Arc<DiscoveryProfile>,
     state: S,
 }
 
@@ -203,15 +294,73 @@ fn disable_auto_negotiation<'a, S: State, T: State>(
     }
 }
 
+/// returns the FEC modes which are valid for a speed in the order in which we are going to try them
+/// the preferred FEC mode goes first if it is valid for the speed
+fn fec_modes_for_speed(speed: u32, preferred: Option<FECMode>) -> Vec<FECMode> {
+    let mut ret = match speed {
+        // 10G and 40G (4x10G) only know about FC-FEC (BASE-R)
+        10000 | 40000 => vec![FECMode::None, FECMode::FC],
+        // 25G and 50G (2x25G) links come with all flavours, but RS-FEC is the most common one
+        25000 | 50000 => vec![FECMode::RS, FECMode::FC, FECMode::None],
+        // 100G (4x25G) is RS-FEC for copper and short reach optics, but none for long reach optics
+        100000 => vec![FECMode::RS, FECMode::None],
+        // everything above is PAM4 which requires RS-FEC
+        x if x > 100000 => vec![FECMode::RS],
+        _ => vec![FECMode::None],
+    };
+    if let Some(pos) = preferred.and_then(|fec| ret.iter().position(|v| *v == fec)) {
+        let fec = ret.remove(pos);
+        ret.insert(0, fec);
+    }
+    ret
+}
+
+fn set_fec_mode<'a, S: State, T: State>(
+    from_state: S,
+    to_state: T,
+    port: &Port<'a>,
+    current_fec: FECMode,
+    fec: FECMode,
+) -> FECMode {
+    match port.set_fec_mode(fec) {
+        Ok(_) => {
+            log::debug!(
+                "Port {}: state machine: {} -> {}: set FEC mode to {:?} successful",
+                port,
+                from_state,
+                to_state,
+                fec
+            );
+            fec
+        }
+        Err(e) => {
+            log::error!(
+                "Port {}: state machine: {} -> {}: failed to set FEC mode to {:?}: {}",
+                port,
+                from_state,
+                to_state,
+                fec,
+                e
+            );
+            current_fec
+        }
+    }
+}
+
 impl Discovery<Start> {
-    /// Creates a new state machine for the given port. The current supported speeds, speed and auto negotiation settings
+    /// Creates a new state machine for the given port. The current supported speeds, speed, auto negotiation and FEC settings
     /// of the port must be passed in. The state machine will reconfigure them if they are not at the right state for the
     /// beginning of the state machine.
+    #[allow(clippy::too_many_arguments)]
     fn new<'a>(
         port: &Port<'a>,
         supported_speeds: Vec<u32>,
         speed: u32,
         auto_negotiation: bool,
+        fec: FECMode,
+        preferred_speed: Option<u32>,
+        preferred_fec: Option<FECMode>,
+        profile: Arc<DiscoveryProfile>,
     ) -> Self {
         let state = Start {};
 
@@ -226,19 +375,20 @@ impl Discovery<Start> {
         // ensure auto negotiation is disabled
         let auto_negotiation = disable_auto_negotiation(state, state, port, auto_negotiation);
 
-        // ensure speed is set to the highest supported speed
-        let speed = supported_speeds
-            .last()
-            .map(|highest_speed| {
-                if speed != *highest_speed {
-                    match port.set_speed(*highest_speed) {
-                        Ok(_) => *highest_speed,
+        // ensure speed is set to the preferred speed if it is supported, or the highest supported speed otherwise
+        let speed = preferred_speed
+            .filter(|preferred_speed| supported_speeds.contains(preferred_speed))
+            .or(supported_speeds.last().copied())
+            .map(|start_speed| {
+                if speed != start_speed {
+                    match port.set_speed(start_speed) {
+                        Ok(_) => start_speed,
                         Err(e) => {
                             log::error!(
                                 "Port {} state machine: {}: failed to set speed to {}: {}",
                                 port,
                                 state,
-                                highest_speed,
+                                start_speed,
                                 e
                             );
                             speed
@@ -250,21 +400,38 @@ impl Discovery<Start> {
             })
             .unwrap_or(speed);
 
+        // FEC modes are only valid for certain speeds, so this needs to happen after setting the speed
+        let fec = if profile.fec {
+            set_fec_mode(
+                state,
+                state,
+                port,
+                fec,
+                fec_modes_for_speed(speed, preferred_fec)[0],
+            )
+        } else {
+            fec
+        };
+
         // bring admin state up
         set_admin_state(state, state, port, true);
 
         log::debug!(
-            "Port {} state machine: {}: initialized. Supported Speeds: {:?}",
+            "Port {} state machine: {}: initialized. Speed: {}, Supported Speeds: {:?}",
             port,
             state,
+            speed,
             supported_speeds.clone()
         );
         Discovery {
             transition_ts: SystemTime::now(),
-            transition_time: Duration::from_secs(5),
+            transition_time: profile.start_dwell(),
             auto_negotiation: auto_negotiation,
             speed: speed,
+            fec: fec,
+            preferred_fec: preferred_fec,
             supported_speeds: supported_speeds,
+            profile: profile,
             state: state,
         }
     }
@@ -277,83 +444,116 @@ impl Discovery<Done> {
             transition_time: Default::default(),
             speed: from.speed,
             auto_negotiation: from.auto_negotiation,
+            fec: from.fec,
+            preferred_fec: from.preferred_fec,
             supported_speeds: from.supported_speeds,
+            profile: from.profile,
             state: Done { success: true },
         };
         log::info!(
-            "Port {}: state machine: {} -> {}: successfully brought up port",
+            "Port {}: state machine: {} -> {}: successfully brought up port (speed: {}, auto negotiation: {}, FEC: {:?})",
             port,
             from.state,
-            ret.state
+            ret.state,
+            ret.speed,
+            ret.auto_negotiation,
+            ret.fec
         );
         ret
     }
 }
 
-impl FromState<Start> for Speed {
-    fn from_state<'a>(from: Discovery<Start>, port: &Port<'a>) -> Discovery<Self> {
-        // we are going to work our way down from the highest supported speed
-        let idx = from.supported_speeds.len();
+/// transitions to the speed at `index` of the supported speeds
+/// the speed is always tried with the first FEC mode which is valid for it first
+fn into_speed<'a, S: State>(from: Discovery<S>, port: &Port<'a>, index: usize) -> Discovery<Speed> {
+    // get the speed that we are going to work with
+    let state = Speed {
+        index: index,
+        speed: from.supported_speeds[index],
+    };
 
-        // if we have zero length, then the supported speeds were empty for some reason, nothing to do here then
-        if idx == 0 {
-            let state = Speed { index: 0, speed: 0 };
-            log::warn!(
-                "Port {}: state machine: {} -> {}: no supported speeds found",
-                port,
-                from.state,
-                state
-            );
-            return Discovery {
-                transition_ts: SystemTime::now(),
-                transition_time: Default::default(),
-                speed: from.speed,
-                auto_negotiation: from.auto_negotiation,
-                supported_speeds: from.supported_speeds,
-                state: state,
-            };
-        }
+    // bring the port admin state down
+    set_admin_state(from.state, state, port, false);
 
-        // get the speed that we are going to work with
-        let state = Speed {
-            index: idx - 1,
-            speed: from.supported_speeds[idx - 1],
-        };
+    // we might be coming from auto negotiation if the profile tries it before the speeds
+    let auto_negotiation = disable_auto_negotiation(from.state, state, port, from.auto_negotiation);
 
-        // bring the port admin state down
-        set_admin_state(from.state, state, port, false);
+    match port.set_speed(state.speed) {
+        Ok(_) => log::debug!(
+            "Port {}: state machine: {} -> {}: set speed to {} successful",
+            port,
+            from.state,
+            state,
+            state.speed
+        ),
+        Err(e) => log::error!(
+            "Port {}: state machine: {} -> {}: failed to set speed to {}: {}",
+            port,
+            from.state,
+            state,
+            state.speed,
+            e
+        ),
+    }
 
-        match port.set_speed(state.speed) {
-            Ok(_) => log::debug!(
-                "Port {}: state machine: {} -> {}: set speed to {} successful",
-                port,
-                from.state,
-                state,
-                state.speed
-            ),
-            Err(e) => log::error!(
-                "Port {}: state machine: {} -> {}: failed to set speed to {}: {}",
-                port,
-                from.state,
-                state,
-                state.speed,
-                e
-            ),
-        }
+    // FEC modes are only valid for certain speeds, so this needs to happen after setting the speed
+    let fec = if from.profile.fec {
+        set_fec_mode(
+            from.state,
+            state,
+            port,
+            from.fec,
+            fec_modes_for_speed(state.speed, from.preferred_fec)[0],
+        )
+    } else {
+        from.fec
+    };
+
+    // and bring the admin state up again
+    set_admin_state(from.state, state, port, true);
+
+    // the profile tells us how much time we give a speed change to bring up a port
+    Discovery {
+        transition_ts: SystemTime::now(),
+        transition_time: from.profile.speed_dwell(),
+        speed: state.speed,
+        auto_negotiation: auto_negotiation,
+        fec: fec,
+        preferred_fec: from.preferred_fec,
+        supported_speeds: from.supported_speeds,
+        profile: from.profile,
+        state: state,
+    }
+}
 
-        // and bring the admin state up again
-        set_admin_state(from.state, state, port, true);
+/// transitions to the highest supported speed: this is where the speed strategy starts
+fn into_first_speed<'a, S: State>(from: Discovery<S>, port: &Port<'a>) -> Discovery<Speed> {
+    // we are going to work our way down from the highest supported speed
+    let idx = from.supported_speeds.len();
 
-        // we are going to give a speed change up to 5 seconds to bring up a port
-        Discovery {
+    // if we have zero length, then the supported speeds were empty for some reason, nothing to do here then
+    if idx == 0 {
+        let state = Speed { index: 0, speed: 0 };
+        log::warn!(
+            "Port {}: state machine: {} -> {}: no supported speeds found",
+            port,
+            from.state,
+            state
+        );
+        return Discovery {
             transition_ts: SystemTime::now(),
-            transition_time: Duration::from_secs(5),
-            speed: state.speed,
+            transition_time: Default::default(),
+            speed: from.speed,
             auto_negotiation: from.auto_negotiation,
+            fec: from.fec,
+            preferred_fec: from.preferred_fec,
             supported_speeds: from.supported_speeds,
+            profile: from.profile,
             state: state,
-        }
+        };
     }
+
+    into_speed(from, port, idx - 1)
 }
 
 impl FromState<Speed> for Speed {
@@ -370,184 +570,256 @@ impl FromState<Speed> for Speed {
         }
 
         // get the next speed that we need to try
-        let state = Speed {
-            index: from.state.index - 1,
-            speed: from.supported_speeds[from.state.index - 1],
-        };
-
-        // bring the port admin state down
-        set_admin_state(from.state, state, port, false);
-
-        match port.set_speed(state.speed) {
-            Ok(_) => log::debug!(
-                "Port {}: state machine: {} -> {}: set speed to {} successful",
-                port,
-                from.state,
-                state,
-                state.speed
-            ),
-            Err(e) => log::error!(
-                "Port {}: state machine: {} -> {}: failed to set speed to {}: {}",
-                port,
-                from.state,
-                state,
-                state.speed,
-                e
-            ),
-        }
-
-        // and bring the admin state up again
-        set_admin_state(from.state, state, port, true);
-
-        // we are going to give a speed change up to 5 seconds to bring up a port
-        Discovery {
-            transition_ts: SystemTime::now(),
-            transition_time: Duration::from_secs(5),
-            speed: state.speed,
-            auto_negotiation: from.auto_negotiation,
-            supported_speeds: from.supported_speeds,
-            state: state,
-        }
+        let index = from.state.index - 1;
+        into_speed(from, port, index)
     }
 }
 
-impl FromState<Speed> for AutoNeg {
-    fn from_state<'a>(from: Discovery<Speed>, port: &Port<'a>) -> Discovery<Self> {
-        let state = AutoNeg {};
-        // bring the port admin state down
-        set_admin_state(from.state, state, port, false);
-
-        // check if auto neg mode is supported
-        // if not we can essentially transition immediately again
-        let auto_neg_supported = match port.get_supported_auto_neg_mode() {
-            Ok(v) => v,
-            Err(e) => {
-                log::error!(
-                    "Port {}: state machine: {} -> {}: failed to check if auto negotiation is supported. We are assuming it is not supported: {}",
-                    port,
-                    from.state,
-                    state,
-                    e
-                );
-                false
-            }
-        };
-        if !auto_neg_supported {
-            log::warn!(
-                "Port {}: state machine: {} -> {}: auto negotiation is not supported",
+impl FromState<Fec> for Speed {
+    fn from_state<'a>(from: Discovery<Fec>, port: &Port<'a>) -> Discovery<Self> {
+        // check first if we are in an invalid state
+        if from.state.speed_index == 0 {
+            log::error!(
+                "Port {}: state machine: {} -> {}: already at lowest supported speed. State machine transition error!",
                 port,
                 from.state,
-                state
+                from.state
             );
+            let state = Speed {
+                index: 0,
+                speed: from.speed,
+            };
             return Discovery {
-                transition_ts: SystemTime::now(),
-                transition_time: Default::default(),
+                transition_ts: from.transition_ts,
+                transition_time: from.transition_time,
                 speed: from.speed,
                 auto_negotiation: from.auto_negotiation,
+                fec: from.fec,
+                preferred_fec: from.preferred_fec,
                 supported_speeds: from.supported_speeds,
+                profile: from.profile,
                 state: state,
             };
         }
 
-        // enable auto neg mode
-        match port.set_auto_neg_mode(true) {
-            Ok(_) => log::debug!(
-                "Port {}: state machine: {} -> {}: set auto negotiation mode to on successful",
-                port,
-                from.state,
-                state
-            ),
-            Err(e) => log::error!(
-                "Port {}: state machine: {} -> {}: failed to set auto negotiation mode to on: {}",
-                port,
-                from.state,
-                state,
-                e
-            ),
-        }
+        // get the next speed that we need to try
+        let index = from.state.speed_index - 1;
+        into_speed(from, port, index)
+    }
+}
 
-        // set config mode to auto
-        match port.set_auto_neg_config_mode(AutoNegConfigMode::Auto) {
-            Ok(_) => log::debug!(
-                "Port {}: state machine: {} -> {}: set auto negotiation config mode to auto successful",
-                port,
-                from.state,
-                state
-            ),
-            // it's likely that this attribute isn't supported, and then it doesn't matter that we cannot set it
-            // so we just log at debug for this
-            Err(e) => log::debug!(
-                "Port {}: state machine: {} -> {}: failed to set auto negotiation config mode to auto: {}",
-                port,
-                from.state,
-                state,
-                e
-            ),
-        }
+/// transitions to the FEC mode at `index` of the valid FEC modes for the current speed
+fn into_fec<'a, S: State>(
+    from: Discovery<S>,
+    port: &Port<'a>,
+    speed_index: usize,
+    index: usize,
+) -> Discovery<Fec> {
+    let state = Fec {
+        speed_index: speed_index,
+        index: index,
+        fec: fec_modes_for_speed(from.speed, from.preferred_fec)[index],
+    };
+
+    // bring the port admin state down
+    set_admin_state(from.state, state, port, false);
+
+    let fec = set_fec_mode(from.state, state, port, from.fec, state.fec);
+
+    // and bring the admin state up again
+    set_admin_state(from.state, state, port, true);
+
+    // the profile tells us how much time we give a FEC change to bring up a port
+    Discovery {
+        transition_ts: SystemTime::now(),
+        transition_time: from.profile.fec_dwell(),
+        speed: from.speed,
+        auto_negotiation: from.auto_negotiation,
+        fec: fec,
+        preferred_fec: from.preferred_fec,
+        supported_speeds: from.supported_speeds,
+        profile: from.profile,
+        state: state,
+    }
+}
 
-        // enable auto neg mode advertisement
-        match port.set_advertised_auto_neg_mode(true) {
-            Ok(_) => log::debug!(
-                "Port {}: state machine: {} -> {}: set auto negotiation advertisement to on successful",
-                port,
-                from.state,
-                state
-            ),
-            // it's likely that this attribute isn't supported, and then it doesn't matter that we cannot set it
-            // so we just log at debug for this
-            Err(e) => log::debug!(
-                "Port {}: state machine: {} -> {}: failed to set auto negotiation advertisement to on: {}",
+impl FromState<Speed> for Fec {
+    fn from_state<'a>(from: Discovery<Speed>, port: &Port<'a>) -> Discovery<Self> {
+        // the speed state already tried the first FEC mode, so we start with the second one
+        let speed_index = from.state.index;
+        into_fec(from, port, speed_index, 1)
+    }
+}
+
+impl FromState<Fec> for Fec {
+    fn from_state<'a>(from: Discovery<Fec>, port: &Port<'a>) -> Discovery<Self> {
+        let speed_index = from.state.speed_index;
+        let index = from.state.index + 1;
+        into_fec(from, port, speed_index, index)
+    }
+}
+
+/// transitions to auto negotiation
+fn into_auto_neg<'a, S: State>(from: Discovery<S>, port: &Port<'a>) -> Discovery<AutoNeg> {
+    let state = AutoNeg {};
+    // bring the port admin state down
+    set_admin_state(from.state, state, port, false);
+
+    // check if auto neg mode is supported
+    // if not we can essentially transition immediately again
+    let auto_neg_supported = match port.get_supported_auto_neg_mode() {
+        Ok(v) => v,
+        Err(e) => {
+            log::error!(
+                "Port {}: state machine: {} -> {}: failed to check if auto negotiation is supported. We are assuming it is not supported: {}",
                 port,
                 from.state,
                 state,
                 e
-            ),
+            );
+            false
         }
-
-        // and bring the admin state up again
-        set_admin_state(from.state, state, port, true);
-
-        // we are going to give auto negotiation up to 10 seconds to bring up a port
-        Discovery {
+    };
+    if !auto_neg_supported {
+        log::warn!(
+            "Port {}: state machine: {} -> {}: auto negotiation is not supported",
+            port,
+            from.state,
+            state
+        );
+        return Discovery {
             transition_ts: SystemTime::now(),
-            transition_time: Duration::from_secs(10),
+            transition_time: Default::default(),
             speed: from.speed,
-            auto_negotiation: true,
+            auto_negotiation: from.auto_negotiation,
+            fec: from.fec,
+            preferred_fec: from.preferred_fec,
             supported_speeds: from.supported_speeds,
+            profile: from.profile,
             state: state,
-        }
+        };
     }
-}
 
-impl FromState<AutoNeg> for Done {
-    fn from_state<'a>(from: Discovery<AutoNeg>, port: &Port<'a>) -> Discovery<Self> {
-        // if we are here that means that nothing worked to bring up the port
-        let state = Done { success: false };
-
-        // bring the port admin state down
-        // and we will leave it down
-        set_admin_state(from.state, state, port, false);
+    // enable auto neg mode
+    match port.set_auto_neg_mode(true) {
+        Ok(_) => log::debug!(
+            "Port {}: state machine: {} -> {}: set auto negotiation mode to on successful",
+            port,
+            from.state,
+            state
+        ),
+        Err(e) => log::error!(
+            "Port {}: state machine: {} -> {}: failed to set auto negotiation mode to on: {}",
+            port,
+            from.state,
+            state,
+            e
+        ),
+    }
 
-        // as we are probably coming from auto neg being enabled, we should disable it again
-        let auto_negotiation =
-            disable_auto_negotiation(from.state, state, port, from.auto_negotiation);
+    // set config mode to auto
+    match port.set_auto_neg_config_mode(AutoNegConfigMode::Auto) {
+        Ok(_) => log::debug!(
+            "Port {}: state machine: {} -> {}: set auto negotiation config mode to auto successful",
+            port,
+            from.state,
+            state
+        ),
+        // it's likely that this attribute isn't supported, and then it doesn't matter that we cannot set it
+        // so we just log at debug for this
+        Err(e) => log::debug!(
+            "Port {}: state machine: {} -> {}: failed to set auto negotiation config mode to auto: {}",
+            port,
+            from.state,
+            state,
+            e
+        ),
+    }
 
-        // we are giving up, let our users know
-        log::warn!(
-            "Port {}: state machine: {} -> {}: unable to bring up port",
+    // enable auto neg mode advertisement
+    match port.set_advertised_auto_neg_mode(true) {
+        Ok(_) => log::debug!(
+            "Port {}: state machine: {} -> {}: set auto negotiation advertisement to on successful",
             port,
             from.state,
             state
-        );
+        ),
+        // it's likely that this attribute isn't supported, and then it doesn't matter that we cannot set it
+        // so we just log at debug for this
+        Err(e) => log::debug!(
+            "Port {}: state machine: {} -> {}: failed to set auto negotiation advertisement to on: {}",
+            port,
+            from.state,
+            state,
+            e
+        ),
+    }
 
-        Discovery {
-            transition_ts: SystemTime::now(),
-            transition_time: Default::default(),
-            speed: from.speed,
-            auto_negotiation: auto_negotiation,
-            supported_speeds: from.supported_speeds,
-            state: state,
+    // and bring the admin state up again
+    set_admin_state(from.state, state, port, true);
+
+    // the profile tells us how much time we give auto negotiation to bring up a port
+    Discovery {
+        transition_ts: SystemTime::now(),
+        transition_time: from.profile.auto_neg_dwell(),
+        speed: from.speed,
+        auto_negotiation: true,
+        fec: from.fec,
+        preferred_fec: from.preferred_fec,
+        supported_speeds: from.supported_speeds,
+        profile: from.profile,
+        state: state,
+    }
+}
+
+/// transitions to a failed discovery: this is where we end up if all strategies have been tried
+fn into_failed<'a, S: State>(from: Discovery<S>, port: &Port<'a>) -> Discovery<Done> {
+    // if we are here that means that nothing worked to bring up the port
+    let state = Done { success: false };
+
+    // bring the port admin state down
+    // and we will leave it down
+    set_admin_state(from.state, state, port, false);
+
+    // as we are probably coming from auto neg being enabled, we should disable it again
+    let auto_negotiation = disable_auto_negotiation(from.state, state, port, from.auto_negotiation);
+
+    // we are giving up, let our users know
+    log::warn!(
+        "Port {}: state machine: {} -> {}: unable to bring up port",
+        port,
+        from.state,
+        state
+    );
+
+    Discovery {
+        transition_ts: SystemTime::now(),
+        transition_time: Default::default(),
+        speed: from.speed,
+        auto_negotiation: auto_negotiation,
+        fec: from.fec,
+        preferred_fec: from.preferred_fec,
+        supported_speeds: from.supported_speeds,
+        profile: from.profile,
+        state: state,
+    }
+}
+
+/// transitions to the strategy which comes after `current` in the discovery profile,
+/// or to a failed discovery if there is none left
+fn into_next_strategy<'a, S: State>(
+    from: Discovery<S>,
+    port: &Port<'a>,
+    current: Option<DiscoveryStrategy>,
+) -> DiscoveryStateMachine {
+    match from.profile.next_strategy(current) {
+        Some(DiscoveryStrategy::Speed) => {
+            DiscoveryStateMachine::Speed(into_first_speed(from, port))
+        }
+        Some(DiscoveryStrategy::AutoNeg) => {
+            DiscoveryStateMachine::AutoNeg(into_auto_neg(from, port))
         }
+        None => DiscoveryStateMachine::Done(into_failed(from, port)),
     }
 }
 
@@ -581,6 +853,19 @@ impl Display for Speed {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Fec {
+    speed_index: usize,
+    index: usize,
+    fec: FECMode,
+}
+impl State for Fec {}
+impl Display for Fec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FEC[{}={:?}]", self.index, self.fec)
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct Done {
     /// Describes if the discovery was successful or not.
@@ -1,2 +1,5 @@
+pub(crate) mod hint;
+pub(crate) mod history;
 pub(crate) mod logicalport;
 pub(crate) mod physicalport;
+pub(crate) mod profile;
@@ -1,4 +1,7 @@
 use crate::processor::port::PhysicalPort;
+use crate::processor::port::PhysicalPortConfigBreakoutMode;
+use onie_sai_common::port_config::split_lanes;
+use onie_sai_common::port_config::LaneSplitError;
 use sai::port::BreakoutModeType;
 use std::fmt::Display;
 use thiserror::Error;
@@ -52,6 +55,30 @@ impl DiscoveryStateMachine {
         ))
     }
 
+    /// initializes a physical port discovery state machine which is already done
+    /// this is used when we restore the state after a warm boot, and must not touch the port
+    pub(crate) fn restored(
+        idx: usize,
+        auto_discovery_with_breakout: bool,
+        current_breakout_mode: BreakoutModeType,
+        supported_breakout_modes: Vec<BreakoutModeType>,
+        success: bool,
+    ) -> Self {
+        let from = Discovery::internal_new(
+            auto_discovery_with_breakout,
+            current_breakout_mode,
+            supported_breakout_modes,
+        );
+        let state = Done { success: success };
+        log::debug!("Physical Port {}: state machine: {}: restored", idx, state);
+        DiscoveryStateMachine::Done(Discovery {
+            auto_discovery_with_breakout: from.auto_discovery_with_breakout,
+            current_breakout_mode: from.current_breakout_mode,
+            left_breakout_modes: from.left_breakout_modes,
+            state: state,
+        })
+    }
+
     pub(crate) fn is_done(&self) -> bool {
         match self {
             DiscoveryStateMachine::Done(_) => true,
@@ -66,6 +93,14 @@ impl DiscoveryStateMachine {
         }
     }
 
+    /// if the port tries a breakout mode in this state
+    pub(crate) fn is_attempt(&self) -> bool {
+        match self {
+            DiscoveryStateMachine::BreakoutMode(_) => true,
+            DiscoveryStateMachine::Done(_) => false,
+        }
+    }
+
     pub(crate) fn can_step(&self, port: &PhysicalPort<'_, '_>) -> bool {
         match self {
             DiscoveryStateMachine::BreakoutMode(s) => s.is_done(port),
@@ -101,6 +136,15 @@ impl DiscoveryStateMachine {
     }
 }
 
+impl Display for DiscoveryStateMachine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiscoveryStateMachine::BreakoutMode(v) => v.state.fmt(f),
+            DiscoveryStateMachine::Done(v) => v.state.fmt(f),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Discovery<S: State> {
     auto_discovery_with_breakout: bool,
@@ -111,8 +155,14 @@ pub(crate) struct Discovery<S: State> {
 
 impl<S: State> Discovery<S> {
     /// Checks if we can advance our state machine. We can only continue if all state machines of all logical ports are done
+    /// NOTE: logical ports which were set down from the kernel are skipped, and if that is all of them, we do not advance either
     fn is_done(&self, port: &PhysicalPort<'_, '_>) -> bool {
+        let mut ret = false;
         for lp in port.ports.iter() {
+            // logical ports which were set down from the kernel do not take part in discovery
+            if lp.kernel_admin_down {
+                continue;
+            }
             match &lp.sm {
                 None => return false,
                 Some(sm) => {
@@ -121,8 +171,9 @@ impl<S: State> Discovery<S> {
                     }
                 }
             }
+            ret = true;
         }
-        return true;
+        ret
     }
 
     /// Checks if we can move to the successful done state. All state machines for all logical ports must be in the done state.
@@ -132,6 +183,9 @@ impl<S: State> Discovery<S> {
             return false;
         }
         for lp in port.ports.iter() {
+            if lp.kernel_admin_down {
+                continue;
+            }
             match &lp.sm {
                 None => return false,
                 Some(sm) => {
@@ -215,35 +269,16 @@ impl FromState<BreakoutMode> for BreakoutMode {
             new_state
         );
 
-        // calculate all the ports that we need to create for the next breakout mode by using the lanes of the port
-        let new_ports = match calculate_new_ports(&next_breakout_mode, &port.lanes) {
-            Ok(v) => v,
-            Err(e) => {
-                log::error!(
-                    "Physical Port {}: state machine: {} -> {}: error calculating new ports: {}",
-                    port.idx,
-                    from.state,
-                    new_state,
-                    e
-                );
-                return from;
-            }
-        };
-        log::debug!(
-            "Physical Port {}: state machine: {} -> {}: calculated new ports: {:?}",
-            port.idx,
-            from.state,
-            new_state,
-            new_ports.clone()
-        );
-
-        // remove all old ports now
-        port.remove_ports();
-
-        // and create new ports from calculation
-        // this function will add the newly created ports to the physical port
-        for new_port_hw_lanes in new_ports.into_iter() {
-            port.create_port(next_breakout_mode, new_port_hw_lanes);
+        // remove all old ports now, and create the new ports for the next breakout mode
+        if let Err(e) = port.break_out(next_breakout_mode) {
+            log::error!(
+                "Physical Port {}: state machine: {} -> {}: error breaking out port: {}",
+                port.idx,
+                from.state,
+                new_state,
+                e
+            );
+            return from;
         }
 
         // as we know a transceiver is present for this port
@@ -251,13 +286,16 @@ impl FromState<BreakoutMode> for BreakoutMode {
         port.create_hifs_and_rifs();
 
         // now we initialize new state machines for the logical ports
+        let preferred_speed = port.preferred_speed();
+        let preferred_fec = port.preferred_fec();
+        let fixed_speed = port.fixed_speed();
         for lp in port.ports.iter_mut() {
-            lp.sm = Some(super::logicalport::DiscoveryStateMachine::new(
-                &lp.port,
-                lp.supported_speeds.clone(),
-                lp.speed,
-                lp.auto_negotiation,
-            ));
+            lp.start_discovery(
+                preferred_speed,
+                preferred_fec,
+                fixed_speed,
+                port.discovery_profile.clone(),
+            );
         }
 
         // return with the new state
@@ -279,51 +317,26 @@ impl FromState<BreakoutMode> for BreakoutMode {
 }
 
 #[derive(Error, Debug)]
-enum CalcError {
-    #[error("invalid number of hw lanes: {0}")]
-    InvalidNumberOfHwLanes(usize),
-
-    #[error("invalid number of hw lanes for breakout mode {1}: {0}")]
-    InvalidNumberOfHwLanesForMode(usize, BreakoutModeType),
+pub(crate) enum CalcError {
+    #[error(transparent)]
+    LaneSplit(#[from] LaneSplitError),
 
     #[error("unknown breakout mode: {0}")]
     UnknownBreakoutMode(i32),
 }
 
-fn calculate_new_ports(
+pub(crate) fn calculate_new_ports(
     mode: &BreakoutModeType,
     lanes: &Vec<u32>,
 ) -> Result<Vec<Vec<u32>>, CalcError> {
-    // validate number of lanes first
-    let lanes_len = lanes.len();
-    if lanes_len == 0 || lanes_len > 4 {
-        return Err(CalcError::InvalidNumberOfHwLanes(lanes.len()));
-    }
-
-    let mut ret: Vec<Vec<u32>> = Vec::new();
-    match mode {
+    let mode = match mode {
         BreakoutModeType::Unknown(v) => return Err(CalcError::UnknownBreakoutMode(*v)),
-        BreakoutModeType::FourLanes => {
-            if lanes_len % 4 != 0 {
-                return Err(CalcError::InvalidNumberOfHwLanesForMode(lanes_len, *mode));
-            }
-            ret.push(lanes.clone());
-        }
-        BreakoutModeType::TwoLanes => {
-            if lanes_len % 2 != 0 {
-                return Err(CalcError::InvalidNumberOfHwLanesForMode(lanes_len, *mode));
-            }
-            for lane_pair in lanes.chunks(2) {
-                ret.push(lane_pair.to_vec());
-            }
-        }
-        BreakoutModeType::OneLane => {
-            for lane in lanes.iter() {
-                ret.push(vec![*lane]);
-            }
-        }
-    }
-    Ok(ret)
+        BreakoutModeType::OneLane => PhysicalPortConfigBreakoutMode::OneLane,
+        BreakoutModeType::TwoLanes => PhysicalPortConfigBreakoutMode::TwoLanes,
+        BreakoutModeType::FourLanes => PhysicalPortConfigBreakoutMode::FourLanes,
+        BreakoutModeType::EightLanes => PhysicalPortConfigBreakoutMode::EightLanes,
+    };
+    Ok(split_lanes(mode, lanes)?)
 }
 
 impl FromState<BreakoutMode> for Done {
@@ -395,3 +408,88 @@ impl Display for Done {
         write!(f, "DONE[{}]", if self.success { "UP" } else { "DOWN" })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calculate_new_ports_eight_lanes() {
+        let lanes = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        assert_eq!(
+            calculate_new_ports(&BreakoutModeType::OneLane, &lanes).unwrap(),
+            vec![
+                vec![1],
+                vec![2],
+                vec![3],
+                vec![4],
+                vec![5],
+                vec![6],
+                vec![7],
+                vec![8]
+            ]
+        );
+        assert_eq!(
+            calculate_new_ports(&BreakoutModeType::TwoLanes, &lanes).unwrap(),
+            vec![vec![1, 2], vec![3, 4], vec![5, 6], vec![7, 8]]
+        );
+        assert_eq!(
+            calculate_new_ports(&BreakoutModeType::FourLanes, &lanes).unwrap(),
+            vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8]]
+        );
+        assert_eq!(
+            calculate_new_ports(&BreakoutModeType::EightLanes, &lanes).unwrap(),
+            vec![vec![1, 2, 3, 4, 5, 6, 7, 8]]
+        );
+    }
+
+    #[test]
+    fn calculate_new_ports_four_lanes() {
+        // the lanes are kept in the order of the physical port
+        let lanes = vec![41, 42, 43, 40];
+        assert_eq!(
+            calculate_new_ports(&BreakoutModeType::OneLane, &lanes).unwrap(),
+            vec![vec![41], vec![42], vec![43], vec![40]]
+        );
+        assert_eq!(
+            calculate_new_ports(&BreakoutModeType::TwoLanes, &lanes).unwrap(),
+            vec![vec![41, 42], vec![43, 40]]
+        );
+        assert_eq!(
+            calculate_new_ports(&BreakoutModeType::FourLanes, &lanes).unwrap(),
+            vec![vec![41, 42, 43, 40]]
+        );
+        assert!(matches!(
+            calculate_new_ports(&BreakoutModeType::EightLanes, &lanes),
+            Err(CalcError::LaneSplit(
+                LaneSplitError::InvalidNumberOfHwLanesForMode(4, _)
+            ))
+        ));
+    }
+
+    #[test]
+    fn calculate_new_ports_invalid() {
+        assert!(matches!(
+            calculate_new_ports(&BreakoutModeType::Unknown(42), &vec![1, 2, 3, 4]),
+            Err(CalcError::UnknownBreakoutMode(42))
+        ));
+        assert!(matches!(
+            calculate_new_ports(&BreakoutModeType::OneLane, &vec![]),
+            Err(CalcError::LaneSplit(
+                LaneSplitError::InvalidNumberOfHwLanes(0)
+            ))
+        ));
+        assert!(matches!(
+            calculate_new_ports(&BreakoutModeType::OneLane, &(1..=9).collect()),
+            Err(CalcError::LaneSplit(
+                LaneSplitError::InvalidNumberOfHwLanes(9)
+            ))
+        ));
+        assert!(matches!(
+            calculate_new_ports(&BreakoutModeType::TwoLanes, &vec![1, 2, 3]),
+            Err(CalcError::LaneSplit(
+                LaneSplitError::InvalidNumberOfHwLanesForMode(3, _)
+            ))
+        ));
+    }
+}
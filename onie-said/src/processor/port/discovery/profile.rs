@@ -0,0 +1,335 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub(crate) enum DiscoveryProfileError {
+    #[error("failed to read discovery profile file: {0}")]
+    IOError(#[from] std::io::Error),
+
+    #[error("failed to parse discovery profile file: {0}")]
+    SerdeError(#[from] serde_json::Error),
+
+    #[error("invalid discovery profile: {0}")]
+    Invalid(String),
+}
+
+/// the strategies that the logical port discovery state machine can use to bring up a port
+#[derive(Serialize, Deserialize, ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum DiscoveryStrategy {
+    /// walk down the supported speeds from the highest to the lowest (including FEC modes if enabled)
+    Speed,
+    /// enable auto negotiation
+    AutoNeg,
+}
+
+impl std::fmt::Display for DiscoveryStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiscoveryStrategy::Speed => write!(f, "speed"),
+            DiscoveryStrategy::AutoNeg => write!(f, "auto-neg"),
+        }
+    }
+}
+
+/// Controls the timings and the strategy of port discovery.
+/// All fields are optional in the profile file, and are taken from the defaults if missing.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct DiscoveryProfile {
+    /// how often the discovery state machines get polled
+    pub(crate) poll_interval_ms: u64,
+
+    /// how long we wait for a port to come up with its initial settings
+    pub(crate) start_dwell_ms: u64,
+
+    /// how long we wait for a port to come up after a speed change
+    pub(crate) speed_dwell_ms: u64,
+
+    /// how long we wait for a port to come up after a FEC mode change
+    pub(crate) fec_dwell_ms: u64,
+
+    /// how long we wait for a port to come up after enabling auto negotiation
+    pub(crate) auto_neg_dwell_ms: u64,
+
+    /// the strategies in the order in which they are being tried
+    pub(crate) strategies: Vec<DiscoveryStrategy>,
+
+    /// if all FEC modes which are valid for a speed should be tried during the speed strategy
+    pub(crate) fec: bool,
+
+    /// how often discovery gets restarted for a physical port after it failed
+    /// unlimited if not set
+    pub(crate) max_retries: Option<u64>,
+}
+
+impl Default for DiscoveryProfile {
+    fn default() -> Self {
+        Self {
+            poll_interval_ms: 1000,
+            start_dwell_ms: 5000,
+            speed_dwell_ms: 5000,
+            fec_dwell_ms: 5000,
+            auto_neg_dwell_ms: 10000,
+            strategies: vec![DiscoveryStrategy::Speed, DiscoveryStrategy::AutoNeg],
+            fec: true,
+            max_retries: None,
+        }
+    }
+}
+
+impl DiscoveryProfile {
+    pub(crate) fn from_file(path: &PathBuf) -> Result<Self, DiscoveryProfileError> {
+        let mut file = File::open(path)?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        let profile: DiscoveryProfile = serde_json::from_str(&contents)?;
+        Ok(profile)
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), DiscoveryProfileError> {
+        if self.poll_interval_ms < 100 || self.poll_interval_ms > 60000 {
+            return Err(DiscoveryProfileError::Invalid(format!(
+                "poll interval must be between 100ms and 60000ms, got {}ms",
+                self.poll_interval_ms
+            )));
+        }
+        for (name, dwell) in [
+            ("start", self.start_dwell_ms),
+            ("speed", self.speed_dwell_ms),
+            ("FEC", self.fec_dwell_ms),
+            ("auto negotiation", self.auto_neg_dwell_ms),
+        ] {
+            // dwell times below the poll interval are pointless as we only step on a poll
+            if dwell < self.poll_interval_ms {
+                return Err(DiscoveryProfileError::Invalid(format!(
+                    "{} dwell time ({}ms) must not be shorter than the poll interval ({}ms)",
+                    name, dwell, self.poll_interval_ms
+                )));
+            }
+        }
+        if self.strategies.is_empty() {
+            return Err(DiscoveryProfileError::Invalid(
+                "at least one strategy is required".to_string(),
+            ));
+        }
+        for (i, strategy) in self.strategies.iter().enumerate() {
+            if self.strategies[..i].contains(strategy) {
+                return Err(DiscoveryProfileError::Invalid(format!(
+                    "strategy {} is listed more than once",
+                    strategy
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms)
+    }
+
+    pub(crate) fn start_dwell(&self) -> Duration {
+        Duration::from_millis(self.start_dwell_ms)
+    }
+
+    pub(crate) fn speed_dwell(&self) -> Duration {
+        Duration::from_millis(self.speed_dwell_ms)
+    }
+
+    pub(crate) fn fec_dwell(&self) -> Duration {
+        Duration::from_millis(self.fec_dwell_ms)
+    }
+
+    pub(crate) fn auto_neg_dwell(&self) -> Duration {
+        Duration::from_millis(self.auto_neg_dwell_ms)
+    }
+
+    /// returns the strategy which comes after `strategy`, or the first one if `strategy` is `None`
+    pub(crate) fn next_strategy(
+        &self,
+        strategy: Option<DiscoveryStrategy>,
+    ) -> Option<DiscoveryStrategy> {
+        match strategy {
+            None => self.strategies.first().copied(),
+            Some(strategy) => self
+                .strategies
+                .iter()
+                .position(|s| *s == strategy)
+                .and_then(|i| self.strategies.get(i + 1))
+                .copied(),
+        }
+    }
+}
+
+// just a convenience conversion method for our RPC
+impl From<&DiscoveryProfile> for onie_sai_rpc::onie_sai::DiscoveryProfileResponse {
+    fn from(profile: &DiscoveryProfile) -> Self {
+        onie_sai_rpc::onie_sai::DiscoveryProfileResponse {
+            poll_interval_ms: profile.poll_interval_ms,
+            start_dwell_ms: profile.start_dwell_ms,
+            speed_dwell_ms: profile.speed_dwell_ms,
+            fec_dwell_ms: profile.fec_dwell_ms,
+            auto_neg_dwell_ms: profile.auto_neg_dwell_ms,
+            strategies: profile.strategies.iter().map(|s| s.to_string()).collect(),
+            fec: profile.fec,
+            max_retries: profile.max_retries,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid(profile: DiscoveryProfile) -> String {
+        match profile.validate() {
+            Err(DiscoveryProfileError::Invalid(e)) => e,
+            v => panic!("profile {:?} is not invalid: {:?}", profile, v),
+        }
+    }
+
+    #[test]
+    fn validate_accepts() {
+        DiscoveryProfile::default().validate().unwrap();
+
+        // no retries at all is fine: the port stays down after the first discovery failed
+        DiscoveryProfile {
+            max_retries: Some(0),
+            ..Default::default()
+        }
+        .validate()
+        .unwrap();
+
+        // the bounds of the poll interval, and dwell times as short as the poll interval
+        for poll_interval_ms in [100, 60000] {
+            DiscoveryProfile {
+                poll_interval_ms: poll_interval_ms,
+                start_dwell_ms: poll_interval_ms,
+                speed_dwell_ms: poll_interval_ms,
+                fec_dwell_ms: poll_interval_ms,
+                auto_neg_dwell_ms: poll_interval_ms,
+                ..Default::default()
+            }
+            .validate()
+            .unwrap();
+        }
+
+        DiscoveryProfile {
+            strategies: vec![DiscoveryStrategy::AutoNeg],
+            fec: false,
+            ..Default::default()
+        }
+        .validate()
+        .unwrap();
+    }
+
+    #[test]
+    fn validate_rejects_strategies() {
+        let e = invalid(DiscoveryProfile {
+            strategies: vec![],
+            ..Default::default()
+        });
+        assert!(e.contains("at least one strategy"), "{}", e);
+
+        let e = invalid(DiscoveryProfile {
+            strategies: vec![
+                DiscoveryStrategy::Speed,
+                DiscoveryStrategy::AutoNeg,
+                DiscoveryStrategy::Speed,
+            ],
+            ..Default::default()
+        });
+        assert!(e.contains("speed is listed more than once"), "{}", e);
+    }
+
+    #[test]
+    fn validate_rejects_intervals() {
+        for poll_interval_ms in [0, 99, 60001] {
+            let e = invalid(DiscoveryProfile {
+                poll_interval_ms: poll_interval_ms,
+                ..Default::default()
+            });
+            assert!(e.contains("poll interval"), "{}", e);
+        }
+
+        for (name, profile) in [
+            (
+                "start",
+                DiscoveryProfile {
+                    start_dwell_ms: 0,
+                    ..Default::default()
+                },
+            ),
+            (
+                "speed",
+                DiscoveryProfile {
+                    speed_dwell_ms: 0,
+                    ..Default::default()
+                },
+            ),
+            (
+                "FEC",
+                DiscoveryProfile {
+                    fec_dwell_ms: 0,
+                    ..Default::default()
+                },
+            ),
+            (
+                "auto negotiation",
+                DiscoveryProfile {
+                    auto_neg_dwell_ms: 999,
+                    ..Default::default()
+                },
+            ),
+        ] {
+            let e = invalid(profile);
+            assert!(e.starts_with(&format!("{} dwell time", name)), "{}", e);
+        }
+    }
+
+    #[test]
+    fn partial_profile() {
+        let profile: DiscoveryProfile = serde_json::from_str(
+            r#"{"speed_dwell_ms": 3000, "strategies": ["auto-neg", "speed"]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            profile,
+            DiscoveryProfile {
+                speed_dwell_ms: 3000,
+                strategies: vec![DiscoveryStrategy::AutoNeg, DiscoveryStrategy::Speed],
+                ..Default::default()
+            }
+        );
+
+        assert!(serde_json::from_str::<DiscoveryProfile>(r#"{"poll_interval": 1000}"#).is_err());
+        assert!(serde_json::from_str::<DiscoveryProfile>(r#"{"strategies": ["fec"]}"#).is_err());
+    }
+
+    #[test]
+    fn next_strategy() {
+        let profile = DiscoveryProfile::default();
+        assert_eq!(profile.next_strategy(None), Some(DiscoveryStrategy::Speed));
+        assert_eq!(
+            profile.next_strategy(Some(DiscoveryStrategy::Speed)),
+            Some(DiscoveryStrategy::AutoNeg)
+        );
+        assert_eq!(
+            profile.next_strategy(Some(DiscoveryStrategy::AutoNeg)),
+            None
+        );
+
+        let profile = DiscoveryProfile {
+            strategies: vec![DiscoveryStrategy::AutoNeg],
+            ..Default::default()
+        };
+        assert_eq!(profile.next_strategy(Some(DiscoveryStrategy::Speed)), None);
+    }
+}
@@ -0,0 +1,344 @@
+use clap::ValueEnum;
+use thiserror::Error;
+
+/// the maximum length of a netdev name: IFNAMSIZ without the terminating NULL byte
+const MAX_NAME_LEN: usize = 15;
+
+/// all placeholders which can be used in a template
+const PLACEHOLDERS: [&str; 6] = ["idx", "port", "subport", "lane", "label", "alias"];
+
+/// the placeholders which tell the logical ports of a physical port apart
+static SUBPORT_PLACEHOLDERS: [&str; 2] = ["subport", "lane"];
+
+/// the placeholders which tell the physical ports apart
+static PORT_PLACEHOLDERS: [&str; 5] = ["idx", "port", "lane", "label", "alias"];
+
+#[derive(Debug, Error)]
+pub(crate) enum HostIfNamingError {
+    #[error("name {0:?} is longer than 15 characters")]
+    TooLong(String),
+
+    #[error("name {0:?} is not a valid interface name")]
+    Invalid(String),
+
+    #[error("no value for {{{0}}}")]
+    MissingValue(&'static str),
+
+    #[error("unknown placeholder {{{0}}} in template")]
+    UnknownPlaceholder(String),
+
+    #[error("unterminated placeholder in template")]
+    UnterminatedPlaceholder,
+
+    #[error("template needs one of {0:?} to produce unique names")]
+    NotUnique(&'static [&'static str]),
+}
+
+/// the naming schemes for the host interfaces of the logical ports
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum HostIfNamingScheme {
+    /// Ethernet<physical port index>-<logical port index>
+    #[default]
+    Index,
+    /// SONiC style names from the first lane of the logical port: Ethernet<first lane>
+    Lane,
+    /// the front panel label from the platform library (or the alias from the port config),
+    /// with -<logical port index> appended for broken out ports
+    Label,
+    /// a template with the placeholders {idx}, {port}, {subport}, {lane}, {label} and {alias}
+    Template,
+}
+
+/// the values that the name of a host interface is built from
+pub(crate) struct HostIfNameValues<'a> {
+    /// the physical port index
+    pub(crate) idx: usize,
+    /// the logical port index within the physical port
+    pub(crate) subport: usize,
+    /// the number of logical ports of the physical port
+    pub(crate) num_subports: usize,
+    /// the first lane of the logical port
+    pub(crate) lane: Option<u32>,
+    /// the front panel label from the platform library
+    pub(crate) label: Option<&'a str>,
+    /// the alias from the port config
+    pub(crate) alias: Option<&'a str>,
+}
+
+/// Names the host interfaces of the logical ports. Every name gets validated against the
+/// restrictions of netdev names in Linux.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct HostIfNaming {
+    scheme: HostIfNamingScheme,
+    template: String,
+}
+
+impl HostIfNaming {
+    /// the template is only used for the template scheme, and is validated here already
+    pub(crate) fn new(
+        scheme: HostIfNamingScheme,
+        template: Option<String>,
+    ) -> Result<Self, HostIfNamingError> {
+        let template = template.unwrap_or_default();
+        if scheme == HostIfNamingScheme::Template {
+            let mut used = Vec::new();
+            render(&template, |p| {
+                used.push(p.to_string());
+                match PLACEHOLDERS.contains(&p) {
+                    true => Ok(String::new()),
+                    false => Err(HostIfNamingError::UnknownPlaceholder(p.to_string())),
+                }
+            })?;
+            for required in [&SUBPORT_PLACEHOLDERS[..], &PORT_PLACEHOLDERS[..]] {
+                if !used.iter().any(|p| required.contains(&p.as_str())) {
+                    return Err(HostIfNamingError::NotUnique(required));
+                }
+            }
+        }
+        Ok(Self { scheme, template })
+    }
+
+    pub(crate) fn name(&self, v: &HostIfNameValues) -> Result<String, HostIfNamingError> {
+        let ret = match self.scheme {
+            HostIfNamingScheme::Index => default_name(v.idx, v.subport),
+            HostIfNamingScheme::Lane => {
+                format!(
+                    "Ethernet{}",
+                    v.lane.ok_or(HostIfNamingError::MissingValue("lane"))?
+                )
+            }
+            HostIfNamingScheme::Label => {
+                let label = v
+                    .label
+                    .or(v.alias)
+                    .ok_or(HostIfNamingError::MissingValue("label"))?;
+                match v.num_subports > 1 {
+                    true => format!("{}-{}", sanitize(label), v.subport),
+                    false => sanitize(label),
+                }
+            }
+            HostIfNamingScheme::Template => render(&self.template, |p| match p {
+                "idx" => Ok(v.idx.to_string()),
+                "port" => Ok((v.idx + 1).to_string()),
+                "subport" => Ok(v.subport.to_string()),
+                "lane" => v
+                    .lane
+                    .map(|lane| lane.to_string())
+                    .ok_or(HostIfNamingError::MissingValue("lane")),
+                "label" => v
+                    .label
+                    .map(sanitize)
+                    .ok_or(HostIfNamingError::MissingValue("label")),
+                "alias" => v
+                    .alias
+                    .map(sanitize)
+                    .ok_or(HostIfNamingError::MissingValue("alias")),
+                _ => Err(HostIfNamingError::UnknownPlaceholder(p.to_string())),
+            })?,
+        };
+        validate_name(&ret)?;
+        Ok(ret)
+    }
+}
+
+/// the name of the index scheme: we fall back to it if a name cannot be built
+pub(crate) fn default_name(idx: usize, subport: usize) -> String {
+    format!("Ethernet{}-{}", idx, subport)
+}
+
+/// replaces all the placeholders in the template with the values from f
+fn render<F>(template: &str, mut f: F) -> Result<String, HostIfNamingError>
+where
+    F: FnMut(&str) -> Result<String, HostIfNamingError>,
+{
+    let mut ret = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        ret.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or(HostIfNamingError::UnterminatedPlaceholder)?;
+        ret.push_str(&f(&rest[start + 1..start + end])?);
+        rest = &rest[start + end + 1..];
+    }
+    ret.push_str(rest);
+    Ok(ret)
+}
+
+/// front panel labels like "Eth1/1" contain characters which are not allowed in netdev names
+fn sanitize(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '/' | ':' => '-',
+            c if c.is_whitespace() => '-',
+            c => c,
+        })
+        .collect()
+}
+
+/// the same checks that the kernel does for netdev names
+fn validate_name(name: &str) -> Result<(), HostIfNamingError> {
+    if name.len() > MAX_NAME_LEN {
+        return Err(HostIfNamingError::TooLong(name.to_string()));
+    }
+    if name.is_empty()
+        || name == "."
+        || name == ".."
+        || name
+            .chars()
+            .any(|c| c == '/' || c == ':' || c.is_whitespace())
+    {
+        return Err(HostIfNamingError::Invalid(name.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values<'a>(
+        idx: usize,
+        subport: usize,
+        num_subports: usize,
+        lane: Option<u32>,
+        label: Option<&'a str>,
+        alias: Option<&'a str>,
+    ) -> HostIfNameValues<'a> {
+        HostIfNameValues {
+            idx: idx,
+            subport: subport,
+            num_subports: num_subports,
+            lane: lane,
+            label: label,
+            alias: alias,
+        }
+    }
+
+    #[test]
+    fn index_scheme() {
+        let naming = HostIfNaming::new(HostIfNamingScheme::Index, None).unwrap();
+        let v = values(3, 1, 4, Some(13), None, None);
+        assert_eq!(naming.name(&v).unwrap(), "Ethernet3-1");
+        assert_eq!(default_name(3, 1), "Ethernet3-1");
+    }
+
+    #[test]
+    fn lane_scheme() {
+        let naming = HostIfNaming::new(HostIfNamingScheme::Lane, None).unwrap();
+        let v = values(3, 1, 4, Some(13), None, None);
+        assert_eq!(naming.name(&v).unwrap(), "Ethernet13");
+        let v = values(3, 1, 4, None, None, None);
+        assert!(matches!(
+            naming.name(&v),
+            Err(HostIfNamingError::MissingValue("lane"))
+        ));
+    }
+
+    #[test]
+    fn label_scheme() {
+        let naming = HostIfNaming::new(HostIfNamingScheme::Label, None).unwrap();
+
+        // the label wins over the alias, and only broken out ports get the subport appended
+        let v = values(0, 0, 1, None, Some("Eth1/1"), Some("alias"));
+        assert_eq!(naming.name(&v).unwrap(), "Eth1-1");
+        let v = values(0, 2, 4, None, None, Some("Eth 1"));
+        assert_eq!(naming.name(&v).unwrap(), "Eth-1-2");
+        let v = values(0, 0, 1, None, None, None);
+        assert!(matches!(
+            naming.name(&v),
+            Err(HostIfNamingError::MissingValue("label"))
+        ));
+    }
+
+    #[test]
+    fn template_scheme() {
+        let naming = HostIfNaming::new(
+            HostIfNamingScheme::Template,
+            Some("sw{port}p{subport}".to_string()),
+        )
+        .unwrap();
+        let v = values(0, 1, 2, None, None, None);
+        assert_eq!(naming.name(&v).unwrap(), "sw1p1");
+
+        let naming = HostIfNaming::new(
+            HostIfNamingScheme::Template,
+            Some("e{alias}.{lane}".to_string()),
+        )
+        .unwrap();
+        let v = values(0, 1, 2, Some(5), None, Some("1:2"));
+        assert_eq!(naming.name(&v).unwrap(), "e1-2.5");
+        let v = values(0, 1, 2, Some(5), None, None);
+        assert!(matches!(
+            naming.name(&v),
+            Err(HostIfNamingError::MissingValue("alias"))
+        ));
+    }
+
+    #[test]
+    fn invalid_templates() {
+        let new = |t: &str| HostIfNaming::new(HostIfNamingScheme::Template, Some(t.to_string()));
+        assert!(matches!(
+            new("sw{port}p{foo}"),
+            Err(HostIfNamingError::UnknownPlaceholder(p)) if p == "foo"
+        ));
+        assert!(matches!(
+            new("sw{port}p{subport"),
+            Err(HostIfNamingError::UnterminatedPlaceholder)
+        ));
+        assert!(matches!(
+            new("sw{port}"),
+            Err(HostIfNamingError::NotUnique(p)) if p == SUBPORT_PLACEHOLDERS
+        ));
+        assert!(matches!(
+            new("sw{subport}"),
+            Err(HostIfNamingError::NotUnique(p)) if p == PORT_PLACEHOLDERS
+        ));
+
+        // lane tells both the physical and the logical ports apart
+        assert!(new("Ethernet{lane}").is_ok());
+
+        // the template is only checked for the template scheme
+        assert!(HostIfNaming::new(HostIfNamingScheme::Index, Some("{foo}".to_string())).is_ok());
+    }
+
+    #[test]
+    fn name_validation() {
+        let naming = HostIfNaming::new(
+            HostIfNamingScheme::Template,
+            Some("{label}{subport}".to_string()),
+        )
+        .unwrap();
+        let v = values(0, 0, 1, None, Some("a-very-long-label"), None);
+        assert!(matches!(
+            naming.name(&v),
+            Err(HostIfNamingError::TooLong(_))
+        ));
+
+        assert!(validate_name("Ethernet0-0").is_ok());
+        assert!(validate_name("123456789012345").is_ok());
+        assert!(matches!(
+            validate_name("1234567890123456"),
+            Err(HostIfNamingError::TooLong(_))
+        ));
+        for name in ["", ".", "..", "eth/0", "eth:0", "eth 0"] {
+            assert!(
+                matches!(validate_name(name), Err(HostIfNamingError::Invalid(_))),
+                "{:?}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn render_templates() {
+        let f = |p: &str| Ok(p.to_uppercase());
+        assert_eq!(render("plain", f).unwrap(), "plain");
+        assert_eq!(render("{a}-{b}", f).unwrap(), "A-B");
+        assert_eq!(render("x{ab}y{c}z", f).unwrap(), "xAByCz");
+        assert!(matches!(
+            render("{a", f),
+            Err(HostIfNamingError::UnterminatedPlaceholder)
+        ));
+    }
+}
@@ -0,0 +1,197 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+use sai::sai_mac_t;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub(crate) enum PortMacSettingsError {
+    #[error("failed to read port MAC settings file: {0}")]
+    IOError(#[from] std::io::Error),
+
+    #[error("failed to parse port MAC settings file: {0}")]
+    SerdeError(#[from] serde_json::Error),
+
+    #[error("invalid port MAC settings: {0}")]
+    Invalid(String),
+}
+
+/// which MAC addresses the router interfaces and host interfaces of the ports are using
+#[derive(Serialize, Deserialize, ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum PortMacMode {
+    /// all ports share the base MAC address
+    Shared,
+    /// every logical port gets the base MAC address plus an offset
+    PerPort,
+}
+
+/// Controls the MAC addresses of the ports. In per-port mode every lane of the switch gets a slot,
+/// and a logical port uses the slot of its first lane. This keeps the MAC addresses of the ports
+/// stable across breakouts.
+/// All fields are optional in the settings file, and are taken from the defaults if missing.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct PortMacSettings {
+    /// shared or per-port MAC addresses
+    pub(crate) mode: PortMacMode,
+
+    /// the offset to the base MAC address of the first slot
+    /// NOTE: the default of 1 leaves the base MAC address to the switch itself
+    pub(crate) offset: u64,
+
+    /// the number of MAC addresses which are reserved for the device including the base MAC address
+    /// (e.g. from the ONIE TlvInfo EEPROM), unlimited if not set
+    pub(crate) count: Option<u64>,
+}
+
+impl Default for PortMacSettings {
+    fn default() -> Self {
+        Self {
+            mode: PortMacMode::Shared,
+            offset: 1,
+            count: None,
+        }
+    }
+}
+
+impl PortMacSettings {
+    pub(crate) fn from_file(path: &PathBuf) -> Result<Self, PortMacSettingsError> {
+        let mut file = File::open(path)?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        let settings: PortMacSettings = serde_json::from_str(&contents)?;
+        Ok(settings)
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), PortMacSettingsError> {
+        if let Some(count) = self.count {
+            if self.mode == PortMacMode::PerPort && self.offset >= count {
+                return Err(PortMacSettingsError::Invalid(format!(
+                    "offset {} leaves no MAC addresses for the ports out of {}",
+                    self.offset, count
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// the MAC address for a slot, `None` if the slot is beyond the reserved MAC addresses
+    pub(crate) fn mac_address(&self, base: sai_mac_t, slot: u64) -> Option<sai_mac_t> {
+        match self.mode {
+            PortMacMode::Shared => Some(base),
+            PortMacMode::PerPort => {
+                let offset = self.offset + slot;
+                if self.count.map_or(false, |count| offset >= count) {
+                    return None;
+                }
+                Some(mac_add(base, offset))
+            }
+        }
+    }
+}
+
+/// adds the offset to the MAC address as a 48-bit number
+fn mac_add(mac: sai_mac_t, offset: u64) -> sai_mac_t {
+    let mut v = [0u8; 8];
+    v[2..].copy_from_slice(&mac);
+    let v = (u64::from_be_bytes(v).wrapping_add(offset) & 0xffff_ffff_ffff).to_be_bytes();
+    let mut ret: sai_mac_t = [0; 6];
+    ret.copy_from_slice(&v[2..]);
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: sai_mac_t = [0x54, 0xbf, 0x64, 0xba, 0x3d, 0xc0];
+
+    fn per_port(offset: u64, count: Option<u64>) -> PortMacSettings {
+        PortMacSettings {
+            mode: PortMacMode::PerPort,
+            offset: offset,
+            count: count,
+        }
+    }
+
+    #[test]
+    fn mac_add_carry() {
+        assert_eq!(mac_add(BASE, 0), BASE);
+        assert_eq!(mac_add(BASE, 1), [0x54, 0xbf, 0x64, 0xba, 0x3d, 0xc1]);
+        assert_eq!(
+            mac_add([0x54, 0xbf, 0x64, 0xba, 0x3d, 0xff], 1),
+            [0x54, 0xbf, 0x64, 0xba, 0x3e, 0x00]
+        );
+        assert_eq!(
+            mac_add([0x54, 0xbf, 0x64, 0xff, 0xff, 0xfe], 3),
+            [0x54, 0xbf, 0x65, 0x00, 0x00, 0x01]
+        );
+        // the carry does not spill beyond 48 bits
+        assert_eq!(mac_add([0xff; 6], 2), [0x00, 0x00, 0x00, 0x00, 0x00, 0x01]);
+    }
+
+    #[test]
+    fn shared_mode() {
+        let settings = PortMacSettings::default();
+        assert_eq!(settings.mac_address(BASE, 0), Some(BASE));
+        assert_eq!(settings.mac_address(BASE, 1000), Some(BASE));
+    }
+
+    #[test]
+    fn per_port_mode() {
+        let settings = per_port(1, None);
+        assert_eq!(
+            settings.mac_address(BASE, 0),
+            Some([0x54, 0xbf, 0x64, 0xba, 0x3d, 0xc1])
+        );
+        assert_eq!(
+            settings.mac_address(BASE, 0x3f),
+            Some([0x54, 0xbf, 0x64, 0xba, 0x3e, 0x00])
+        );
+    }
+
+    #[test]
+    fn per_port_mode_count_limit() {
+        // the base MAC address and 3 more: slots 0 to 2 are left for the ports
+        let settings = per_port(1, Some(4));
+        assert!(settings.validate().is_ok());
+        assert_eq!(
+            settings.mac_address(BASE, 2),
+            Some([0x54, 0xbf, 0x64, 0xba, 0x3d, 0xc3])
+        );
+        assert_eq!(settings.mac_address(BASE, 3), None);
+
+        // the count does not limit the shared mode
+        let settings = PortMacSettings {
+            mode: PortMacMode::Shared,
+            offset: 1,
+            count: Some(1),
+        };
+        assert!(settings.validate().is_ok());
+        assert_eq!(settings.mac_address(BASE, 3), Some(BASE));
+    }
+
+    #[test]
+    fn validation() {
+        assert!(PortMacSettings::default().validate().is_ok());
+        assert!(per_port(0, Some(1)).validate().is_ok());
+        assert!(matches!(
+            per_port(4, Some(4)).validate(),
+            Err(PortMacSettingsError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn settings_file_format() {
+        let settings: PortMacSettings =
+            serde_json::from_str(r#"{"mode": "per-port", "count": 128}"#).unwrap();
+        assert_eq!(settings, per_port(1, Some(128)));
+        let settings: PortMacSettings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings, PortMacSettings::default());
+        assert!(serde_json::from_str::<PortMacSettings>(r#"{"modes": "shared"}"#).is_err());
+    }
+}
@@ -0,0 +1,443 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::path::PathBuf;
+
+use sai::port::PortSerdesAttribute;
+use serde::Deserialize;
+use xcvr::TransceiverInfo;
+
+const DEFAULT_KEY: &str = "Default";
+
+/// serdes attribute name -> lane name (e.g. `lane0`) -> hex value (e.g. `0x1a4a00`)
+type SerdesSettings = HashMap<String, HashMap<String, String>>;
+
+/// media key -> serdes settings
+type MediaKeySettings = HashMap<String, SerdesSettings>;
+
+/// The SONiC `media_settings.json` of a platform. It holds serdes tuning values which need to be
+/// applied depending on the port and the transceiver which is inserted in it.
+/// NOTE: port numbers in this file are 1-based like the index column of the SONiC `port_config.ini`,
+/// while our physical port indexes are 0-based.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub(crate) struct MediaSettings {
+    /// port ranges (e.g. `1-32`, `1,2,3` or `1`) -> media key settings
+    #[serde(rename = "GLOBAL_MEDIA_SETTINGS", default)]
+    global: HashMap<String, MediaKeySettings>,
+
+    /// port number -> media key settings
+    #[serde(rename = "PORT_MEDIA_SETTINGS", default)]
+    port: HashMap<String, MediaKeySettings>,
+}
+
+impl MediaSettings {
+    pub(crate) fn from_file(path: &PathBuf) -> Option<MediaSettings> {
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                log::info!(
+                    "no media settings file at {}. No serdes tuning will be applied.",
+                    path.display()
+                );
+                return None;
+            }
+            Err(e) => {
+                log::error!("failed to open media settings file: {:?}", e);
+                return None;
+            }
+        };
+        let mut contents = String::new();
+        if let Err(e) = file.read_to_string(&mut contents) {
+            log::error!("failed to read media settings file: {:?}", e);
+            return None;
+        }
+        match serde_json::from_str(&contents) {
+            Ok(settings) => Some(settings),
+            Err(e) => {
+                log::error!("failed to parse media settings file: {:?}", e);
+                None
+            }
+        }
+    }
+
+    /// looks up the serdes settings for a physical port and the transceiver that is inserted in it
+    /// The vendor key is tried first, then the media keys, and then the default settings. For every
+    /// key the port settings are more specific than the global settings, so they are tried first,
+    /// and the global default settings are the last resort.
+    pub(crate) fn lookup(&self, idx: usize, info: &TransceiverInfo) -> Option<&SerdesSettings> {
+        let port_number = idx + 1;
+        let keys = lookup_keys(info);
+
+        let settings: Vec<&MediaKeySettings> = self
+            .port
+            .get(&port_number.to_string())
+            .into_iter()
+            .chain(
+                self.global
+                    .iter()
+                    .filter(|(range, _)| port_range_contains(range, port_number))
+                    .map(|(_, settings)| settings),
+            )
+            .collect();
+        find_settings(&settings, &keys)
+    }
+
+    /// returns the serdes attributes for a logical port from the settings found with `lookup`
+    /// `subport` and `num_lanes` select the lanes of the logical port within the physical port.
+    pub(crate) fn serdes_attributes(
+        settings: &SerdesSettings,
+        subport: usize,
+        num_lanes: usize,
+    ) -> Vec<PortSerdesAttribute> {
+        let mut ret = Vec::with_capacity(settings.len());
+        for (name, lanes) in settings.iter() {
+            let mut values: Vec<i32> = Vec::with_capacity(num_lanes);
+            for lane in (subport * num_lanes)..((subport + 1) * num_lanes) {
+                let v = lanes
+                    .get(&format!("lane{}", lane))
+                    .and_then(|v| parse_value(v));
+                match v {
+                    Some(v) => values.push(v),
+                    None => break,
+                }
+            }
+            if values.len() != num_lanes {
+                log::warn!(
+                    "media settings: {} has no valid values for all lanes of subport {}. Skipping.",
+                    name,
+                    subport
+                );
+                continue;
+            }
+            let attr = match name.as_str() {
+                "preemphasis" => PortSerdesAttribute::Preemphasis(values),
+                "idriver" => PortSerdesAttribute::IDriver(values),
+                "ipredriver" => PortSerdesAttribute::IPreDriver(values),
+                "pre1" => PortSerdesAttribute::TxFirPre1(values),
+                "pre2" => PortSerdesAttribute::TxFirPre2(values),
+                "pre3" => PortSerdesAttribute::TxFirPre3(values),
+                "main" => PortSerdesAttribute::TxFirMain(values),
+                "post1" => PortSerdesAttribute::TxFirPost1(values),
+                "post2" => PortSerdesAttribute::TxFirPost2(values),
+                "post3" => PortSerdesAttribute::TxFirPost3(values),
+                "attn" => PortSerdesAttribute::TxFirAttn(values),
+                _ => {
+                    log::warn!("media settings: unsupported serdes attribute {}", name);
+                    continue;
+                }
+            };
+            ret.push(attr);
+        }
+        ret
+    }
+}
+
+// builds the list of keys to look for in order of precedence
+fn lookup_keys(info: &TransceiverInfo) -> Vec<String> {
+    let mut ret = Vec::with_capacity(5);
+
+    // vendor key: <vendor name>-<part number>
+    let vendor = info.manufacturer.trim();
+    let part_number = info.model.trim();
+    if !vendor.is_empty() && !part_number.is_empty() {
+        ret.push(format!("{}-{}", vendor, part_number));
+    }
+
+    // media keys: <form factor>-<compliance code>-<cable length>M from most to least specific
+    let form_factor = info.type_abbrv_name.trim();
+    if !form_factor.is_empty() {
+        let cable_length = if info.cable_length > 0 {
+            Some(format!("{}M", info.cable_length))
+        } else {
+            None
+        };
+        if let Some(compliance) = compliance_code(&info.specification_compliance) {
+            if let Some(cable_length) = &cable_length {
+                ret.push(format!("{}-{}-{}", form_factor, compliance, cable_length));
+            }
+            ret.push(format!("{}-{}", form_factor, compliance));
+        }
+        if let Some(cable_length) = &cable_length {
+            ret.push(format!("{}-{}", form_factor, cable_length));
+        }
+        ret.push(form_factor.to_string());
+    }
+
+    ret.push(DEFAULT_KEY.to_string());
+    ret
+}
+
+// the keys in the file can be comma-separated lists of keys which share the same settings
+fn find_settings<'a>(
+    settings: &[&'a MediaKeySettings],
+    keys: &[String],
+) -> Option<&'a SerdesSettings> {
+    keys.iter().find_map(|key| {
+        settings.iter().find_map(|settings| {
+            settings
+                .iter()
+                .find(|(k, _)| k.split(',').any(|k| k.trim() == key.as_str()))
+                .map(|(_, v)| v)
+        })
+    })
+}
+
+// the specification compliance is either the compliance code itself, or a python dict as a string
+// as it is being returned by the SONiC sfp API, e.g. "{'10/40G Ethernet Compliance Code': '40GBASE-CR4'}"
+pub(crate) fn compliance_code(spec: &str) -> Option<String> {
+    let spec = spec.trim();
+    if spec.is_empty() {
+        return None;
+    }
+    if !spec.starts_with('{') {
+        return Some(spec.to_string());
+    }
+    spec.trim_matches(|c| c == '{' || c == '}')
+        .split(',')
+        .filter_map(|kv| kv.split_once(':'))
+        .find(|(k, _)| k.contains("Ethernet Compliance Code"))
+        .map(|(_, v)| v.trim().trim_matches('\'').to_string())
+        .filter(|v| !v.is_empty() && v != "Unknown")
+}
+
+fn port_range_contains(range: &str, port_number: usize) -> bool {
+    range.split(',').any(|r| match r.split_once('-') {
+        Some((start, end)) => match (start.trim().parse::<usize>(), end.trim().parse::<usize>()) {
+            (Ok(start), Ok(end)) => (start..=end).contains(&port_number),
+            _ => false,
+        },
+        None => r.trim().parse::<usize>() == Ok(port_number),
+    })
+}
+
+fn parse_value(v: &str) -> Option<i32> {
+    let v = v.trim();
+    let ret = match v.strip_prefix("0x").or_else(|| v.strip_prefix("0X")) {
+        Some(hex) => i64::from_str_radix(hex, 16),
+        None => v.parse::<i64>(),
+    };
+    ret.ok().map(|v| v as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn debug_7726() -> MediaSettings {
+        let path =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../docs/debug-7726/media_settings.json");
+        MediaSettings::from_file(&path).unwrap()
+    }
+
+    fn transceiver(manufacturer: &str, model: &str, form_factor: &str) -> TransceiverInfo {
+        TransceiverInfo {
+            manufacturer: manufacturer.to_string(),
+            model: model.to_string(),
+            type_abbrv_name: form_factor.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn preemphasis(settings: &SerdesSettings) -> Vec<i32> {
+        let attrs = MediaSettings::serdes_attributes(settings, 0, 4);
+        match attrs.as_slice() {
+            [PortSerdesAttribute::Preemphasis(v)] => v.clone(),
+            _ => panic!("unexpected serdes attributes: {:?}", attrs),
+        }
+    }
+
+    #[test]
+    fn lookup_vendor_key_from_global() {
+        let ms = debug_7726();
+        let info = transceiver("FIBERSTORE", "QSFP-BD-40G", "QSFP+");
+        let settings = ms.lookup(0, &info).unwrap();
+        assert_eq!(
+            preemphasis(settings),
+            vec![0x0b3700, 0x0c3700, 0x093202, 0x0b3700]
+        );
+    }
+
+    #[test]
+    fn lookup_comma_separated_vendor_key() {
+        let ms = debug_7726();
+        let info = transceiver("FS", "QSFP28-100G-DAC", "QSFP28");
+        let settings = ms.lookup(31, &info).unwrap();
+        assert_eq!(preemphasis(settings), vec![0x184804; 4]);
+    }
+
+    #[test]
+    fn lookup_media_key_from_port() {
+        let ms = debug_7726();
+        let mut info = transceiver("ACME", "UNKNOWN", "QSFP+");
+        info.specification_compliance =
+            "{'10/40G Ethernet Compliance Code': '40GBASE-LR4'}".to_string();
+        let settings = ms.lookup(0, &info).unwrap();
+        assert_eq!(
+            preemphasis(settings),
+            vec![0x0b3700, 0x0c3700, 0x093202, 0x0b3700]
+        );
+    }
+
+    #[test]
+    fn lookup_default_from_port() {
+        let ms = debug_7726();
+        let info = transceiver("ACME", "UNKNOWN", "SFP+");
+        let settings = ms.lookup(0, &info).unwrap();
+        assert_eq!(preemphasis(settings), vec![0x1a4a00; 4]);
+    }
+
+    #[test]
+    fn lookup_unknown_port() {
+        let ms = debug_7726();
+        let info = transceiver("FIBERSTORE", "QSFP-BD-40G", "QSFP+");
+        assert!(ms.lookup(32, &info).is_none());
+    }
+
+    #[test]
+    fn lookup_precedence() {
+        let ms: MediaSettings = serde_json::from_str(
+            r#"{
+                "GLOBAL_MEDIA_SETTINGS": {
+                    "2-4": {
+                        "QSFP28": { "main": { "lane0": "1" } },
+                        "Default": { "main": { "lane0": "6" } }
+                    }
+                },
+                "PORT_MEDIA_SETTINGS": {
+                    "1": {
+                        "ACME-1234": { "main": { "lane0": "2" } },
+                        "QSFP28-2M": { "main": { "lane0": "3" } },
+                        "Default": { "main": { "lane0": "4" } }
+                    },
+                    "2": {
+                        "ACME-1234": { "main": { "lane0": "5" } },
+                        "Default": { "main": { "lane0": "7" } }
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+        let main = |idx: usize, info: &TransceiverInfo| {
+            ms.lookup(idx, info)
+                .and_then(|s| s.get("main"))
+                .and_then(|lanes| lanes.get("lane0"))
+                .cloned()
+        };
+        let mut info = transceiver("ACME", "1234", "QSFP28");
+        info.cable_length = 2;
+
+        // the port settings win for the same key
+        assert_eq!(main(1, &info).as_deref(), Some("5"));
+
+        // a more specific key in the global settings wins over a less specific one in the port settings
+        info.manufacturer = "OTHER".to_string();
+        assert_eq!(main(1, &info).as_deref(), Some("1"));
+        info.manufacturer = "ACME".to_string();
+
+        // the vendor key comes first, then the media keys, and then the default
+        assert_eq!(main(0, &info).as_deref(), Some("2"));
+        info.manufacturer = "OTHER".to_string();
+        assert_eq!(main(0, &info).as_deref(), Some("3"));
+        info.type_abbrv_name = "QSFP-DD".to_string();
+        assert_eq!(main(0, &info).as_deref(), Some("4"));
+
+        // the default of the port settings comes before the global default, which is the last resort
+        assert_eq!(main(1, &info).as_deref(), Some("7"));
+        assert_eq!(main(2, &info).as_deref(), Some("6"));
+
+        // no settings at all for this port
+        assert_eq!(main(4, &info), None);
+    }
+
+    #[test]
+    fn lookup_keys_order() {
+        let mut info = transceiver("ACME", "1234", "QSFP+");
+        info.cable_length = 3;
+        info.specification_compliance = "40GBASE-CR4".to_string();
+        assert_eq!(
+            lookup_keys(&info),
+            vec![
+                "ACME-1234",
+                "QSFP+-40GBASE-CR4-3M",
+                "QSFP+-40GBASE-CR4",
+                "QSFP+-3M",
+                "QSFP+",
+                "Default"
+            ]
+        );
+        assert_eq!(lookup_keys(&TransceiverInfo::default()), vec!["Default"]);
+    }
+
+    #[test]
+    fn serdes_attributes_breakout_lanes() {
+        let settings: SerdesSettings = serde_json::from_str(
+            r#"{
+                "main": { "lane0": "0", "lane1": "1", "lane2": "2", "lane3": "3" },
+                "post1": { "lane0": "0", "lane1": "1", "lane2": "2" },
+                "unknown": { "lane0": "0", "lane1": "1", "lane2": "2", "lane3": "3" }
+            }"#,
+        )
+        .unwrap();
+
+        // the second subport of a 2x breakout gets lanes 2 and 3, post1 lacks lane 3
+        let attrs = MediaSettings::serdes_attributes(&settings, 1, 2);
+        assert_eq!(attrs.len(), 1);
+        assert!(matches!(&attrs[0], PortSerdesAttribute::TxFirMain(v) if v == &vec![2, 3]));
+
+        // the third subport of a 4x breakout gets lane 2 only
+        let mut attrs = MediaSettings::serdes_attributes(&settings, 2, 1);
+        attrs.sort_by_key(|a| format!("{:?}", a));
+        assert_eq!(attrs.len(), 2);
+        assert!(matches!(&attrs[0], PortSerdesAttribute::TxFirMain(v) if v == &vec![2]));
+        assert!(matches!(&attrs[1], PortSerdesAttribute::TxFirPost1(v) if v == &vec![2]));
+    }
+
+    #[test]
+    fn compliance_code_formats() {
+        assert_eq!(
+            compliance_code("40GBASE-CR4").as_deref(),
+            Some("40GBASE-CR4")
+        );
+        assert_eq!(
+            compliance_code(
+                "{'10/40G Ethernet Compliance Code': '40GBASE-SR4', 'SONET Compliance Codes': 'Unknown'}"
+            )
+            .as_deref(),
+            Some("40GBASE-SR4")
+        );
+        assert_eq!(
+            compliance_code("{'10/40G Ethernet Compliance Code': 'Unknown'}"),
+            None
+        );
+        assert_eq!(
+            compliance_code("{'Fibre Channel Speed': '1200 MBytes/Sec'}"),
+            None
+        );
+        assert_eq!(compliance_code("  "), None);
+    }
+
+    #[test]
+    fn port_range_contains_formats() {
+        assert!(port_range_contains("1-32", 1));
+        assert!(port_range_contains("1-32", 32));
+        assert!(!port_range_contains("1-32", 33));
+        assert!(port_range_contains("1,2,3", 2));
+        assert!(!port_range_contains("1,2,3", 4));
+        assert!(port_range_contains("5", 5));
+        assert!(port_range_contains("1-4, 9-12", 10));
+        assert!(!port_range_contains("a-b", 1));
+    }
+
+    #[test]
+    fn parse_value_formats() {
+        assert_eq!(parse_value("0x1a4a00"), Some(0x1a4a00));
+        assert_eq!(parse_value("0X1A"), Some(0x1a));
+        assert_eq!(parse_value(" 42 "), Some(42));
+        assert_eq!(parse_value("-3"), Some(-3));
+        assert_eq!(parse_value("0xffffffff"), Some(-1));
+        assert_eq!(parse_value("lane0"), None);
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use ipnet::IpNet;
+use sai::neighbor::NeighborEntry;
+use sai::neighbor::NeighborEntryAttribute;
+use sai::next_hop::NextHop;
+use sai::next_hop::NextHopAttribute;
+use sai::next_hop::NextHopType;
+use sai::route::NextHopID;
+use sai::route::RouteEntry;
+use sai::route::RouteEntryAttribute;
+use sai::router_interface::RouterInterfaceID;
+use sai::sai_mac_t;
+use sai::switch::Switch;
+use sai::virtual_router::VirtualRouter;
+use sai::PacketAction;
+
+use crate::processor::netlink::is_link_local;
+
+/// a kernel neighbor that was programmed in SAI together with its next hop
+#[derive(Debug, Clone)]
+struct SyncedNeighbor<'a> {
+    mac_address: sai_mac_t,
+    entry: NeighborEntry<'a>,
+    next_hop: NextHop<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RouteTarget {
+    /// the destination is directly reachable on the router interface
+    Connected,
+    /// the destination is reachable through a gateway, which must be a neighbor
+    Gateway(IpAddr),
+}
+
+/// a kernel route that was programmed in SAI
+#[derive(Debug, Clone)]
+struct SyncedRoute<'a> {
+    target: RouteTarget,
+    entry: RouteEntry<'a>,
+    /// the default routes exist from the start, and are only updated but never removed
+    default: bool,
+}
+
+/// The kernel neighbors and routes of a host interface which are synced to SAI. Everything
+/// references the router interface of the logical port, so it must be flushed before the router
+/// interface gets removed.
+/// Routes through a gateway which is not resolved yet are sent to the CPU, so that the kernel
+/// resolves the neighbor and routes the packets until then.
+#[derive(Debug, Clone)]
+pub(crate) struct RouteSync<'a> {
+    switch: Switch<'a>,
+    router: VirtualRouter<'a>,
+    neighbors: HashMap<IpAddr, SyncedNeighbor<'a>>,
+    routes: HashMap<IpNet, SyncedRoute<'a>>,
+}
+
+impl<'a> RouteSync<'a> {
+    pub(crate) fn new(switch: Switch<'a>, router: VirtualRouter<'a>) -> Self {
+        Self {
+            switch: switch,
+            router: router,
+            neighbors: HashMap::new(),
+            routes: HashMap::new(),
+        }
+    }
+
+    /// creates or updates a neighbor, and points all routes through it to its next hop
+    pub(crate) fn neighbor_changed(
+        &mut self,
+        rif_id: RouterInterfaceID,
+        ip: IpAddr,
+        mac_address: sai_mac_t,
+    ) -> Result<(), sai::Error> {
+        if let Some(neighbor) = self.neighbors.get_mut(&ip) {
+            if neighbor.mac_address != mac_address {
+                neighbor.entry.set_dst_mac_address(mac_address)?;
+                neighbor.mac_address = mac_address;
+                log::debug!("updated neighbor {} to {:?}", neighbor.entry, mac_address);
+            }
+            return Ok(());
+        }
+
+        // NOTE: the host route of a link-local neighbor would clash with the ones of the same
+        // address on other router interfaces, so only the neighbor and its next hop are programmed
+        let mut attrs = vec![NeighborEntryAttribute::DstMacAddress(mac_address)];
+        if is_link_local(&ip) {
+            attrs.push(NeighborEntryAttribute::NoHostRoute(true));
+        }
+        let entry = self.switch.create_neighbor_entry(rif_id, ip, attrs)?;
+        let next_hop = match self.switch.create_next_hop(vec![
+            NextHopAttribute::Type(NextHopType::IP),
+            NextHopAttribute::IP(ip),
+            NextHopAttribute::RouterInterfaceID(rif_id),
+        ]) {
+            Ok(v) => v,
+            Err(e) => {
+                if let Err(e) = entry.remove() {
+                    log::error!(
+                        "failed to remove neighbor {ip} after failed next hop creation: {e:?}"
+                    );
+                }
+                return Err(e);
+            }
+        };
+        log::info!("added neighbor {} with next hop {}", entry, next_hop);
+        let next_hop_id = NextHopID::from(&next_hop);
+        self.neighbors.insert(
+            ip,
+            SyncedNeighbor {
+                mac_address: mac_address,
+                entry: entry,
+                next_hop: next_hop,
+            },
+        );
+        self.repoint_routes(ip, next_hop_id);
+        Ok(())
+    }
+
+    /// removes a neighbor, all routes through it are sent to the CPU again
+    pub(crate) fn neighbor_removed(&mut self, ip: IpAddr, cpu_port_id: NextHopID) {
+        if let Some(neighbor) = self.neighbors.remove(&ip) {
+            self.repoint_routes(ip, cpu_port_id);
+            remove_neighbor(ip, neighbor);
+        }
+    }
+
+    /// creates or updates a route: connected routes go to the router interface, all other
+    /// routes go to the next hop of their gateway
+    pub(crate) fn route_changed(
+        &mut self,
+        rif_id: RouterInterfaceID,
+        cpu_port_id: NextHopID,
+        destination: IpNet,
+        gateway: Option<IpAddr>,
+    ) -> Result<(), sai::Error> {
+        let target = match gateway {
+            Some(ip) => RouteTarget::Gateway(ip),
+            None => RouteTarget::Connected,
+        };
+        let next_hop_id = match target {
+            RouteTarget::Connected => NextHopID::from(rif_id),
+            RouteTarget::Gateway(ip) => self
+                .neighbors
+                .get(&ip)
+                .map_or(cpu_port_id, |neighbor| NextHopID::from(&neighbor.next_hop)),
+        };
+
+        if let Some(route) = self.routes.get_mut(&destination) {
+            if route.target != target {
+                route
+                    .entry
+                    .set_attribute(RouteEntryAttribute::NextHopID(next_hop_id))?;
+                route.target = target;
+                log::info!("updated route {} to {:?}", destination, target);
+            }
+            return Ok(());
+        }
+
+        // NOTE: the default routes always exist, they only get pointed somewhere else
+        let default = destination.prefix_len() == 0;
+        let entry = match default {
+            true => {
+                let entry = self.router.get_route_entry(destination)?;
+                entry.set_attribute(RouteEntryAttribute::NextHopID(next_hop_id))?;
+                entry.set_attribute(RouteEntryAttribute::PacketAction(PacketAction::Forward))?;
+                entry
+            }
+            false => self.router.create_route_entry(
+                destination,
+                vec![
+                    RouteEntryAttribute::PacketAction(PacketAction::Forward),
+                    RouteEntryAttribute::NextHopID(next_hop_id),
+                ],
+            )?,
+        };
+        log::info!("added route {} to {:?}", destination, target);
+        self.routes.insert(
+            destination,
+            SyncedRoute {
+                target: target,
+                entry: entry,
+                default: default,
+            },
+        );
+        Ok(())
+    }
+
+    /// removes a route, the default routes are set back to drop instead
+    pub(crate) fn route_removed(&mut self, destination: &IpNet) {
+        if let Some(route) = self.routes.remove(destination) {
+            remove_route(route);
+        }
+    }
+
+    /// removes all neighbors and routes from SAI: routes first, as they reference the next hops
+    pub(crate) fn flush(&mut self) {
+        for (_, route) in self.routes.drain() {
+            remove_route(route);
+        }
+        for (ip, neighbor) in self.neighbors.drain() {
+            remove_neighbor(ip, neighbor);
+        }
+    }
+
+    fn repoint_routes(&self, gateway: IpAddr, next_hop_id: NextHopID) {
+        for (destination, route) in self.routes.iter() {
+            if route.target != RouteTarget::Gateway(gateway) {
+                continue;
+            }
+            if let Err(e) = route
+                .entry
+                .set_attribute(RouteEntryAttribute::NextHopID(next_hop_id))
+            {
+                log::error!("failed to point route {destination} via {gateway} to next hop {next_hop_id}: {e:?}");
+            }
+        }
+    }
+}
+
+fn remove_neighbor(ip: IpAddr, neighbor: SyncedNeighbor<'_>) {
+    let next_hop = neighbor.next_hop.to_string();
+    if let Err(e) = neighbor.next_hop.remove() {
+        log::error!("failed to remove next hop {next_hop} of neighbor {ip}: {e:?}");
+    }
+    let entry = neighbor.entry.to_string();
+    match neighbor.entry.remove() {
+        Ok(_) => log::info!("removed neighbor {entry}"),
+        Err(e) => log::error!("failed to remove neighbor {entry}: {e:?}"),
+    }
+}
+
+fn remove_route(route: SyncedRoute<'_>) {
+    let destination = IpNet::from(&route.entry);
+    let res = match route.default {
+        true => route
+            .entry
+            .set_attribute(RouteEntryAttribute::PacketAction(PacketAction::Drop)),
+        false => route.entry.remove(),
+    };
+    match res {
+        Ok(_) => log::info!("removed route {destination}"),
+        Err(e) => log::error!("failed to remove route {destination}: {e:?}"),
+    }
+}
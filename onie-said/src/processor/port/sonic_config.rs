@@ -0,0 +1,533 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::PhysicalPortConfig;
+use super::PhysicalPortConfigFECMode;
+use super::PhysicalPortConfigSpeed;
+
+const PLATFORM_JSON: &str = "platform.json";
+const PORT_CONFIG_INI: &str = "port_config.ini";
+const CONFIG_DB_JSON: &str = "config_db.json";
+const DEFAULT_SKU: &str = "default_sku";
+
+/// the columns of a `port_config.ini` which does not come with a header
+const DEFAULT_INI_COLUMNS: [&str; 5] = ["name", "lanes", "alias", "index", "speed"];
+
+#[derive(Debug, Error)]
+pub(crate) enum SonicConfigError {
+    #[error("failed to read SONiC config file: {0}")]
+    IOError(#[from] std::io::Error),
+
+    #[error("failed to parse SONiC config file: {0}")]
+    SerdeError(#[from] serde_json::Error),
+
+    #[error("invalid SONiC config: {0}")]
+    Invalid(String),
+}
+
+/// a single (logical) port entry from any of the SONiC formats
+#[derive(Debug, Clone, Default)]
+struct SonicPortEntry {
+    name: String,
+    lanes: Vec<u32>,
+    alias: Option<String>,
+    /// the 1-based front panel port: breakout ports share the same index
+    index: Option<u32>,
+    speed: Option<u32>,
+    fec: Option<PhysicalPortConfigFECMode>,
+    mtu: Option<u32>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PlatformJson {
+    interfaces: HashMap<String, PlatformJsonInterface>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PlatformJsonInterface {
+    index: String,
+    lanes: String,
+    /// breakout mode (e.g. `4x25G[10G]`) -> aliases of the ports
+    #[serde(default)]
+    breakout_modes: HashMap<String, Vec<String>>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ConfigDbJson {
+    #[serde(rename = "PORT", default)]
+    port: HashMap<String, HashMap<String, String>>,
+}
+
+/// Looks for the SONiC port configuration in a SONiC device directory of a platform
+/// (e.g. `/usr/share/sonic/device/<platform>/`). The formats are tried in this order:
+/// `platform.json` (as it describes all breakout modes), the `port_config.ini` of the default
+/// HwSKU (or of the first HwSKU which has one), and finally a `config_db.json`.
+pub(crate) fn detect(device_dir: &Path) -> Option<Vec<PhysicalPortConfig>> {
+    let path = device_dir.join(PLATFORM_JSON);
+    if path.exists() {
+        return load(&path, from_platform_json);
+    }
+    if let Some(path) = find_port_config_ini(device_dir) {
+        return load(&path, from_port_config_ini);
+    }
+    let path = device_dir.join(CONFIG_DB_JSON);
+    if path.exists() {
+        return load(&path, from_config_db_json);
+    }
+    log::info!(
+        "no SONiC port configuration found in {}",
+        device_dir.display()
+    );
+    None
+}
+
+fn load(
+    path: &Path,
+    f: fn(&str) -> Result<Vec<PhysicalPortConfig>, SonicConfigError>,
+) -> Option<Vec<PhysicalPortConfig>> {
+    match read_to_string(path).and_then(|contents| f(&contents)) {
+        Ok(v) => {
+            log::info!(
+                "loaded port config for {} physical ports from SONiC config file {}",
+                v.len(),
+                path.display()
+            );
+            Some(v)
+        }
+        Err(e) => {
+            log::error!(
+                "failed to load port config from SONiC config file {}: {}",
+                path.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+fn read_to_string(path: &Path) -> Result<String, SonicConfigError> {
+    let mut file = File::open(path)?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+/// the HwSKU from the `default_sku` file takes precedence, otherwise we take the first one we find
+fn find_port_config_ini(device_dir: &Path) -> Option<PathBuf> {
+    if let Ok(contents) = read_to_string(&device_dir.join(DEFAULT_SKU)) {
+        if let Some(sku) = contents.split_whitespace().next() {
+            let path = device_dir.join(sku).join(PORT_CONFIG_INI);
+            if path.exists() {
+                return Some(path);
+            }
+        }
+    }
+    let path = device_dir.join(PORT_CONFIG_INI);
+    if path.exists() {
+        return Some(path);
+    }
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(device_dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    dirs.sort();
+    dirs.into_iter()
+        .map(|dir| dir.join(PORT_CONFIG_INI))
+        .find(|path| path.exists())
+}
+
+/// parses a SONiC `platform.json`: every interface is a physical port, and the speeds for
+/// every breakout mode come from the breakout mode definitions (e.g. `1x100G[40G]` or `4x25G[10G]`)
+pub(crate) fn from_platform_json(
+    contents: &str,
+) -> Result<Vec<PhysicalPortConfig>, SonicConfigError> {
+    let platform: PlatformJson = serde_json::from_str(contents)?;
+    let mut ports = Vec::with_capacity(platform.interfaces.len());
+    for (name, intf) in platform.interfaces.into_iter() {
+        let lanes = parse_list(&name, "lanes", &intf.lanes)?;
+        let index = parse_list(&name, "index", &intf.index)?
+            .first()
+            .copied()
+            .ok_or_else(|| SonicConfigError::Invalid(format!("{}: empty index", name)))?;
+        let mut speed = PhysicalPortConfigSpeed::default();
+        let mut alias = None;
+        for (mode, aliases) in intf.breakout_modes.iter() {
+            let (num_ports, port_speed) = match parse_breakout_mode(mode) {
+                Some(v) => v,
+                None => {
+                    log::debug!(
+                        "{}: skipping unsupported breakout mode {} from platform.json",
+                        name,
+                        mode
+                    );
+                    continue;
+                }
+            };
+            if num_ports == 0 || lanes.len() % num_ports != 0 {
+                continue;
+            }
+            speed.set(lanes.len() / num_ports, port_speed);
+            if num_ports == 1 {
+                alias = aliases.first().cloned();
+            }
+        }
+        ports.push((
+            index,
+            PhysicalPortConfig::from_sonic(lanes, speed, alias, None, None),
+        ));
+    }
+    Ok(sorted(ports))
+}
+
+/// parses a SONiC `port_config.ini`: the columns are taken from the commented header line
+pub(crate) fn from_port_config_ini(
+    contents: &str,
+) -> Result<Vec<PhysicalPortConfig>, SonicConfigError> {
+    let mut columns: Vec<String> = DEFAULT_INI_COLUMNS.iter().map(|s| s.to_string()).collect();
+    let mut entries = Vec::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('#') {
+            // only the header line has a name column, everything else is a regular comment
+            let header: Vec<String> = header.split_whitespace().map(|s| s.to_string()).collect();
+            if header.first().map(|s| s.as_str()) == Some("name") {
+                columns = header;
+            }
+            continue;
+        }
+        let values: HashMap<&str, &str> = columns
+            .iter()
+            .map(|s| s.as_str())
+            .zip(line.split_whitespace())
+            .collect();
+        entries.push(SonicPortEntry::from_values(&values)?);
+    }
+    Ok(group_entries(entries))
+}
+
+/// parses the `PORT` table of a SONiC `config_db.json`
+pub(crate) fn from_config_db_json(
+    contents: &str,
+) -> Result<Vec<PhysicalPortConfig>, SonicConfigError> {
+    let config_db: ConfigDbJson = serde_json::from_str(contents)?;
+    let mut entries = Vec::with_capacity(config_db.port.len());
+    for (name, values) in config_db.port.iter() {
+        let mut values: HashMap<&str, &str> = values
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        values.insert("name", name.as_str());
+        entries.push(SonicPortEntry::from_values(&values)?);
+    }
+    Ok(group_entries(entries))
+}
+
+impl SonicPortEntry {
+    fn from_values(values: &HashMap<&str, &str>) -> Result<Self, SonicConfigError> {
+        let name = values
+            .get("name")
+            .ok_or_else(|| SonicConfigError::Invalid("port without name".to_string()))?
+            .to_string();
+        let lanes = values
+            .get("lanes")
+            .ok_or_else(|| SonicConfigError::Invalid(format!("{}: no lanes", name)))?;
+        let lanes = parse_list(&name, "lanes", lanes)?;
+        let index = match values.get("index") {
+            Some(v) => parse_list(&name, "index", v)?.first().copied(),
+            None => None,
+        };
+        let speed = match values.get("speed") {
+            Some(v) => Some(parse_u32(&name, "speed", v)?),
+            None => None,
+        };
+        let mtu = match values.get("mtu") {
+            Some(v) => Some(parse_u32(&name, "mtu", v)?),
+            None => None,
+        };
+        let fec = match values.get("fec").map(|v| v.to_lowercase()) {
+            None => None,
+            Some(v) => match v.as_str() {
+                "none" => Some(PhysicalPortConfigFECMode::None),
+                "rs" => Some(PhysicalPortConfigFECMode::RS),
+                "fc" => Some(PhysicalPortConfigFECMode::FC),
+                _ => {
+                    return Err(SonicConfigError::Invalid(format!(
+                        "{}: invalid FEC mode: {}",
+                        name, v
+                    )))
+                }
+            },
+        };
+        Ok(SonicPortEntry {
+            name: name,
+            lanes: lanes,
+            alias: values.get("alias").map(|s| s.to_string()),
+            index: index,
+            speed: speed,
+            fec: fec,
+            mtu: mtu,
+        })
+    }
+}
+
+/// Groups the (logical) port entries to physical ports by their index: ports which are broken out
+/// share the same index. Entries without an index are physical ports on their own.
+fn group_entries(entries: Vec<SonicPortEntry>) -> Vec<PhysicalPortConfig> {
+    let mut groups: BTreeMap<u32, Vec<SonicPortEntry>> = BTreeMap::new();
+    let mut ungrouped = Vec::new();
+    for entry in entries.into_iter() {
+        match entry.index {
+            Some(index) => groups.entry(index).or_default().push(entry),
+            None => ungrouped.push(vec![entry]),
+        }
+    }
+    let mut ports: Vec<(u32, PhysicalPortConfig)> = Vec::with_capacity(groups.len());
+    for (index, group) in groups.into_iter() {
+        ports.push((index, physical_port_config_from_group(group)));
+    }
+    for group in ungrouped.into_iter() {
+        ports.push((u32::MAX, physical_port_config_from_group(group)));
+    }
+    sorted(ports)
+}
+
+fn physical_port_config_from_group(group: Vec<SonicPortEntry>) -> PhysicalPortConfig {
+    let mut group = group;
+    group.sort_by_key(|e| e.lanes.first().copied().unwrap_or_default());
+    let lanes: Vec<u32> = group.iter().flat_map(|e| e.lanes.clone()).collect();
+
+    // every other breakout mode is derived from the speed per lane of the current one
+    let mut speed = PhysicalPortConfigSpeed::default();
+    if let Some((entry, port_speed)) = group.iter().find_map(|e| e.speed.map(|speed| (e, speed))) {
+        let lane_speed = port_speed / entry.lanes.len().max(1) as u32;
+        for num_lanes in [1, 2, 4, 8] {
+            if num_lanes <= lanes.len() {
+                speed.set(num_lanes, lane_speed * num_lanes as u32);
+            }
+        }
+        log::debug!(
+            "{}: deriving port speeds from {} on {} lane(s)",
+            entry.name,
+            port_speed,
+            entry.lanes.len()
+        );
+    }
+
+    let first = group.first();
+    PhysicalPortConfig::from_sonic(
+        lanes,
+        speed,
+        first.and_then(|e| e.alias.clone()),
+        first.and_then(|e| e.fec),
+        first.and_then(|e| e.mtu),
+    )
+}
+
+/// sorts the ports by their index, and by their lanes if the index is missing or ambiguous
+fn sorted(ports: Vec<(u32, PhysicalPortConfig)>) -> Vec<PhysicalPortConfig> {
+    let mut ports = ports;
+    ports.sort_by_key(|(index, pc)| (*index, pc.lanes.first().copied().unwrap_or_default()));
+    ports.into_iter().map(|(_, pc)| pc).collect()
+}
+
+/// parses a breakout mode like `1x100G[40G]`, `2x50G` or `4x25G[10G](4)` into the number of ports
+/// and their speed. Mixed breakout modes (e.g. `1x200G(2)+2x100G(2)`) are not supported.
+fn parse_breakout_mode(mode: &str) -> Option<(usize, u32)> {
+    if mode.contains('+') {
+        return None;
+    }
+    let (num_ports, rest) = mode.split_once('x')?;
+    let num_ports = num_ports.trim().parse::<usize>().ok()?;
+    let speed_len = rest.find(|c: char| !c.is_ascii_digit())?;
+    let speed = rest[..speed_len].parse::<u32>().ok()?;
+    if !rest[speed_len..].starts_with('G') {
+        return None;
+    }
+    Some((num_ports, speed * 1000))
+}
+
+fn parse_list(name: &str, field: &str, value: &str) -> Result<Vec<u32>, SonicConfigError> {
+    value
+        .split(',')
+        .map(|v| parse_u32(name, field, v))
+        .collect()
+}
+
+fn parse_u32(name: &str, field: &str, value: &str) -> Result<u32, SonicConfigError> {
+    value.trim().parse::<u32>().map_err(|e| {
+        SonicConfigError::Invalid(format!("{}: invalid {} {}: {}", name, field, value, e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn speeds(pc: &PhysicalPortConfig) -> [Option<u32>; 4] {
+        [
+            pc.speed.one_lane,
+            pc.speed.two_lanes,
+            pc.speed.four_lanes,
+            pc.speed.eight_lanes,
+        ]
+    }
+
+    #[test]
+    fn config_db_json_from_docs() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../docs/config_db.json");
+        let ports = from_config_db_json(&read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(ports.len(), 15);
+
+        let pc = &ports[0];
+        assert_eq!(pc.lanes, vec![29]);
+        assert_eq!(speeds(pc), [Some(10000), None, None, None]);
+        assert_eq!(pc.alias.as_deref(), Some("Eth1/1"));
+        assert_eq!(pc.mtu, Some(9100));
+
+        let pc = &ports[12];
+        assert_eq!(pc.lanes, vec![41, 42, 43, 44]);
+        assert_eq!(speeds(pc), [Some(25000), Some(50000), Some(100000), None]);
+        assert_eq!(pc.alias.as_deref(), Some("Eth1/13"));
+    }
+
+    #[test]
+    fn config_db_json_fec_is_only_a_hint() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../docs/config_db.json");
+        let ports = from_config_db_json(&read_to_string(&path).unwrap()).unwrap();
+        for pc in ports.iter() {
+            assert_eq!(pc.fec, None);
+            assert_eq!(pc.preferred_fec, Some(PhysicalPortConfigFECMode::None));
+            assert!(pc.discovery_enabled());
+        }
+    }
+
+    #[test]
+    fn config_db_json_invalid_fec() {
+        let ret = from_config_db_json(
+            r#"{"PORT": {"Ethernet0": {"lanes": "0", "index": "1", "fec": "bogus"}}}"#,
+        );
+        assert!(matches!(ret, Err(SonicConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn port_config_ini_with_header_and_breakout() {
+        let ports = from_port_config_ini(
+            "# name          lanes     alias    index  speed   fec\n\
+             Ethernet0       0,1,2,3   Eth1     1      100000  rs\n\
+             # Ethernet4 is broken out\n\
+             Ethernet6       6,7       Eth2/2   2      50000\n\
+             Ethernet4       4,5       Eth2/1   2      50000\n",
+        )
+        .unwrap();
+        assert_eq!(ports.len(), 2);
+
+        assert_eq!(ports[0].lanes, vec![0, 1, 2, 3]);
+        assert_eq!(
+            speeds(&ports[0]),
+            [Some(25000), Some(50000), Some(100000), None]
+        );
+        assert_eq!(ports[0].preferred_fec, Some(PhysicalPortConfigFECMode::RS));
+
+        // the breakout ports are merged, and the first one by lanes names the port
+        assert_eq!(ports[1].lanes, vec![4, 5, 6, 7]);
+        assert_eq!(
+            speeds(&ports[1]),
+            [Some(25000), Some(50000), Some(100000), None]
+        );
+        assert_eq!(ports[1].alias.as_deref(), Some("Eth2/1"));
+        assert_eq!(ports[1].preferred_fec, None);
+    }
+
+    #[test]
+    fn port_config_ini_without_header() {
+        let ports = from_port_config_ini(
+            "Ethernet1 1 Eth2 2 10000\n\
+             Ethernet0 0 Eth1 1 10000\n",
+        )
+        .unwrap();
+        assert_eq!(ports.len(), 2);
+        assert_eq!(ports[0].lanes, vec![0]);
+        assert_eq!(ports[0].alias.as_deref(), Some("Eth1"));
+        assert_eq!(speeds(&ports[0]), [Some(10000), None, None, None]);
+    }
+
+    #[test]
+    fn port_config_ini_invalid_lanes() {
+        let ret = from_port_config_ini("Ethernet0 0,x Eth1 1 10000\n");
+        assert!(matches!(ret, Err(SonicConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn platform_json_breakout_modes() {
+        let ports = from_platform_json(
+            r#"{
+                "interfaces": {
+                    "Ethernet4": {
+                        "index": "2,2,2,2",
+                        "lanes": "4,5,6,7",
+                        "breakout_modes": {
+                            "1x40G": ["Eth2"],
+                            "1x200G(2)+2x100G(2)": ["Eth2/1", "Eth2/2", "Eth2/3"]
+                        }
+                    },
+                    "Ethernet0": {
+                        "index": "1,1,1,1",
+                        "lanes": "0,1,2,3",
+                        "breakout_modes": {
+                            "1x100G[40G]": ["Eth1"],
+                            "2x50G": ["Eth1/1", "Eth1/2"],
+                            "4x25G[10G]": ["Eth1/1", "Eth1/2", "Eth1/3", "Eth1/4"]
+                        }
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(ports.len(), 2);
+
+        assert_eq!(ports[0].lanes, vec![0, 1, 2, 3]);
+        assert_eq!(
+            speeds(&ports[0]),
+            [Some(25000), Some(50000), Some(100000), None]
+        );
+        assert_eq!(ports[0].alias.as_deref(), Some("Eth1"));
+
+        // the mixed breakout mode is skipped
+        assert_eq!(ports[1].lanes, vec![4, 5, 6, 7]);
+        assert_eq!(speeds(&ports[1]), [None, None, Some(40000), None]);
+        assert_eq!(ports[1].alias.as_deref(), Some("Eth2"));
+    }
+
+    #[test]
+    fn platform_json_invalid_index() {
+        let ret =
+            from_platform_json(r#"{"interfaces": {"Ethernet0": {"index": "", "lanes": "0"}}}"#);
+        assert!(matches!(ret, Err(SonicConfigError::Invalid(_))));
+    }
+
+    #[test]
+    fn breakout_modes() {
+        assert_eq!(parse_breakout_mode("1x100G[40G]"), Some((1, 100000)));
+        assert_eq!(parse_breakout_mode("2x50G"), Some((2, 50000)));
+        assert_eq!(parse_breakout_mode("4x25G[10G](4)"), Some((4, 25000)));
+        assert_eq!(parse_breakout_mode("8x50G"), Some((8, 50000)));
+        assert_eq!(parse_breakout_mode("1x200G(2)+2x100G(2)"), None);
+        assert_eq!(parse_breakout_mode("1x100M"), None);
+        assert_eq!(parse_breakout_mode("100G"), None);
+        assert_eq!(parse_breakout_mode("xx100G"), None);
+    }
+}
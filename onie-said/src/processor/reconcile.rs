@@ -0,0 +1,106 @@
+use sai::Status;
+
+/// a divergence between the desired state and the actual state in SAI or the kernel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Drift {
+    pub(crate) description: String,
+    pub(crate) repaired: bool,
+}
+
+impl Drift {
+    pub(crate) fn new(description: String, repaired: bool) -> Self {
+        Self {
+            description: description,
+            repaired: repaired,
+        }
+    }
+}
+
+impl std::fmt::Display for Drift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.repaired {
+            true => write!(f, "{} (repaired)", self.description),
+            false => write!(f, "{} (not repaired)", self.description),
+        }
+    }
+}
+
+/// the counters of the reconciliation loop since the start of the processor
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ReconcileStats {
+    pub(crate) runs: u64,
+    pub(crate) drift_count: u64,
+    pub(crate) repair_count: u64,
+    /// the divergences of the last run which could not be repaired
+    pub(crate) unrepaired: Vec<String>,
+}
+
+impl ReconcileStats {
+    pub(crate) fn record(&mut self, drift: Vec<Drift>) {
+        self.runs += 1;
+        self.drift_count += drift.len() as u64;
+        self.repair_count += drift.iter().filter(|d| d.repaired).count() as u64;
+        self.unrepaired = drift
+            .into_iter()
+            .filter(|d| !d.repaired)
+            .map(|d| d.description)
+            .collect();
+    }
+}
+
+/// if a SAI call failed because the object does not exist in SAI (anymore)
+pub(crate) fn object_missing<T>(res: &Result<T, sai::Error>) -> bool {
+    matches!(
+        res,
+        Err(sai::Error::SAI(Status::ItemNotFound)) | Err(sai::Error::SAI(Status::InvalidObjectId))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_drift() {
+        let mut stats = ReconcileStats::default();
+        stats.record(vec![]);
+        assert_eq!(stats.runs, 1);
+        assert_eq!(stats.drift_count, 0);
+        assert_eq!(stats.repair_count, 0);
+        assert!(stats.unrepaired.is_empty());
+
+        stats.record(vec![
+            Drift::new("a".to_string(), true),
+            Drift::new("b".to_string(), false),
+            Drift::new("c".to_string(), false),
+        ]);
+        assert_eq!(stats.runs, 2);
+        assert_eq!(stats.drift_count, 3);
+        assert_eq!(stats.repair_count, 1);
+        assert_eq!(stats.unrepaired, vec!["b".to_string(), "c".to_string()]);
+
+        // the counters add up, but only the unrepaired drift of the last run is kept
+        stats.record(vec![Drift::new("d".to_string(), true)]);
+        assert_eq!(stats.runs, 3);
+        assert_eq!(stats.drift_count, 4);
+        assert_eq!(stats.repair_count, 2);
+        assert!(stats.unrepaired.is_empty());
+    }
+
+    #[test]
+    fn object_missing_status() {
+        assert!(object_missing::<()>(&Err(sai::Error::SAI(
+            Status::ItemNotFound
+        ))));
+        assert!(object_missing::<()>(&Err(sai::Error::SAI(
+            Status::InvalidObjectId
+        ))));
+        assert!(!object_missing::<()>(&Err(sai::Error::SAI(
+            Status::NotSupported
+        ))));
+        assert!(!object_missing::<()>(&Err(
+            sai::Error::APIFunctionUnavailable
+        )));
+        assert!(!object_missing(&Ok(())));
+    }
+}
@@ -0,0 +1,571 @@
+use std::ffi::CStr;
+use std::fs::File;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::unix::net::UnixStream;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::sync::mpsc::Sender;
+use std::sync::mpsc::TryRecvError;
+use std::sync::Arc;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+
+use onie_sai_rpc::onie_sai;
+use sai::switch::Switch;
+use thiserror::Error;
+
+use super::ProcessError;
+
+/// the default timeout for a shell exec command if the request does not specify one
+pub(crate) const SHELL_EXEC_DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// the default idle timeout for interactive shell sessions if the request does not specify one
+pub(crate) const SHELL_DEFAULT_IDLE_TIMEOUT_SECS: u64 = 300;
+
+/// the shell is considered to be waiting for input if there was no output for this long: this is when
+/// its prompt is taken from its output, and the command is considered done if there is no prompt
+const SHELL_EXEC_IDLE_MILLIS: u64 = 500;
+
+/// the command which makes the vendor shell quit
+const SHELL_QUIT: &[u8] = b"quit\n";
+
+#[derive(Debug, Error)]
+pub(crate) enum ShellError {
+    #[error("pseudo-terminal IO error: {0}")]
+    IOError(#[from] std::io::Error),
+
+    #[error("SAI command failed: {0}")]
+    SAIError(#[from] sai::Error),
+}
+
+/// a pseudo-terminal pair: the vendor shell gets the slave side as stdin and stdout
+/// and we are reading from and writing to the master side
+struct Pty {
+    master: File,
+    slave: File,
+}
+
+impl Pty {
+    fn new(echo: bool) -> Result<Self, std::io::Error> {
+        let master_fd =
+            unsafe { libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY | libc::O_CLOEXEC) };
+        if master_fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let master = unsafe { File::from_raw_fd(master_fd) };
+        if unsafe { libc::grantpt(master_fd) } < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        if unsafe { libc::unlockpt(master_fd) } < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let mut name = [0 as libc::c_char; 128];
+        let ret = unsafe { libc::ptsname_r(master_fd, name.as_mut_ptr(), name.len()) };
+        if ret != 0 {
+            return Err(std::io::Error::from_raw_os_error(ret));
+        }
+        let name = unsafe { CStr::from_ptr(name.as_ptr()) };
+        let slave_fd = unsafe {
+            libc::open(
+                name.as_ptr(),
+                libc::O_RDWR | libc::O_NOCTTY | libc::O_CLOEXEC,
+            )
+        };
+        if slave_fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let slave = unsafe { File::from_raw_fd(slave_fd) };
+
+        // there is no process group which we want to receive signals from the line discipline (Ctrl+C, etc.)
+        let mut tio: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(slave_fd, &mut tio) } < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        tio.c_lflag &= !libc::ISIG;
+        if !echo {
+            tio.c_lflag &= !libc::ECHO;
+        }
+        if unsafe { libc::tcsetattr(slave_fd, libc::TCSANOW, &tio) } < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        // we poll the master side, so it must not block
+        set_nonblocking(&master)?;
+
+        Ok(Self { master, slave })
+    }
+}
+
+fn set_nonblocking(file: &File) -> Result<(), std::io::Error> {
+    let fd = file.as_raw_fd();
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    if unsafe { libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) } < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn set_window_size(master: &File, rows: u16, cols: u16) -> Result<(), std::io::Error> {
+    let ws = libc::winsize {
+        ws_row: rows,
+        ws_col: cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    if unsafe { libc::ioctl(master.as_raw_fd(), libc::TIOCSWINSZ, &ws) } < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// The vendor shell always reads from stdin and writes to stdout of our process.
+/// This points stdin and stdout to the given file for as long as it lives, and restores
+/// them again when it is dropped.
+/// NOTE: the daemon itself never writes to stdout (it logs to stderr), so that only the output of
+/// the vendor shell ends up in the session
+struct StdioRedirect {
+    stdin: File,
+    stdout: File,
+}
+
+impl StdioRedirect {
+    fn new(to: &File) -> Result<Self, std::io::Error> {
+        let _ = std::io::stdout().flush();
+        let stdin_fd = unsafe { libc::dup(libc::STDIN_FILENO) };
+        if stdin_fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let stdin = unsafe { File::from_raw_fd(stdin_fd) };
+        let stdout_fd = unsafe { libc::dup(libc::STDOUT_FILENO) };
+        if stdout_fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let stdout = unsafe { File::from_raw_fd(stdout_fd) };
+
+        if unsafe { libc::dup2(to.as_raw_fd(), libc::STDIN_FILENO) } < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        if unsafe { libc::dup2(to.as_raw_fd(), libc::STDOUT_FILENO) } < 0 {
+            let err = std::io::Error::last_os_error();
+            unsafe { libc::dup2(stdin.as_raw_fd(), libc::STDIN_FILENO) };
+            return Err(err);
+        }
+        Ok(Self { stdin, stdout })
+    }
+}
+
+impl Drop for StdioRedirect {
+    fn drop(&mut self) {
+        // whatever the vendor shell has buffered in its stdio streams still belongs to the session
+        let _ = std::io::stdout().flush();
+        unsafe { libc::fflush(std::ptr::null_mut()) };
+        if unsafe { libc::dup2(self.stdin.as_raw_fd(), libc::STDIN_FILENO) } < 0 {
+            log::error!(
+                "shell: failed to restore stdin: {:?}",
+                std::io::Error::last_os_error()
+            );
+        }
+        if unsafe { libc::dup2(self.stdout.as_raw_fd(), libc::STDOUT_FILENO) } < 0 {
+            log::error!(
+                "shell: failed to restore stdout: {:?}",
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+}
+
+/// what the session does with the shell while it is running
+pub(crate) enum ShellMode {
+    /// bridges the shell to a client which is connected over the given connection
+    Interactive {
+        conn: UnixStream,
+        rows: u16,
+        cols: u16,
+        idle_timeout: Duration,
+    },
+    /// runs a single command, and sends its output back once the shell quit, or right away with
+    /// the partial output if the command timed out
+    Exec {
+        command: String,
+        timeout: Duration,
+        resp_tx: Sender<Result<onie_sai::ShellExecResponse, ProcessError>>,
+    },
+}
+
+/// A running vendor shell. The shell runs in its own thread, so that the processor
+/// can continue to process events while a shell is open. The shell runs inside of SAI, so the
+/// session borrows the switch, and dropping it waits until the shell is gone.
+/// NOTE: there can only be one session at a time as the shell uses stdin and stdout of the process
+#[derive(Debug)]
+pub(crate) struct ShellSession<'a> {
+    pub(crate) id: u64,
+    master: File,
+    finished: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    switch: PhantomData<Switch<'a>>,
+}
+
+impl<'a> ShellSession<'a> {
+    pub(crate) fn start(id: u64, switch: &Switch<'a>, mode: ShellMode) -> Result<Self, ShellError> {
+        let enable_shell = switch.shell_enabler()?;
+        let pty = Pty::new(matches!(mode, ShellMode::Interactive { .. }))?;
+        if let ShellMode::Interactive { rows, cols, .. } = mode {
+            if rows > 0 && cols > 0 {
+                set_window_size(&pty.master, rows, cols)?;
+            }
+        }
+        let master = pty.master.try_clone()?;
+        let bridge_master = pty.master.try_clone()?;
+        let redirect = StdioRedirect::new(&pty.slave)?;
+        let finished = Arc::new(AtomicBool::new(false));
+        let thread_finished = finished.clone();
+
+        let thread = thread::spawn(move || {
+            log::debug!("shell session {id}: started");
+            let (exit_tx, exit_rx) = mpsc::channel::<()>();
+            let bridge = thread::spawn(move || match mode {
+                ShellMode::Interactive {
+                    conn, idle_timeout, ..
+                } => {
+                    bridge_interactive(id, bridge_master, conn, idle_timeout, exit_rx);
+                    None
+                }
+                ShellMode::Exec {
+                    command,
+                    timeout,
+                    resp_tx,
+                } => {
+                    let mut master = bridge_master;
+                    let exec_ret = bridge_exec(id, &mut master, command, timeout, &exit_rx);
+                    if !matches!(exec_ret, Ok((_, true))) {
+                        return Some((exec_ret, resp_tx));
+                    }
+
+                    // a command which timed out can keep the shell busy for much longer, so the
+                    // response with the partial output goes out right away
+                    if let Err(e) = resp_tx.send(
+                        exec_ret
+                            .map(|(output, timed_out)| onie_sai::ShellExecResponse {
+                                output: output,
+                                timed_out: timed_out,
+                                ..Default::default()
+                            })
+                            .map_err(|e| ProcessError::ShellIOError(e)),
+                    ) {
+                        log::error!("failed to send shell exec response to rpc server: {:?}", e);
+                    }
+                    drain(id, &mut master, &exit_rx);
+                    None
+                }
+            });
+
+            // this blocks until the shell quits
+            let shell_ret = enable_shell();
+            if let Err(e) = shell_ret.as_ref() {
+                log::error!("shell session {id}: shell failed: {:?}", e);
+            }
+
+            // give stdin and stdout back first, so that the bridge can drain everything that is left
+            drop(redirect);
+            drop(pty);
+            let _ = exit_tx.send(());
+            match bridge.join() {
+                Ok(Some((exec_ret, resp_tx))) => {
+                    let resp = match (shell_ret, exec_ret) {
+                        (Err(e), _) => Err(ProcessError::SAIError(e)),
+                        (Ok(_), Err(e)) => Err(ProcessError::ShellIOError(e)),
+                        (Ok(_), Ok((output, timed_out))) => Ok(onie_sai::ShellExecResponse {
+                            output: output,
+                            timed_out: timed_out,
+                            ..Default::default()
+                        }),
+                    };
+                    if let Err(e) = resp_tx.send(resp) {
+                        log::error!("failed to send shell exec response to rpc server: {:?}", e);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => log::error!("shell session {id}: bridge thread paniced: {:?}", e),
+            }
+
+            thread_finished.store(true, Ordering::SeqCst);
+            log::debug!("shell session {id}: finished");
+        });
+
+        Ok(Self {
+            id,
+            master,
+            finished,
+            thread: Some(thread),
+            switch: PhantomData,
+        })
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn resize(&self, rows: u16, cols: u16) -> Result<(), ShellError> {
+        set_window_size(&self.master, rows, cols)?;
+        Ok(())
+    }
+
+    /// asks the vendor shell to quit, and waits up to the given timeout for the session to finish
+    pub(crate) fn quit(&self, timeout: Duration) -> Result<(), ShellError> {
+        (&self.master).write_all(SHELL_QUIT)?;
+        let start = Instant::now();
+        while !self.is_finished() && start.elapsed() < timeout {
+            thread::sleep(Duration::from_millis(10));
+        }
+        if !self.is_finished() {
+            return Err(ShellError::IOError(std::io::Error::new(
+                ErrorKind::TimedOut,
+                "shell did not quit",
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ShellSession<'_> {
+    fn drop(&mut self) {
+        let thread = match self.thread.take() {
+            Some(thread) => thread,
+            None => return,
+        };
+        // the shell must never outlive SAI, no matter how long it takes to quit
+        if !self.is_finished() {
+            log::warn!("shell session {}: waiting for the shell to quit", self.id);
+            let _ = (&self.master).write_all(SHELL_QUIT);
+        }
+        if let Err(e) = thread.join() {
+            log::error!("shell session {}: session thread paniced: {:?}", self.id, e);
+        }
+    }
+}
+
+/// the session thread signals that the shell quit, or it went away without doing so
+fn shell_exited(exit_rx: &mpsc::Receiver<()>) -> bool {
+    !matches!(exit_rx.try_recv(), Err(TryRecvError::Empty))
+}
+
+fn bridge_interactive(
+    id: u64,
+    mut master: File,
+    mut conn: UnixStream,
+    idle_timeout: Duration,
+    exit_rx: mpsc::Receiver<()>,
+) {
+    if let Err(e) = conn.set_nonblocking(true) {
+        log::error!("shell session {id}: failed to set connection to non-blocking: {e:?}");
+    }
+    let mut buf = [0u8; 1024];
+    let mut last_input = Instant::now();
+    let mut quit_sent = false;
+    let mut client_connected = true;
+    let mut need_to_exit_thread = false;
+    loop {
+        need_to_exit_thread |= shell_exited(&exit_rx);
+        let mut idle = true;
+
+        // client -> shell
+        if client_connected {
+            match conn.read(&mut buf) {
+                Ok(0) => {
+                    // the client is gone, so there is no reason to keep the shell open
+                    log::debug!("shell session {id}: client disconnected");
+                    client_connected = false;
+                    if !quit_sent {
+                        let _ = master.write_all(SHELL_QUIT);
+                        quit_sent = true;
+                    }
+                }
+                Ok(n) => {
+                    idle = false;
+                    last_input = Instant::now();
+                    if let Err(e) = master.write_all(&buf[..n]) {
+                        log::error!("shell session {id}: failed to write to shell: {e:?}");
+                    }
+                }
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(e) => {
+                    log::error!("shell session {id}: failed to read from client: {e:?}");
+                    client_connected = false;
+                }
+            }
+        }
+
+        // shell -> client
+        match master.read(&mut buf) {
+            Ok(0) => {}
+            Ok(n) => {
+                idle = false;
+                if client_connected {
+                    if let Err(e) = conn.write_all(&buf[..n]) {
+                        log::error!("shell session {id}: failed to write to client: {e:?}");
+                        client_connected = false;
+                    }
+                }
+            }
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {}
+            // EIO: the slave side is closed, the shell is gone
+            Err(_) => {
+                if need_to_exit_thread {
+                    break;
+                }
+            }
+        }
+
+        if !quit_sent && last_input.elapsed() >= idle_timeout {
+            log::warn!("shell session {id}: idle for {idle_timeout:?}, closing session");
+            if client_connected {
+                let _ = conn.write_all(b"\r\nonie-said: shell session timed out\r\n");
+            }
+            let _ = master.write_all(SHELL_QUIT);
+            quit_sent = true;
+        }
+
+        if need_to_exit_thread && idle {
+            break;
+        }
+        if idle {
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+    let _ = conn.shutdown(std::net::Shutdown::Both);
+    log::debug!("shell session {id}: bridge thread exiting");
+}
+
+fn bridge_exec(
+    id: u64,
+    master: &mut File,
+    command: String,
+    timeout: Duration,
+    exit_rx: &mpsc::Receiver<()>,
+) -> anyhow::Result<(String, bool)> {
+    let mut output = Vec::new();
+    let mut buf = [0u8; 1024];
+    let start = Instant::now();
+    let mut last_output = start;
+    let mut prompt: Option<Vec<u8>> = None;
+    let mut command_sent = false;
+    let mut quit_sent = false;
+    let mut timed_out = false;
+    let mut need_to_exit_thread = false;
+    loop {
+        need_to_exit_thread |= shell_exited(exit_rx);
+        let idle = last_output.elapsed() >= Duration::from_millis(SHELL_EXEC_IDLE_MILLIS);
+        if !command_sent && (idle || need_to_exit_thread) {
+            // whatever the shell printed when it started ends with its prompt
+            prompt = last_line(&output);
+            log::debug!(
+                "shell session {id}: shell prompt: {:?}",
+                prompt.as_ref().map(|p| String::from_utf8_lossy(p))
+            );
+            output.clear();
+            master.write_all(format!("{command}\n").as_bytes())?;
+            command_sent = true;
+            last_output = Instant::now();
+        } else if command_sent && !quit_sent {
+            let done = match prompt.as_ref() {
+                Some(prompt) => match prompt_position(&output, prompt) {
+                    Some(pos) => {
+                        output.truncate(pos);
+                        true
+                    }
+                    None => false,
+                },
+                // without a prompt we can only guess that the command is done once it is quiet
+                None => !output.is_empty() && idle,
+            };
+            if done {
+                master.write_all(SHELL_QUIT)?;
+                quit_sent = true;
+            } else if start.elapsed() >= timeout {
+                // NOTE: the shell runs in our process, so there is nothing that we can signal: it only
+                // reads the quit once the command is done, and we are not waiting for that
+                let _ = master.write_all(SHELL_QUIT);
+                timed_out = true;
+                break;
+            }
+        }
+        match master.read(&mut buf) {
+            Ok(n) if n > 0 => {
+                // NOTE: everything after the quit is only the shell saying goodbye
+                if !quit_sent {
+                    output.extend_from_slice(&buf[..n]);
+                }
+                last_output = Instant::now();
+                continue;
+            }
+            Ok(_) => {}
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {}
+            // EIO: the slave side is closed, the shell is gone
+            Err(_) if need_to_exit_thread => break,
+            Err(_) => {}
+        }
+        if need_to_exit_thread {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    if timed_out {
+        log::warn!("shell session {id}: command '{command}' timed out after {timeout:?}");
+    }
+    log::debug!("shell session {id}: exec thread exiting");
+
+    // the terminal uses CRLF line endings which nobody wants in a captured output
+    let output = String::from_utf8_lossy(&output).replace("\r\n", "\n");
+    Ok((output, timed_out))
+}
+
+/// discards the shell output until it quits, so that it never blocks on writing to a full terminal
+fn drain(id: u64, master: &mut File, exit_rx: &mpsc::Receiver<()>) {
+    let mut buf = [0u8; 1024];
+    loop {
+        let need_to_exit_thread = shell_exited(exit_rx);
+        if let Ok(n) = master.read(&mut buf) {
+            if n > 0 {
+                continue;
+            }
+        }
+        if need_to_exit_thread {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    log::debug!("shell session {id}: drained the shell output until it quit");
+}
+
+/// the last line of the output without surrounding whitespace, if there is any
+fn last_line(output: &[u8]) -> Option<Vec<u8>> {
+    let line = output
+        .rsplit(|b| *b == b'\n')
+        .next()
+        .unwrap_or_default()
+        .trim_ascii();
+    match line.is_empty() {
+        true => None,
+        false => Some(line.to_vec()),
+    }
+}
+
+/// the position of the prompt if the output ends with it
+fn prompt_position(output: &[u8], prompt: &[u8]) -> Option<usize> {
+    let output = output.trim_ascii_end();
+    match output.ends_with(prompt) {
+        true => Some(output.len() - prompt.len()),
+        false => None,
+    }
+}
@@ -0,0 +1,140 @@
+use std::fs::File;
+use std::io::Read;
+use std::io::Write;
+use std::path::PathBuf;
+
+use sai::ObjectID;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::port::HostInterface;
+use super::port::LogicalPort;
+use super::port::PhysicalPort;
+
+#[derive(Debug, Error)]
+pub(crate) enum WarmBootError {
+    #[error("failed to read/write warm boot state file: {0}")]
+    IOError(#[from] std::io::Error),
+
+    #[error("failed to parse/serialize warm boot state file: {0}")]
+    SerdeError(#[from] serde_json::Error),
+}
+
+/// Warm boot settings of the processor. If this is passed to the processor, then
+/// the processor will persist its state on shutdown and perform a warm shutdown of
+/// the switch. If `state` is set, then the processor will not initialize the switch
+/// from scratch, but it will rebuild its state from the ASIC and the state.
+#[derive(Debug, Clone)]
+pub(crate) struct WarmBoot {
+    pub(crate) state_file: PathBuf,
+    pub(crate) state: Option<WarmBootState>,
+}
+
+/// The state of the processor which we cannot recover from SAI alone.
+/// It is being written to a file on warm shutdown, and read again on warm boot.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct WarmBootState {
+    pub(crate) auto_discovery: bool,
+    pub(crate) auto_discovery_with_breakout: bool,
+    pub(crate) cpu_hostif: u64,
+    pub(crate) routes: Vec<String>,
+    pub(crate) ports: Vec<PhysicalPortState>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct PhysicalPortState {
+    pub(crate) idx: usize,
+    pub(crate) lanes: Vec<u32>,
+    pub(crate) auto_discovery: bool,
+    pub(crate) auto_discovery_with_breakout: bool,
+    pub(crate) auto_discovery_counter: u64,
+    pub(crate) oper_status: bool,
+    /// `None` if there was no discovery state machine, or if it was not done yet.
+    /// Otherwise it describes if the discovery was successful.
+    pub(crate) discovery_result: Option<bool>,
+    pub(crate) ports: Vec<LogicalPortState>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct LogicalPortState {
+    pub(crate) oid: u64,
+    pub(crate) lanes: Vec<u32>,
+    /// `None` if there was no discovery state machine, or if it was not done yet.
+    /// Otherwise it describes if the discovery was successful.
+    pub(crate) discovery_result: Option<bool>,
+    pub(crate) hif: Option<HostInterfaceState>,
+    pub(crate) rif: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub(crate) struct HostInterfaceState {
+    pub(crate) oid: u64,
+    pub(crate) name: String,
+    pub(crate) oper_status: bool,
+}
+
+impl WarmBootState {
+    pub(crate) fn from_file(path: &PathBuf) -> Result<Self, WarmBootError> {
+        let mut file = File::open(path)?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        let state: WarmBootState = serde_json::from_str(&contents)?;
+        Ok(state)
+    }
+
+    pub(crate) fn to_file(&self, path: &PathBuf) -> Result<(), WarmBootError> {
+        let contents = serde_json::to_string(self)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = File::create(path)?;
+        file.write_all(contents.as_bytes())?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+impl From<&PhysicalPort<'_, '_>> for PhysicalPortState {
+    fn from(port: &PhysicalPort<'_, '_>) -> Self {
+        Self {
+            idx: port.idx,
+            lanes: port.lanes.clone(),
+            auto_discovery: port.auto_discovery,
+            auto_discovery_with_breakout: port.auto_discovery_with_breakout,
+            auto_discovery_counter: port.auto_discovery_counter,
+            oper_status: port.oper_status,
+            discovery_result: port
+                .sm
+                .as_ref()
+                .filter(|sm| sm.is_done())
+                .map(|sm| sm.is_done_and_success()),
+            ports: port.ports.iter().map(|p| p.into()).collect(),
+        }
+    }
+}
+
+impl From<&LogicalPort<'_>> for LogicalPortState {
+    fn from(port: &LogicalPort<'_>) -> Self {
+        Self {
+            oid: port.port.to_id().into(),
+            lanes: port.lanes.clone(),
+            discovery_result: port
+                .sm
+                .as_ref()
+                .filter(|sm| sm.is_done())
+                .map(|sm| sm.is_done_and_success()),
+            hif: port.hif.as_ref().map(|hif| hif.into()),
+            rif: port.rif.as_ref().map(|rif| rif.to_id().into()),
+        }
+    }
+}
+
+impl From<&HostInterface<'_>> for HostInterfaceState {
+    fn from(hif: &HostInterface<'_>) -> Self {
+        Self {
+            oid: hif.intf.to_id().into(),
+            name: hif.name.clone(),
+            oper_status: hif.oper_status,
+        }
+    }
+}
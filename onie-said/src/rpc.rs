@@ -9,6 +9,7 @@ use ttrpc::Server;
 use onie_sai_rpc::onie_sai;
 use onie_sai_rpc::onie_sai_ttrpc;
 
+use crate::processor::port::PortError;
 use crate::processor::ProcessError;
 use crate::processor::ProcessRequest;
 
@@ -45,6 +46,34 @@ impl onie_sai_ttrpc::OnieSai for OnieSaiServer {
         Ok(resp)
     }
 
+    fn shell_exec(
+        &self,
+        _ctx: &ttrpc::TtrpcContext,
+        req: onie_sai::ShellExecRequest,
+    ) -> ttrpc::Result<onie_sai::ShellExecResponse> {
+        let (tx, rx) = channel();
+        self.proc_tx
+            .send(ProcessRequest::ShellExec((req, tx)))
+            .map_err(map_tx_error)?;
+        let resp = rx.recv().map_err(map_rx_error)?;
+        let resp = resp.map_err(map_process_error)?;
+        Ok(resp)
+    }
+
+    fn shell_resize(
+        &self,
+        _ctx: &ttrpc::TtrpcContext,
+        req: onie_sai::ShellResizeRequest,
+    ) -> ttrpc::Result<onie_sai::ShellResizeResponse> {
+        let (tx, rx) = channel();
+        self.proc_tx
+            .send(ProcessRequest::ShellResize((req, tx)))
+            .map_err(map_tx_error)?;
+        let resp = rx.recv().map_err(map_rx_error)?;
+        let resp = resp.map_err(map_process_error)?;
+        Ok(resp)
+    }
+
     fn port_list(
         &self,
         _ctx: &ttrpc::TtrpcContext,
@@ -139,6 +168,104 @@ impl onie_sai_ttrpc::OnieSai for OnieSaiServer {
         let resp = resp.map_err(map_process_error)?;
         Ok(resp)
     }
+
+    fn switch_status(
+        &self,
+        _ctx: &ttrpc::TtrpcContext,
+        req: onie_sai::SwitchStatusRequest,
+    ) -> ttrpc::Result<onie_sai::SwitchStatusResponse> {
+        let (tx, rx) = channel();
+        self.proc_tx
+            .send(ProcessRequest::SwitchStatus((req, tx)))
+            .map_err(map_tx_error)?;
+        let resp = rx.recv().map_err(map_rx_error)?;
+        let resp = resp.map_err(map_process_error)?;
+        Ok(resp)
+    }
+
+    fn reconcile_status(
+        &self,
+        _ctx: &ttrpc::TtrpcContext,
+        req: onie_sai::ReconcileStatusRequest,
+    ) -> ttrpc::Result<onie_sai::ReconcileStatusResponse> {
+        let (tx, rx) = channel();
+        self.proc_tx
+            .send(ProcessRequest::ReconcileStatus((req, tx)))
+            .map_err(map_tx_error)?;
+        let resp = rx.recv().map_err(map_rx_error)?;
+        let resp = resp.map_err(map_process_error)?;
+        Ok(resp)
+    }
+
+    fn rediscover(
+        &self,
+        _ctx: &ttrpc::TtrpcContext,
+        req: onie_sai::RediscoverRequest,
+    ) -> ttrpc::Result<onie_sai::RediscoverResponse> {
+        let (tx, rx) = channel();
+        self.proc_tx
+            .send(ProcessRequest::Rediscover((req, tx)))
+            .map_err(map_tx_error)?;
+        let resp = rx.recv().map_err(map_rx_error)?;
+        let resp = resp.map_err(map_process_error)?;
+        Ok(resp)
+    }
+
+    fn set_port(
+        &self,
+        _ctx: &ttrpc::TtrpcContext,
+        req: onie_sai::SetPortRequest,
+    ) -> ttrpc::Result<onie_sai::SetPortResponse> {
+        let (tx, rx) = channel();
+        self.proc_tx
+            .send(ProcessRequest::SetPort((req, tx)))
+            .map_err(map_tx_error)?;
+        let resp = rx.recv().map_err(map_rx_error)?;
+        let resp = resp.map_err(map_process_error)?;
+        Ok(resp)
+    }
+
+    fn discovery_profile(
+        &self,
+        _ctx: &ttrpc::TtrpcContext,
+        req: onie_sai::DiscoveryProfileRequest,
+    ) -> ttrpc::Result<onie_sai::DiscoveryProfileResponse> {
+        let (tx, rx) = channel();
+        self.proc_tx
+            .send(ProcessRequest::DiscoveryProfile((req, tx)))
+            .map_err(map_tx_error)?;
+        let resp = rx.recv().map_err(map_rx_error)?;
+        let resp = resp.map_err(map_process_error)?;
+        Ok(resp)
+    }
+
+    fn validate_port_config(
+        &self,
+        _ctx: &ttrpc::TtrpcContext,
+        req: onie_sai::ValidatePortConfigRequest,
+    ) -> ttrpc::Result<onie_sai::ValidatePortConfigResponse> {
+        let (tx, rx) = channel();
+        self.proc_tx
+            .send(ProcessRequest::ValidatePortConfig((req, tx)))
+            .map_err(map_tx_error)?;
+        let resp = rx.recv().map_err(map_rx_error)?;
+        let resp = resp.map_err(map_process_error)?;
+        Ok(resp)
+    }
+
+    fn sai_dump(
+        &self,
+        _ctx: &ttrpc::TtrpcContext,
+        req: onie_sai::SAIDumpRequest,
+    ) -> ttrpc::Result<onie_sai::SAIDumpResponse> {
+        let (tx, rx) = channel();
+        self.proc_tx
+            .send(ProcessRequest::SAIDump((req, tx)))
+            .map_err(map_tx_error)?;
+        let resp = rx.recv().map_err(map_rx_error)?;
+        let resp = resp.map_err(map_process_error)?;
+        Ok(resp)
+    }
 }
 
 fn map_tx_error<T: std::fmt::Debug>(e: T) -> ttrpc::error::Error {
@@ -156,8 +283,23 @@ fn map_rx_error<T: std::fmt::Debug>(e: T) -> ttrpc::error::Error {
 }
 
 fn map_process_error(e: ProcessError) -> ttrpc::error::Error {
+    let code = match e {
+        ProcessError::ShellBusy(_) => ttrpc::Code::UNAVAILABLE,
+        ProcessError::NoSuchShellSession(_) => ttrpc::Code::NOT_FOUND,
+        ProcessError::ShellUnavailable => ttrpc::Code::FAILED_PRECONDITION,
+        ProcessError::NoSuchPort(_) => ttrpc::Code::NOT_FOUND,
+        ProcessError::InvalidArgument(_) => ttrpc::Code::INVALID_ARGUMENT,
+        ProcessError::AutoDiscoveryDisabled(_) => ttrpc::Code::FAILED_PRECONDITION,
+        ProcessError::PortError(PortError::NoSuchLogicalPort(_, _)) => ttrpc::Code::NOT_FOUND,
+        ProcessError::PortError(PortError::BreakoutModeUnsupported(_, _))
+        | ProcessError::PortError(PortError::SpeedUnsupported(_, _))
+        | ProcessError::PortError(PortError::SpeedUnsupportedForBreakoutMode(_, _, _, _)) => {
+            ttrpc::Code::INVALID_ARGUMENT
+        }
+        _ => ttrpc::Code::INTERNAL,
+    };
     ttrpc::Error::RpcStatus(ttrpc::get_status(
-        ttrpc::Code::INTERNAL,
+        code,
         format!("processor failed to process request: {}", e),
     ))
 }
@@ -0,0 +1,268 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use thiserror::Error;
+
+/// the ID string at the start of every ONIE TlvInfo EEPROM
+const TLV_INFO_ID: &[u8; 8] = b"TlvInfo\0";
+
+/// the only header version that is defined by ONIE
+const TLV_INFO_VERSION: u8 = 0x01;
+
+/// ID string (8 bytes), version (1 byte) and total length (2 bytes)
+const TLV_INFO_HEADER_SIZE: usize = 11;
+
+/// the maximum size of the EEPROM contents as defined by ONIE
+const TLV_INFO_MAX_SIZE: usize = 2048;
+
+pub(crate) const TLV_CODE_PRODUCT_NAME: u8 = 0x21;
+pub(crate) const TLV_CODE_PART_NUMBER: u8 = 0x22;
+pub(crate) const TLV_CODE_SERIAL_NUMBER: u8 = 0x23;
+pub(crate) const TLV_CODE_MAC_BASE: u8 = 0x24;
+pub(crate) const TLV_CODE_PLATFORM_NAME: u8 = 0x28;
+pub(crate) const TLV_CODE_MAC_SIZE: u8 = 0x2a;
+pub(crate) const TLV_CODE_CRC_32: u8 = 0xfe;
+
+#[derive(Debug, Error)]
+pub(crate) enum SysEepromError {
+    #[error("failed to read system EEPROM: {0}")]
+    IOError(#[from] std::io::Error),
+
+    #[error("invalid TlvInfo header")]
+    InvalidHeader,
+
+    #[error("unsupported TlvInfo version {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("TlvInfo data is truncated")]
+    Truncated,
+
+    #[error("TlvInfo data has no CRC-32 TLV at the end")]
+    MissingCRC,
+
+    #[error("TlvInfo CRC-32 mismatch: expected {expected:#010x}, calculated {calculated:#010x}")]
+    CRCMismatch { expected: u32, calculated: u32 },
+}
+
+/// The contents of an ONIE TlvInfo system EEPROM. Only the TLVs are kept, and they are only
+/// available if the header and the CRC-32 are valid.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SysEeprom {
+    tlvs: Vec<(u8, Vec<u8>)>,
+}
+
+impl SysEeprom {
+    /// reads the EEPROM from a file: this is usually the sysfs file of the EEPROM driver
+    /// NOTE: sysfs files of EEPROMs are larger than the TlvInfo data, so we only read what we need
+    pub(crate) fn from_file(path: &Path) -> Result<Self, SysEepromError> {
+        let file = File::open(path)?;
+        let mut buf = Vec::with_capacity(TLV_INFO_MAX_SIZE);
+        file.take(TLV_INFO_MAX_SIZE as u64).read_to_end(&mut buf)?;
+        Self::parse(&buf)
+    }
+
+    pub(crate) fn parse(data: &[u8]) -> Result<Self, SysEepromError> {
+        if data.len() < TLV_INFO_HEADER_SIZE || &data[0..8] != TLV_INFO_ID {
+            return Err(SysEepromError::InvalidHeader);
+        }
+        if data[8] != TLV_INFO_VERSION {
+            return Err(SysEepromError::UnsupportedVersion(data[8]));
+        }
+        let total_len = u16::from_be_bytes([data[9], data[10]]) as usize;
+        if TLV_INFO_HEADER_SIZE + total_len > TLV_INFO_MAX_SIZE {
+            return Err(SysEepromError::InvalidHeader);
+        }
+        let end = TLV_INFO_HEADER_SIZE + total_len;
+        if data.len() < end {
+            return Err(SysEepromError::Truncated);
+        }
+
+        // walk all TLVs: the CRC-32 TLV must be the last one
+        let mut tlvs = Vec::new();
+        let mut pos = TLV_INFO_HEADER_SIZE;
+        while pos < end {
+            if pos + 2 > end {
+                return Err(SysEepromError::Truncated);
+            }
+            let code = data[pos];
+            let len = data[pos + 1] as usize;
+            let value_pos = pos + 2;
+            if value_pos + len > end {
+                return Err(SysEepromError::Truncated);
+            }
+            if code == TLV_CODE_CRC_32 {
+                if len != 4 || value_pos + len != end {
+                    return Err(SysEepromError::MissingCRC);
+                }
+                // the CRC covers everything up to and including the type and length of the CRC TLV
+                let expected = u32::from_be_bytes([
+                    data[value_pos],
+                    data[value_pos + 1],
+                    data[value_pos + 2],
+                    data[value_pos + 3],
+                ]);
+                let calculated = crc32(&data[..value_pos]);
+                if expected != calculated {
+                    return Err(SysEepromError::CRCMismatch {
+                        expected,
+                        calculated,
+                    });
+                }
+                return Ok(Self { tlvs });
+            }
+            tlvs.push((code, data[value_pos..value_pos + len].to_vec()));
+            pos = value_pos + len;
+        }
+        Err(SysEepromError::MissingCRC)
+    }
+
+    /// the value of the first TLV with the code
+    pub(crate) fn get(&self, code: u8) -> Option<&[u8]> {
+        self.tlvs
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(_, v)| v.as_slice())
+    }
+
+    fn get_string(&self, code: u8) -> Option<String> {
+        self.get(code).map(|v| {
+            String::from_utf8_lossy(v)
+                .trim_end_matches('\0')
+                .to_string()
+        })
+    }
+
+    pub(crate) fn base_mac(&self) -> Option<[u8; 6]> {
+        self.get(TLV_CODE_MAC_BASE)
+            .and_then(|v| <[u8; 6]>::try_from(v).ok())
+    }
+
+    /// the number of MAC addresses which are reserved for the device starting from the base MAC
+    pub(crate) fn num_macs(&self) -> Option<u16> {
+        self.get(TLV_CODE_MAC_SIZE)
+            .and_then(|v| <[u8; 2]>::try_from(v).ok())
+            .map(u16::from_be_bytes)
+    }
+
+    pub(crate) fn product_name(&self) -> Option<String> {
+        self.get_string(TLV_CODE_PRODUCT_NAME)
+    }
+
+    pub(crate) fn part_number(&self) -> Option<String> {
+        self.get_string(TLV_CODE_PART_NUMBER)
+    }
+
+    pub(crate) fn serial_number(&self) -> Option<String> {
+        self.get_string(TLV_CODE_SERIAL_NUMBER)
+    }
+
+    pub(crate) fn platform_name(&self) -> Option<String> {
+        self.get_string(TLV_CODE_PLATFORM_NAME)
+    }
+}
+
+/// the CRC-32 that ONIE uses for the TlvInfo EEPROM (the same as Ethernet and zlib)
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for b in data {
+        crc ^= *b as u32;
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0xedb8_8320,
+                _ => crc >> 1,
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tlv_info(tlvs: &[(u8, &[u8])]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (code, value) in tlvs {
+            body.push(*code);
+            body.push(value.len() as u8);
+            body.extend_from_slice(value);
+        }
+        let mut ret = TLV_INFO_ID.to_vec();
+        ret.push(TLV_INFO_VERSION);
+        ret.extend_from_slice(&((body.len() + 6) as u16).to_be_bytes());
+        ret.extend_from_slice(&body);
+        ret.push(TLV_CODE_CRC_32);
+        ret.push(4);
+        let crc = crc32(&ret);
+        ret.extend_from_slice(&crc.to_be_bytes());
+        ret
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+    }
+
+    #[test]
+    fn parse_valid() {
+        let data = tlv_info(&[
+            (TLV_CODE_PRODUCT_NAME, b"S5248F-ON"),
+            (TLV_CODE_SERIAL_NUMBER, b"CN046MRJCES0085E0015"),
+            (TLV_CODE_MAC_BASE, &[0x54, 0xbf, 0x64, 0xba, 0x3d, 0xc0]),
+            (TLV_CODE_MAC_SIZE, &[0x00, 0x80]),
+        ]);
+        let eeprom = SysEeprom::parse(&data).unwrap();
+        assert_eq!(eeprom.product_name(), Some("S5248F-ON".to_string()));
+        assert_eq!(
+            eeprom.serial_number(),
+            Some("CN046MRJCES0085E0015".to_string())
+        );
+        assert_eq!(eeprom.part_number(), None);
+        assert_eq!(
+            eeprom.base_mac(),
+            Some([0x54, 0xbf, 0x64, 0xba, 0x3d, 0xc0])
+        );
+        assert_eq!(eeprom.num_macs(), Some(128));
+    }
+
+    #[test]
+    fn parse_trailing_data() {
+        // sysfs files are larger than the TlvInfo data and are usually padded with 0xff
+        let mut data = tlv_info(&[(TLV_CODE_MAC_BASE, &[0x02, 0, 0, 0, 0, 0x01])]);
+        data.extend_from_slice(&[0xff; 64]);
+        let eeprom = SysEeprom::parse(&data).unwrap();
+        assert_eq!(eeprom.base_mac(), Some([0x02, 0, 0, 0, 0, 0x01]));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let data = tlv_info(&[(TLV_CODE_MAC_BASE, &[0x02, 0, 0, 0, 0, 0x01])]);
+
+        let mut bad_crc = data.clone();
+        bad_crc[TLV_INFO_HEADER_SIZE + 2] ^= 0xff;
+        assert!(matches!(
+            SysEeprom::parse(&bad_crc),
+            Err(SysEepromError::CRCMismatch { .. })
+        ));
+
+        let mut bad_header = data.clone();
+        bad_header[0] = b'X';
+        assert!(matches!(
+            SysEeprom::parse(&bad_header),
+            Err(SysEepromError::InvalidHeader)
+        ));
+
+        let mut bad_version = data.clone();
+        bad_version[8] = 0x02;
+        assert!(matches!(
+            SysEeprom::parse(&bad_version),
+            Err(SysEepromError::UnsupportedVersion(0x02))
+        ));
+
+        assert!(matches!(
+            SysEeprom::parse(&data[..data.len() - 2]),
+            Err(SysEepromError::Truncated)
+        ));
+    }
+}
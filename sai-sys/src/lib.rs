@@ -2,6 +2,7 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
+use std::net::IpAddr;
 use std::net::Ipv4Addr;
 use std::net::Ipv6Addr;
 
@@ -73,6 +74,42 @@ impl From<sai_ip_prefix_t> for IpNet {
     }
 }
 
+impl From<IpAddr> for sai_ip_address_t {
+    fn from(value: IpAddr) -> Self {
+        match value {
+            IpAddr::V4(v) => sai_ip_address_t {
+                addr_family: _sai_ip_addr_family_t_SAI_IP_ADDR_FAMILY_IPV4,
+                addr: sai_ip_addr_t {
+                    ip4: u32::from(v).to_be(),
+                },
+            },
+            IpAddr::V6(v) => sai_ip_address_t {
+                addr_family: _sai_ip_addr_family_t_SAI_IP_ADDR_FAMILY_IPV6,
+                addr: sai_ip_addr_t { ip6: v.octets() },
+            },
+        }
+    }
+}
+
+impl From<sai_ip_address_t> for IpAddr {
+    fn from(value: sai_ip_address_t) -> Self {
+        match value.addr_family {
+            _sai_ip_addr_family_t_SAI_IP_ADDR_FAMILY_IPV4 => {
+                IpAddr::V4(From::from(unsafe { value.addr.ip4.to_be() }))
+            }
+            _sai_ip_addr_family_t_SAI_IP_ADDR_FAMILY_IPV6 => {
+                IpAddr::V6(From::from(unsafe { value.addr.ip6 }))
+            }
+            unknown_addr_family => {
+                panic!(
+                    "unknown addr_family within sai_ip_address_t: {}",
+                    unknown_addr_family
+                );
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::mem::MaybeUninit;
@@ -355,4 +392,31 @@ mod tests {
         println!("{ipnet_v4} {ipnet_v4_back}");
         println!("{ipnet_v6} {ipnet_v6_back}");
     }
+
+    #[test]
+    fn ipaddr_from_conversions() {
+        let ip_v4 = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1));
+        let ip_v6 = IpAddr::V6(Ipv6Addr::new(
+            0x2001, 0x0db8, 0x85a3, 0x0000, 0x0000, 0x8a2e, 0x0370, 0x7334,
+        ));
+        let converted_v4: sai_ip_address_t = ip_v4.into();
+        let converted_v6: sai_ip_address_t = ip_v6.into();
+        assert_eq!(
+            converted_v4.addr_family,
+            _sai_ip_addr_family_t_SAI_IP_ADDR_FAMILY_IPV4
+        );
+        unsafe {
+            assert_eq!(converted_v4.addr.ip4, 0xc0a80001_u32.to_be());
+        }
+        assert_eq!(
+            converted_v6.addr_family,
+            _sai_ip_addr_family_t_SAI_IP_ADDR_FAMILY_IPV6
+        );
+
+        // now reverse again
+        let ip_v4_back: IpAddr = converted_v4.into();
+        let ip_v6_back: IpAddr = converted_v6.into();
+        assert_eq!(ip_v4, ip_v4_back);
+        assert_eq!(ip_v6, ip_v6_back);
+    }
 }
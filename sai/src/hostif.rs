@@ -241,6 +241,26 @@ impl<'a> HostIf<'a> {
         }
     }
 
+    /// get the operational status of the host interface
+    pub fn get_oper_status(&self) -> Result<bool, Error> {
+        let hostif_api = self.sai.hostif_api().ok_or(Error::APIUnavailable)?;
+        let get_hostif_attribute = hostif_api
+            .get_hostif_attribute
+            .ok_or(Error::APIFunctionUnavailable)?;
+
+        let mut attr = sai_attribute_t {
+            id: _sai_hostif_attr_t_SAI_HOSTIF_ATTR_OPER_STATUS,
+            value: sai_attribute_value_t { booldata: false },
+        };
+
+        let st = unsafe { get_hostif_attribute(self.id, 1, &mut attr as *mut _) };
+        if st != SAI_STATUS_SUCCESS as sai_status_t {
+            return Err(Error::SAI(Status::from(st)));
+        }
+
+        Ok(unsafe { attr.value.booldata })
+    }
+
     pub fn remove(self) -> Result<(), Error> {
         let hostif_api = self.sai.hostif_api().ok_or(Error::APIUnavailable)?;
         let remove_hostif = hostif_api
@@ -1,6 +1,8 @@
 // export all modules from here
 pub mod bridge;
 pub mod hostif;
+pub mod neighbor;
+pub mod next_hop;
 pub mod port;
 pub mod route;
 pub mod router_interface;
@@ -13,7 +15,10 @@ use port::PortID;
 // we are re-exporting some things here
 pub use sai_sys::sai_ip_prefix_t;
 pub use sai_sys::sai_mac_t;
+pub use sai_sys::SAI_KEY_BOOT_TYPE;
 pub use sai_sys::SAI_KEY_INIT_CONFIG_FILE;
+pub use sai_sys::SAI_KEY_WARM_BOOT_READ_FILE;
+pub use sai_sys::SAI_KEY_WARM_BOOT_WRITE_FILE;
 
 // imports for here
 use std::ffi::{CStr, CString};
@@ -491,6 +496,10 @@ pub struct SAI {
     route_api_ptr: Option<*const sai_route_api_t>,
     virtual_router_api_backing: sai_virtual_router_api_t,
     virtual_router_api_ptr: Option<*const sai_virtual_router_api_t>,
+    neighbor_api_backing: sai_neighbor_api_t,
+    neighbor_api_ptr: Option<*const sai_neighbor_api_t>,
+    next_hop_api_backing: sai_next_hop_api_t,
+    next_hop_api_ptr: Option<*const sai_next_hop_api_t>,
 }
 
 impl SAI {
@@ -526,6 +535,14 @@ impl SAI {
         self.virtual_router_api_ptr.map(|api| unsafe { *api })
     }
 
+    fn neighbor_api(&self) -> Option<sai_neighbor_api_t> {
+        self.neighbor_api_ptr.map(|api| unsafe { *api })
+    }
+
+    fn next_hop_api(&self) -> Option<sai_next_hop_api_t> {
+        self.next_hop_api_ptr.map(|api| unsafe { *api })
+    }
+
     pub fn api_version() -> Result<u64, Status> {
         let mut version: sai_api_version_t = 0;
         unsafe {
@@ -564,6 +581,18 @@ impl SAI {
         }
     }
 
+    /// generates a dump file which includes the SAI state and vendor SDK information
+    /// NOTE: SAI must be initialized for this to work
+    pub fn dbg_generate_dump(dump_file_name: &str) -> Result<(), Status> {
+        let dump_file_name = CString::new(dump_file_name).map_err(|_| Status::InvalidParameter)?;
+        unsafe {
+            match sai_dbg_generate_dump(dump_file_name.as_ptr()) {
+                0 => Ok(()),
+                v => Err(Status::from(v)),
+            }
+        }
+    }
+
     pub fn new(profile: Vec<(CString, CString)>) -> Result<SAI, InitError> {
         let init_lock = SAI_INITIALIZED.try_lock();
         if let Ok(mut sai_initialized) = init_lock {
@@ -783,6 +812,57 @@ impl SAI {
             }
             self.virtual_router_api_ptr = Some(virtual_router_api_ptr);
         }
+
+        // neighbor API
+        // NOTE: the neighbor and next hop APIs are only needed for the route sync, so we don't want to fail the
+        // whole initialization if a SAI implementation does not provide them. Their functions return
+        // `Error::APIUnavailable` instead.
+        {
+            self.neighbor_api_backing = Default::default();
+            let neighbor_api_ptr_orig = &self.neighbor_api_backing as *const _;
+            let mut neighbor_api_ptr = &mut self.neighbor_api_backing as *mut _;
+            let neighbor_api_ptr_ptr = &mut neighbor_api_ptr as *mut *mut _;
+            let st =
+                unsafe { sai_api_query(_sai_api_t_SAI_API_NEIGHBOR, neighbor_api_ptr_ptr as _) };
+            if st != SAI_STATUS_SUCCESS as i32 {
+                log::error!(
+                    "sai_api_query(SAI_API_NEIGHBOR) failed, neighbor API will be unavailable: {:?}",
+                    Status::from(st)
+                );
+                self.neighbor_api_ptr = None;
+            } else {
+                if neighbor_api_ptr_orig != neighbor_api_ptr {
+                    log::debug!(
+                        "sai_api_query(SAI_API_NEIGHBOR) updated pointer away from our own table"
+                    );
+                }
+                self.neighbor_api_ptr = Some(neighbor_api_ptr);
+            }
+        }
+
+        // next hop API
+        {
+            self.next_hop_api_backing = Default::default();
+            let next_hop_api_ptr_orig = &self.next_hop_api_backing as *const _;
+            let mut next_hop_api_ptr = &mut self.next_hop_api_backing as *mut _;
+            let next_hop_api_ptr_ptr = &mut next_hop_api_ptr as *mut *mut _;
+            let st =
+                unsafe { sai_api_query(_sai_api_t_SAI_API_NEXT_HOP, next_hop_api_ptr_ptr as _) };
+            if st != SAI_STATUS_SUCCESS as i32 {
+                log::error!(
+                    "sai_api_query(SAI_API_NEXT_HOP) failed, next hop API will be unavailable: {:?}",
+                    Status::from(st)
+                );
+                self.next_hop_api_ptr = None;
+            } else {
+                if next_hop_api_ptr_orig != next_hop_api_ptr {
+                    log::debug!(
+                        "sai_api_query(SAI_API_NEXT_HOP) updated pointer away from our own table"
+                    );
+                }
+                self.next_hop_api_ptr = Some(next_hop_api_ptr);
+            }
+        }
         Ok(())
     }
 
@@ -860,7 +940,21 @@ impl Drop for SAI {
             *cb_write_lock = None;
         }
         *SAI_INITIALIZED.lock().unwrap() = false;
+        *SWITCH_CREATED.lock().unwrap() = false;
+    }
+}
+
+/// checks with SAI that the given object ID is of the expected object type
+/// NOTE: this is useful to validate object IDs which were not created by us in this process (e.g. after a warm boot)
+pub(crate) fn check_object_type(
+    id: sai_object_id_t,
+    object_type: sai_object_type_t,
+) -> Result<(), Error> {
+    let typ = unsafe { sai_object_type_query(id) };
+    if typ != object_type {
+        return Err(Error::SAI(Status::InvalidObjectType));
     }
+    Ok(())
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -0,0 +1,103 @@
+use std::net::IpAddr;
+
+use super::*;
+use sai_sys::*;
+
+#[derive(Clone, Copy, Debug)]
+pub enum NeighborEntryAttribute {
+    DstMacAddress(sai_mac_t),
+    PacketAction(PacketAction),
+    NoHostRoute(bool),
+}
+
+impl From<NeighborEntryAttribute> for sai_attribute_t {
+    fn from(value: NeighborEntryAttribute) -> Self {
+        match value {
+            NeighborEntryAttribute::DstMacAddress(v) => sai_attribute_t {
+                id: _sai_neighbor_entry_attr_t_SAI_NEIGHBOR_ENTRY_ATTR_DST_MAC_ADDRESS,
+                value: sai_attribute_value_t { mac: v },
+            },
+            NeighborEntryAttribute::PacketAction(v) => sai_attribute_t {
+                id: _sai_neighbor_entry_attr_t_SAI_NEIGHBOR_ENTRY_ATTR_PACKET_ACTION,
+                value: sai_attribute_value_t { s32: v.into() },
+            },
+            NeighborEntryAttribute::NoHostRoute(v) => sai_attribute_t {
+                id: _sai_neighbor_entry_attr_t_SAI_NEIGHBOR_ENTRY_ATTR_NO_HOST_ROUTE,
+                value: sai_attribute_value_t { booldata: v },
+            },
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct NeighborEntry<'a> {
+    pub(crate) entry: sai_neighbor_entry_t,
+    pub(crate) sai: &'a SAI,
+}
+
+impl From<&NeighborEntry<'_>> for IpAddr {
+    fn from(value: &NeighborEntry<'_>) -> Self {
+        value.entry.ip_address.into()
+    }
+}
+
+impl std::fmt::Debug for NeighborEntry<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "NeighborEntry(switch_id:oid:{:#x}, rif_id:oid:{:#x}, ip_address:{:?})",
+            self.entry.switch_id,
+            self.entry.rif_id,
+            IpAddr::from(self.entry.ip_address)
+        )
+    }
+}
+
+impl std::fmt::Display for NeighborEntry<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "NeighborEntry: switch_id:oid:{:#x}, rif_id:oid:{:#x}, ip_address:{}",
+            self.entry.switch_id,
+            self.entry.rif_id,
+            IpAddr::from(self.entry.ip_address)
+        )
+    }
+}
+
+impl<'a> NeighborEntry<'a> {
+    /// sets a single neighbor entry attribute
+    pub fn set_attribute(&self, attr: NeighborEntryAttribute) -> Result<(), Error> {
+        let neighbor_api = self.sai.neighbor_api().ok_or(Error::APIUnavailable)?;
+        let set_neighbor_entry_attribute = neighbor_api
+            .set_neighbor_entry_attribute
+            .ok_or(Error::APIFunctionUnavailable)?;
+
+        let attr: sai_attribute_t = attr.into();
+
+        let st = unsafe { set_neighbor_entry_attribute(&self.entry, &attr) };
+        if st != SAI_STATUS_SUCCESS as sai_status_t {
+            Err(Error::SAI(Status::from(st)))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn set_dst_mac_address(&self, mac_address: sai_mac_t) -> Result<(), Error> {
+        self.set_attribute(NeighborEntryAttribute::DstMacAddress(mac_address))
+    }
+
+    pub fn remove(self) -> Result<(), Error> {
+        let neighbor_api = self.sai.neighbor_api().ok_or(Error::APIUnavailable)?;
+        let remove_neighbor_entry = neighbor_api
+            .remove_neighbor_entry
+            .ok_or(Error::APIFunctionUnavailable)?;
+
+        let st = unsafe { remove_neighbor_entry(&self.entry) };
+        if st != SAI_STATUS_SUCCESS as i32 {
+            Err(Error::SAI(Status::from(st)))
+        } else {
+            Ok(())
+        }
+    }
+}
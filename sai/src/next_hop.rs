@@ -0,0 +1,79 @@
+use std::net::IpAddr;
+
+use crate::router_interface::RouterInterfaceID;
+
+use super::*;
+use sai_sys::*;
+
+#[derive(Clone, Copy, Debug)]
+pub enum NextHopType {
+    IP,
+}
+
+impl From<NextHopType> for i32 {
+    fn from(value: NextHopType) -> Self {
+        match value {
+            NextHopType::IP => _sai_next_hop_type_t_SAI_NEXT_HOP_TYPE_IP as i32,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum NextHopAttribute {
+    Type(NextHopType),
+    IP(IpAddr),
+    RouterInterfaceID(RouterInterfaceID),
+}
+
+impl From<NextHopAttribute> for sai_attribute_t {
+    fn from(value: NextHopAttribute) -> Self {
+        match value {
+            NextHopAttribute::Type(v) => sai_attribute_t {
+                id: _sai_next_hop_attr_t_SAI_NEXT_HOP_ATTR_TYPE,
+                value: sai_attribute_value_t { s32: v.into() },
+            },
+            NextHopAttribute::IP(v) => sai_attribute_t {
+                id: _sai_next_hop_attr_t_SAI_NEXT_HOP_ATTR_IP,
+                value: sai_attribute_value_t { ipaddr: v.into() },
+            },
+            NextHopAttribute::RouterInterfaceID(v) => sai_attribute_t {
+                id: _sai_next_hop_attr_t_SAI_NEXT_HOP_ATTR_ROUTER_INTERFACE_ID,
+                value: sai_attribute_value_t { oid: v.into() },
+            },
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct NextHop<'a> {
+    pub(crate) id: sai_object_id_t,
+    pub(crate) sai: &'a SAI,
+}
+
+impl std::fmt::Debug for NextHop<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "NextHop(oid:{:#x})", self.id)
+    }
+}
+
+impl std::fmt::Display for NextHop<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "oid:{:#x}", self.id)
+    }
+}
+
+impl<'a> NextHop<'a> {
+    pub fn remove(self) -> Result<(), Error> {
+        let next_hop_api = self.sai.next_hop_api().ok_or(Error::APIUnavailable)?;
+        let remove_next_hop = next_hop_api
+            .remove_next_hop
+            .ok_or(Error::APIFunctionUnavailable)?;
+
+        let st = unsafe { remove_next_hop(self.id) };
+        if st != SAI_STATUS_SUCCESS as i32 {
+            Err(Error::SAI(Status::from(st)))
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -63,6 +63,7 @@ pub enum BreakoutModeType {
     OneLane,
     TwoLanes,
     FourLanes,
+    EightLanes,
     Unknown(i32),
 }
 
@@ -84,6 +85,9 @@ impl From<BreakoutModeType> for i32 {
             BreakoutModeType::FourLanes => {
                 _sai_port_breakout_mode_type_t_SAI_PORT_BREAKOUT_MODE_TYPE_4_LANE as i32
             }
+            BreakoutModeType::EightLanes => {
+                _sai_port_breakout_mode_type_extensions_t_SAI_PORT_BREAKOUT_MODE_TYPE_8_LANE as i32
+            }
             BreakoutModeType::Unknown(v) => v,
         }
     }
@@ -110,6 +114,12 @@ impl From<i32> for BreakoutModeType {
             {
                 BreakoutModeType::FourLanes
             }
+            x if x
+                == sai_sys::_sai_port_breakout_mode_type_extensions_t_SAI_PORT_BREAKOUT_MODE_TYPE_8_LANE
+                    as i32 =>
+            {
+                BreakoutModeType::EightLanes
+            }
             x => BreakoutModeType::Unknown(x),
         }
     }
@@ -195,6 +205,7 @@ typedef enum _sai_port_media_type_t
     SAI_PORT_MEDIA_TYPE_BACKPLANE,
 } sai_port_media_type_t;
 */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum MediaType {
     NotPresent,
     Unknown,
@@ -251,6 +262,7 @@ typedef enum _sai_port_fec_mode_t
     SAI_PORT_FEC_MODE_FC,
 } sai_port_fec_mode_t;
 */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum FECMode {
     None,
     RS,
@@ -278,6 +290,438 @@ impl From<i32> for FECMode {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InterfaceType {
+    None,
+    CR,
+    CR2,
+    CR4,
+    SR,
+    SR2,
+    SR4,
+    LR,
+    LR4,
+    KR,
+    KR4,
+    CAUI,
+    GMII,
+    SFI,
+    XLAUI,
+    KR2,
+    CAUI4,
+    XAUI,
+    XFI,
+    XGMII,
+    CR8,
+    KR8,
+    SR8,
+    LR8,
+    Unknown(i32),
+}
+
+impl From<InterfaceType> for i32 {
+    fn from(value: InterfaceType) -> Self {
+        match value {
+            InterfaceType::None => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_NONE as i32,
+            InterfaceType::CR => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_CR as i32,
+            InterfaceType::CR2 => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_CR2 as i32,
+            InterfaceType::CR4 => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_CR4 as i32,
+            InterfaceType::SR => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_SR as i32,
+            InterfaceType::SR2 => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_SR2 as i32,
+            InterfaceType::SR4 => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_SR4 as i32,
+            InterfaceType::LR => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_LR as i32,
+            InterfaceType::LR4 => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_LR4 as i32,
+            InterfaceType::KR => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_KR as i32,
+            InterfaceType::KR4 => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_KR4 as i32,
+            InterfaceType::CAUI => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_CAUI as i32,
+            InterfaceType::GMII => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_GMII as i32,
+            InterfaceType::SFI => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_SFI as i32,
+            InterfaceType::XLAUI => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_XLAUI as i32,
+            InterfaceType::KR2 => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_KR2 as i32,
+            InterfaceType::CAUI4 => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_CAUI4 as i32,
+            InterfaceType::XAUI => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_XAUI as i32,
+            InterfaceType::XFI => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_XFI as i32,
+            InterfaceType::XGMII => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_XGMII as i32,
+            InterfaceType::CR8 => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_CR8 as i32,
+            InterfaceType::KR8 => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_KR8 as i32,
+            InterfaceType::SR8 => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_SR8 as i32,
+            InterfaceType::LR8 => _sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_LR8 as i32,
+            InterfaceType::Unknown(v) => v,
+        }
+    }
+}
+
+impl From<i32> for InterfaceType {
+    fn from(value: i32) -> Self {
+        match value {
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_NONE as i32 => {
+                InterfaceType::None
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_CR as i32 => {
+                InterfaceType::CR
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_CR2 as i32 => {
+                InterfaceType::CR2
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_CR4 as i32 => {
+                InterfaceType::CR4
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_SR as i32 => {
+                InterfaceType::SR
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_SR2 as i32 => {
+                InterfaceType::SR2
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_SR4 as i32 => {
+                InterfaceType::SR4
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_LR as i32 => {
+                InterfaceType::LR
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_LR4 as i32 => {
+                InterfaceType::LR4
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_KR as i32 => {
+                InterfaceType::KR
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_KR4 as i32 => {
+                InterfaceType::KR4
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_CAUI as i32 => {
+                InterfaceType::CAUI
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_GMII as i32 => {
+                InterfaceType::GMII
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_SFI as i32 => {
+                InterfaceType::SFI
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_XLAUI as i32 => {
+                InterfaceType::XLAUI
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_KR2 as i32 => {
+                InterfaceType::KR2
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_CAUI4 as i32 => {
+                InterfaceType::CAUI4
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_XAUI as i32 => {
+                InterfaceType::XAUI
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_XFI as i32 => {
+                InterfaceType::XFI
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_XGMII as i32 => {
+                InterfaceType::XGMII
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_CR8 as i32 => {
+                InterfaceType::CR8
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_KR8 as i32 => {
+                InterfaceType::KR8
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_SR8 as i32 => {
+                InterfaceType::SR8
+            }
+            x if x == sai_sys::_sai_port_interface_type_t_SAI_PORT_INTERFACE_TYPE_LR8 as i32 => {
+                InterfaceType::LR8
+            }
+            x => InterfaceType::Unknown(x),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum PortAttribute {
+    HwLaneList(Vec<u32>),
+    Speed(u32),
+    AdminState(bool),
+    MTU(u32),
+    AutoNegMode(bool),
+    AdvertisedAutoNegMode(bool),
+    AutoNegConfigMode(AutoNegConfigMode),
+    MediaType(MediaType),
+    FECMode(FECMode),
+    InterfaceType(InterfaceType),
+    LinkTraining(bool),
+}
+
+impl PortAttribute {
+    pub(crate) fn to_sai_attribute_t(
+        &self,
+        hw_lane_list_backing: &mut Vec<u32>,
+    ) -> sai_attribute_t {
+        match self {
+            PortAttribute::HwLaneList(v) => {
+                hw_lane_list_backing.clear();
+                hw_lane_list_backing.extend(v.iter());
+                sai_attribute_t {
+                    id: _sai_port_attr_t_SAI_PORT_ATTR_HW_LANE_LIST,
+                    value: sai_attribute_value_t {
+                        u32list: sai_u32_list_t {
+                            count: hw_lane_list_backing.len() as u32,
+                            list: hw_lane_list_backing.as_mut_ptr(),
+                        },
+                    },
+                }
+            }
+            PortAttribute::Speed(v) => sai_attribute_t {
+                id: _sai_port_attr_t_SAI_PORT_ATTR_SPEED,
+                value: sai_attribute_value_t { u32_: *v },
+            },
+            PortAttribute::AdminState(v) => sai_attribute_t {
+                id: _sai_port_attr_t_SAI_PORT_ATTR_ADMIN_STATE,
+                value: sai_attribute_value_t { booldata: *v },
+            },
+            PortAttribute::MTU(v) => sai_attribute_t {
+                id: _sai_port_attr_t_SAI_PORT_ATTR_MTU,
+                value: sai_attribute_value_t { u32_: *v },
+            },
+            PortAttribute::AutoNegMode(v) => sai_attribute_t {
+                id: _sai_port_attr_t_SAI_PORT_ATTR_AUTO_NEG_MODE,
+                value: sai_attribute_value_t { booldata: *v },
+            },
+            PortAttribute::AdvertisedAutoNegMode(v) => sai_attribute_t {
+                id: _sai_port_attr_t_SAI_PORT_ATTR_ADVERTISED_AUTO_NEG_MODE,
+                value: sai_attribute_value_t { booldata: *v },
+            },
+            PortAttribute::AutoNegConfigMode(v) => sai_attribute_t {
+                id: _sai_port_attr_t_SAI_PORT_ATTR_AUTO_NEG_CONFIG_MODE,
+                value: sai_attribute_value_t { s32: (*v).into() },
+            },
+            PortAttribute::MediaType(v) => sai_attribute_t {
+                id: _sai_port_attr_t_SAI_PORT_ATTR_MEDIA_TYPE,
+                value: sai_attribute_value_t { s32: (*v).into() },
+            },
+            PortAttribute::FECMode(v) => sai_attribute_t {
+                id: _sai_port_attr_t_SAI_PORT_ATTR_FEC_MODE,
+                value: sai_attribute_value_t { s32: (*v).into() },
+            },
+            PortAttribute::InterfaceType(v) => sai_attribute_t {
+                id: _sai_port_attr_t_SAI_PORT_ATTR_INTERFACE_TYPE,
+                value: sai_attribute_value_t { s32: (*v).into() },
+            },
+            PortAttribute::LinkTraining(v) => sai_attribute_t {
+                id: _sai_port_attr_t_SAI_PORT_ATTR_LINK_TRAINING_ENABLE,
+                value: sai_attribute_value_t { booldata: *v },
+            },
+        }
+    }
+}
+
+/// selects which attribute to read with `Port::get_attribute`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PortAttributeType {
+    HwLaneList,
+    Speed,
+    AdminState,
+    MTU,
+    AutoNegMode,
+    AdvertisedAutoNegMode,
+    AutoNegConfigMode,
+    MediaType,
+    FECMode,
+    InterfaceType,
+    LinkTraining,
+}
+
+impl From<&PortAttribute> for PortAttributeType {
+    fn from(value: &PortAttribute) -> Self {
+        match value {
+            PortAttribute::HwLaneList(_) => PortAttributeType::HwLaneList,
+            PortAttribute::Speed(_) => PortAttributeType::Speed,
+            PortAttribute::AdminState(_) => PortAttributeType::AdminState,
+            PortAttribute::MTU(_) => PortAttributeType::MTU,
+            PortAttribute::AutoNegMode(_) => PortAttributeType::AutoNegMode,
+            PortAttribute::AdvertisedAutoNegMode(_) => PortAttributeType::AdvertisedAutoNegMode,
+            PortAttribute::AutoNegConfigMode(_) => PortAttributeType::AutoNegConfigMode,
+            PortAttribute::MediaType(_) => PortAttributeType::MediaType,
+            PortAttribute::FECMode(_) => PortAttributeType::FECMode,
+            PortAttribute::InterfaceType(_) => PortAttributeType::InterfaceType,
+            PortAttribute::LinkTraining(_) => PortAttributeType::LinkTraining,
+        }
+    }
+}
+
+/// serdes attributes of a port
+/// NOTE: all list values are per lane, and all of them are create-only
+#[derive(Clone, Debug)]
+pub enum PortSerdesAttribute {
+    PortID(PortID),
+    Preemphasis(Vec<i32>),
+    IDriver(Vec<i32>),
+    IPreDriver(Vec<i32>),
+    TxFirPre1(Vec<i32>),
+    TxFirPre2(Vec<i32>),
+    TxFirPre3(Vec<i32>),
+    TxFirMain(Vec<i32>),
+    TxFirPost1(Vec<i32>),
+    TxFirPost2(Vec<i32>),
+    TxFirPost3(Vec<i32>),
+    TxFirAttn(Vec<i32>),
+}
+
+impl PortSerdesAttribute {
+    pub(crate) fn to_sai_attribute_t(&self, backing: &mut Vec<Vec<i32>>) -> sai_attribute_t {
+        // NOTE: the heap buffer of a pushed vector does not move when `backing` grows
+        let mut s32list = |v: &Vec<i32>| {
+            backing.push(v.clone());
+            let list = backing.last_mut().unwrap();
+            sai_s32_list_t {
+                count: list.len() as u32,
+                list: list.as_mut_ptr(),
+            }
+        };
+        match self {
+            PortSerdesAttribute::PortID(v) => sai_attribute_t {
+                id: _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_PORT_ID,
+                value: sai_attribute_value_t { oid: (*v).into() },
+            },
+            PortSerdesAttribute::Preemphasis(v) => sai_attribute_t {
+                id: _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_PREEMPHASIS,
+                value: sai_attribute_value_t {
+                    s32list: s32list(v),
+                },
+            },
+            PortSerdesAttribute::IDriver(v) => sai_attribute_t {
+                id: _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_IDRIVER,
+                value: sai_attribute_value_t {
+                    s32list: s32list(v),
+                },
+            },
+            PortSerdesAttribute::IPreDriver(v) => sai_attribute_t {
+                id: _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_IPREDRIVER,
+                value: sai_attribute_value_t {
+                    s32list: s32list(v),
+                },
+            },
+            PortSerdesAttribute::TxFirPre1(v) => sai_attribute_t {
+                id: _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_TX_FIR_PRE1,
+                value: sai_attribute_value_t {
+                    s32list: s32list(v),
+                },
+            },
+            PortSerdesAttribute::TxFirPre2(v) => sai_attribute_t {
+                id: _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_TX_FIR_PRE2,
+                value: sai_attribute_value_t {
+                    s32list: s32list(v),
+                },
+            },
+            PortSerdesAttribute::TxFirPre3(v) => sai_attribute_t {
+                id: _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_TX_FIR_PRE3,
+                value: sai_attribute_value_t {
+                    s32list: s32list(v),
+                },
+            },
+            PortSerdesAttribute::TxFirMain(v) => sai_attribute_t {
+                id: _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_TX_FIR_MAIN,
+                value: sai_attribute_value_t {
+                    s32list: s32list(v),
+                },
+            },
+            PortSerdesAttribute::TxFirPost1(v) => sai_attribute_t {
+                id: _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_TX_FIR_POST1,
+                value: sai_attribute_value_t {
+                    s32list: s32list(v),
+                },
+            },
+            PortSerdesAttribute::TxFirPost2(v) => sai_attribute_t {
+                id: _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_TX_FIR_POST2,
+                value: sai_attribute_value_t {
+                    s32list: s32list(v),
+                },
+            },
+            PortSerdesAttribute::TxFirPost3(v) => sai_attribute_t {
+                id: _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_TX_FIR_POST3,
+                value: sai_attribute_value_t {
+                    s32list: s32list(v),
+                },
+            },
+            PortSerdesAttribute::TxFirAttn(v) => sai_attribute_t {
+                id: _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_TX_FIR_ATTN,
+                value: sai_attribute_value_t {
+                    s32list: s32list(v),
+                },
+            },
+        }
+    }
+}
+
+/// selects which attribute to read with `PortSerdes::get_attribute`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PortSerdesAttributeType {
+    PortID,
+    Preemphasis,
+    IDriver,
+    IPreDriver,
+    TxFirPre1,
+    TxFirPre2,
+    TxFirPre3,
+    TxFirMain,
+    TxFirPost1,
+    TxFirPost2,
+    TxFirPost3,
+    TxFirAttn,
+}
+
+impl PortSerdesAttributeType {
+    fn id(&self) -> sai_attr_id_t {
+        match self {
+            PortSerdesAttributeType::PortID => _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_PORT_ID,
+            PortSerdesAttributeType::Preemphasis => {
+                _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_PREEMPHASIS
+            }
+            PortSerdesAttributeType::IDriver => {
+                _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_IDRIVER
+            }
+            PortSerdesAttributeType::IPreDriver => {
+                _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_IPREDRIVER
+            }
+            PortSerdesAttributeType::TxFirPre1 => {
+                _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_TX_FIR_PRE1
+            }
+            PortSerdesAttributeType::TxFirPre2 => {
+                _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_TX_FIR_PRE2
+            }
+            PortSerdesAttributeType::TxFirPre3 => {
+                _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_TX_FIR_PRE3
+            }
+            PortSerdesAttributeType::TxFirMain => {
+                _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_TX_FIR_MAIN
+            }
+            PortSerdesAttributeType::TxFirPost1 => {
+                _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_TX_FIR_POST1
+            }
+            PortSerdesAttributeType::TxFirPost2 => {
+                _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_TX_FIR_POST2
+            }
+            PortSerdesAttributeType::TxFirPost3 => {
+                _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_TX_FIR_POST3
+            }
+            PortSerdesAttributeType::TxFirAttn => {
+                _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_TX_FIR_ATTN
+            }
+        }
+    }
+
+    fn to_attribute(&self, v: Vec<i32>) -> PortSerdesAttribute {
+        match self {
+            PortSerdesAttributeType::PortID => unreachable!(),
+            PortSerdesAttributeType::Preemphasis => PortSerdesAttribute::Preemphasis(v),
+            PortSerdesAttributeType::IDriver => PortSerdesAttribute::IDriver(v),
+            PortSerdesAttributeType::IPreDriver => PortSerdesAttribute::IPreDriver(v),
+            PortSerdesAttributeType::TxFirPre1 => PortSerdesAttribute::TxFirPre1(v),
+            PortSerdesAttributeType::TxFirPre2 => PortSerdesAttribute::TxFirPre2(v),
+            PortSerdesAttributeType::TxFirPre3 => PortSerdesAttribute::TxFirPre3(v),
+            PortSerdesAttributeType::TxFirMain => PortSerdesAttribute::TxFirMain(v),
+            PortSerdesAttributeType::TxFirPost1 => PortSerdesAttribute::TxFirPost1(v),
+            PortSerdesAttributeType::TxFirPost2 => PortSerdesAttribute::TxFirPost2(v),
+            PortSerdesAttributeType::TxFirPost3 => PortSerdesAttribute::TxFirPost3(v),
+            PortSerdesAttributeType::TxFirAttn => PortSerdesAttribute::TxFirAttn(v),
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct PortID {
     pub(crate) id: sai_object_id_t,
@@ -460,6 +904,26 @@ impl<'a> Port<'a> {
         Ok(unsafe { attr.value.u32_ })
     }
 
+    /// get the configured FEC mode of the port
+    pub fn get_fec_mode(&self) -> Result<FECMode, Error> {
+        let port_api = self.sai.port_api().ok_or(Error::APIUnavailable)?;
+        let get_port_attribute = port_api
+            .get_port_attribute
+            .ok_or(Error::APIFunctionUnavailable)?;
+
+        let mut attr = sai_attribute_t {
+            id: _sai_port_attr_t_SAI_PORT_ATTR_FEC_MODE,
+            value: sai_attribute_value_t { s32: 0 },
+        };
+
+        let st = unsafe { get_port_attribute(self.id, 1, &mut attr as *mut _) };
+        if st != SAI_STATUS_SUCCESS as sai_status_t {
+            return Err(Error::SAI(Status::from(st)));
+        }
+
+        Ok(FECMode::from(unsafe { attr.value.s32 }))
+    }
+
     /// returns true if auto neg mode is supported by this port
     pub fn get_supported_auto_neg_mode(&self) -> Result<bool, Error> {
         let port_api = self.sai.port_api().ok_or(Error::APIUnavailable)?;
@@ -665,16 +1129,15 @@ impl<'a> Port<'a> {
         Ok(ret)
     }
 
-    pub fn set_speed(&self, speed: u32) -> Result<(), Error> {
+    /// sets a single port attribute
+    pub fn set_attribute(&self, attr: PortAttribute) -> Result<(), Error> {
         let port_api = self.sai.port_api().ok_or(Error::APIUnavailable)?;
         let set_port_attribute = port_api
             .set_port_attribute
             .ok_or(Error::APIFunctionUnavailable)?;
 
-        let attr = sai_attribute_t {
-            id: _sai_port_attr_t_SAI_PORT_ATTR_SPEED,
-            value: sai_attribute_value_t { u32_: speed },
-        };
+        let mut hw_lane_list_backing: Vec<u32> = Vec::new();
+        let attr = attr.to_sai_attribute_t(&mut hw_lane_list_backing);
 
         let st = unsafe { set_port_attribute(self.id, &attr) };
         if st != SAI_STATUS_SUCCESS as sai_status_t {
@@ -684,150 +1147,142 @@ impl<'a> Port<'a> {
         }
     }
 
-    /// enables/disables advertisement of auto neg mode for this port
-    /// NOTE: this is different from enabling/disabling auto neg mode for the port (this is just advertising it)!
-    pub fn set_advertised_auto_neg_mode(&self, enable: bool) -> Result<(), Error> {
+    /// gets a single port attribute
+    pub fn get_attribute(&self, attr_type: PortAttributeType) -> Result<PortAttribute, Error> {
+        // lists need a buffer, so we simply defer to the dedicated getter
+        if attr_type == PortAttributeType::HwLaneList {
+            return Ok(PortAttribute::HwLaneList(self.get_hw_lanes()?));
+        }
+
         let port_api = self.sai.port_api().ok_or(Error::APIUnavailable)?;
-        let set_port_attribute = port_api
-            .set_port_attribute
+        let get_port_attribute = port_api
+            .get_port_attribute
             .ok_or(Error::APIFunctionUnavailable)?;
 
-        let attr = sai_attribute_t {
-            id: _sai_port_attr_t_SAI_PORT_ATTR_ADVERTISED_AUTO_NEG_MODE,
-            value: sai_attribute_value_t { booldata: enable },
+        let id = match attr_type {
+            PortAttributeType::HwLaneList => _sai_port_attr_t_SAI_PORT_ATTR_HW_LANE_LIST,
+            PortAttributeType::Speed => _sai_port_attr_t_SAI_PORT_ATTR_SPEED,
+            PortAttributeType::AdminState => _sai_port_attr_t_SAI_PORT_ATTR_ADMIN_STATE,
+            PortAttributeType::MTU => _sai_port_attr_t_SAI_PORT_ATTR_MTU,
+            PortAttributeType::AutoNegMode => _sai_port_attr_t_SAI_PORT_ATTR_AUTO_NEG_MODE,
+            PortAttributeType::AdvertisedAutoNegMode => {
+                _sai_port_attr_t_SAI_PORT_ATTR_ADVERTISED_AUTO_NEG_MODE
+            }
+            PortAttributeType::AutoNegConfigMode => {
+                _sai_port_attr_t_SAI_PORT_ATTR_AUTO_NEG_CONFIG_MODE
+            }
+            PortAttributeType::MediaType => _sai_port_attr_t_SAI_PORT_ATTR_MEDIA_TYPE,
+            PortAttributeType::FECMode => _sai_port_attr_t_SAI_PORT_ATTR_FEC_MODE,
+            PortAttributeType::InterfaceType => _sai_port_attr_t_SAI_PORT_ATTR_INTERFACE_TYPE,
+            PortAttributeType::LinkTraining => _sai_port_attr_t_SAI_PORT_ATTR_LINK_TRAINING_ENABLE,
         };
 
-        let st = unsafe { set_port_attribute(self.id, &attr) };
+        // NOTE: the union is large enough for all of the scalar types below
+        let mut attr = sai_attribute_t {
+            id,
+            value: sai_attribute_value_t { u64_: 0 },
+        };
+
+        let st = unsafe { get_port_attribute(self.id, 1, &mut attr as *mut _) };
         if st != SAI_STATUS_SUCCESS as sai_status_t {
-            Err(Error::SAI(Status::from(st)))
-        } else {
-            Ok(())
+            return Err(Error::SAI(Status::from(st)));
         }
+
+        let ret = unsafe {
+            match attr_type {
+                PortAttributeType::HwLaneList => unreachable!(),
+                PortAttributeType::Speed => PortAttribute::Speed(attr.value.u32_),
+                PortAttributeType::AdminState => PortAttribute::AdminState(attr.value.booldata),
+                PortAttributeType::MTU => PortAttribute::MTU(attr.value.u32_),
+                PortAttributeType::AutoNegMode => PortAttribute::AutoNegMode(attr.value.booldata),
+                PortAttributeType::AdvertisedAutoNegMode => {
+                    PortAttribute::AdvertisedAutoNegMode(attr.value.booldata)
+                }
+                PortAttributeType::AutoNegConfigMode => {
+                    PortAttribute::AutoNegConfigMode(attr.value.s32.into())
+                }
+                PortAttributeType::MediaType => PortAttribute::MediaType(attr.value.s32.into()),
+                PortAttributeType::FECMode => PortAttribute::FECMode(attr.value.s32.into()),
+                PortAttributeType::InterfaceType => {
+                    PortAttribute::InterfaceType(attr.value.s32.into())
+                }
+                PortAttributeType::LinkTraining => PortAttribute::LinkTraining(attr.value.booldata),
+            }
+        };
+        Ok(ret)
+    }
+
+    pub fn set_speed(&self, speed: u32) -> Result<(), Error> {
+        self.set_attribute(PortAttribute::Speed(speed))
+    }
+
+    /// enables/disables advertisement of auto neg mode for this port
+    /// NOTE: this is different from enabling/disabling auto neg mode for the port (this is just advertising it)!
+    pub fn set_advertised_auto_neg_mode(&self, enable: bool) -> Result<(), Error> {
+        self.set_attribute(PortAttribute::AdvertisedAutoNegMode(enable))
     }
 
     /// enables/disables auto neg mode for this port
     /// NOTE: this is different from auto neg mode advertisement!
     pub fn set_auto_neg_mode(&self, enable: bool) -> Result<(), Error> {
-        let port_api = self.sai.port_api().ok_or(Error::APIUnavailable)?;
-        let set_port_attribute = port_api
-            .set_port_attribute
-            .ok_or(Error::APIFunctionUnavailable)?;
-
-        let attr = sai_attribute_t {
-            id: _sai_port_attr_t_SAI_PORT_ATTR_AUTO_NEG_MODE,
-            value: sai_attribute_value_t { booldata: enable },
-        };
-
-        let st = unsafe { set_port_attribute(self.id, &attr) };
-        if st != SAI_STATUS_SUCCESS as sai_status_t {
-            Err(Error::SAI(Status::from(st)))
-        } else {
-            Ok(())
-        }
+        self.set_attribute(PortAttribute::AutoNegMode(enable))
     }
 
     /// enables/disables auto neg mode for this port
     /// NOTE: this is different from auto neg mode advertisement!
     pub fn set_auto_neg_config_mode(&self, config_mode: AutoNegConfigMode) -> Result<(), Error> {
-        let port_api = self.sai.port_api().ok_or(Error::APIUnavailable)?;
-        let set_port_attribute = port_api
-            .set_port_attribute
-            .ok_or(Error::APIFunctionUnavailable)?;
-
-        let attr = sai_attribute_t {
-            id: _sai_port_attr_t_SAI_PORT_ATTR_AUTO_NEG_CONFIG_MODE,
-            value: sai_attribute_value_t {
-                s32: config_mode.into(),
-            },
-        };
-
-        let st = unsafe { set_port_attribute(self.id, &attr) };
-        if st != SAI_STATUS_SUCCESS as sai_status_t {
-            Err(Error::SAI(Status::from(st)))
-        } else {
-            Ok(())
-        }
+        self.set_attribute(PortAttribute::AutoNegConfigMode(config_mode))
     }
 
     pub fn set_admin_state(&self, admin_state: bool) -> Result<(), Error> {
-        let port_api = self.sai.port_api().ok_or(Error::APIUnavailable)?;
-        let set_port_attribute = port_api
-            .set_port_attribute
-            .ok_or(Error::APIFunctionUnavailable)?;
-
-        let attr = sai_attribute_t {
-            id: _sai_port_attr_t_SAI_PORT_ATTR_ADMIN_STATE,
-            value: sai_attribute_value_t {
-                booldata: admin_state,
-            },
-        };
-
-        let st = unsafe { set_port_attribute(self.id, &attr) };
-        if st != SAI_STATUS_SUCCESS as sai_status_t {
-            Err(Error::SAI(Status::from(st)))
-        } else {
-            Ok(())
-        }
+        self.set_attribute(PortAttribute::AdminState(admin_state))
     }
 
     pub fn set_mtu(&self, mtu: u32) -> Result<(), Error> {
-        let port_api = self.sai.port_api().ok_or(Error::APIUnavailable)?;
-        let set_port_attribute = port_api
-            .set_port_attribute
-            .ok_or(Error::APIFunctionUnavailable)?;
-
-        let attr = sai_attribute_t {
-            id: _sai_port_attr_t_SAI_PORT_ATTR_MTU,
-            value: sai_attribute_value_t { u32_: mtu },
-        };
-
-        let st = unsafe { set_port_attribute(self.id, &attr) };
-        if st != SAI_STATUS_SUCCESS as sai_status_t {
-            Err(Error::SAI(Status::from(st)))
-        } else {
-            Ok(())
-        }
+        self.set_attribute(PortAttribute::MTU(mtu))
     }
 
     pub fn set_media_type(&self, media_type: MediaType) -> Result<(), Error> {
-        let port_api = self.sai.port_api().ok_or(Error::APIUnavailable)?;
-        let set_port_attribute = port_api
-            .set_port_attribute
-            .ok_or(Error::APIFunctionUnavailable)?;
+        self.set_attribute(PortAttribute::MediaType(media_type))
+    }
 
-        let attr = sai_attribute_t {
-            id: _sai_port_attr_t_SAI_PORT_ATTR_MEDIA_TYPE,
-            value: sai_attribute_value_t {
-                s32: media_type.into(),
-            },
-        };
+    pub fn set_fec_mode(&self, fec_mode: FECMode) -> Result<(), Error> {
+        self.set_attribute(PortAttribute::FECMode(fec_mode))
+    }
 
-        let st = unsafe { set_port_attribute(self.id, &attr) };
-        if st != SAI_STATUS_SUCCESS as sai_status_t {
-            Err(Error::SAI(Status::from(st)))
-        } else {
-            Ok(())
-        }
+    pub fn set_interface_type(&self, interface_type: InterfaceType) -> Result<(), Error> {
+        self.set_attribute(PortAttribute::InterfaceType(interface_type))
     }
 
-    pub fn set_fec_mode(&self, fec_mode: FECMode) -> Result<(), Error> {
+    pub fn set_link_training(&self, enable: bool) -> Result<(), Error> {
+        self.set_attribute(PortAttribute::LinkTraining(enable))
+    }
+
+    /// returns the serdes object of the port if one was created for it
+    pub fn get_port_serdes(&self) -> Result<Option<PortSerdes<'a>>, Error> {
         let port_api = self.sai.port_api().ok_or(Error::APIUnavailable)?;
-        let set_port_attribute = port_api
-            .set_port_attribute
+        let get_port_attribute = port_api
+            .get_port_attribute
             .ok_or(Error::APIFunctionUnavailable)?;
 
-        let attr = sai_attribute_t {
-            id: _sai_port_attr_t_SAI_PORT_ATTR_FEC_MODE,
-            value: sai_attribute_value_t {
-                s32: fec_mode.into(),
-            },
+        let mut attr = sai_attribute_t {
+            id: _sai_port_attr_t_SAI_PORT_ATTR_PORT_SERDES_ID,
+            value: sai_attribute_value_t { oid: 0 },
         };
 
-        let st = unsafe { set_port_attribute(self.id, &attr) };
+        let st = unsafe { get_port_attribute(self.id, 1, &mut attr as *mut _) };
         if st != SAI_STATUS_SUCCESS as sai_status_t {
-            Err(Error::SAI(Status::from(st)))
+            return Err(Error::SAI(Status::from(st)));
+        }
+
+        let oid = unsafe { attr.value.oid };
+        if oid == SAI_NULL_OBJECT_ID as sai_object_id_t {
+            Ok(None)
         } else {
-            Ok(())
+            Ok(Some(PortSerdes {
+                id: oid,
+                sai: self.sai,
+            }))
         }
     }
 
@@ -853,7 +1308,6 @@ impl ObjectID<PortID> for Port<'_> {
 #[derive(Clone)]
 pub struct PortSerdes<'a> {
     pub(crate) id: sai_object_id_t,
-    #[allow(dead_code)]
     pub(crate) sai: &'a SAI,
 }
 
@@ -868,3 +1322,77 @@ impl std::fmt::Display for PortSerdes<'_> {
         write!(f, "oid:{:#x}", self.id)
     }
 }
+
+impl<'a> PortSerdes<'a> {
+    /// returns the port which this serdes object belongs to
+    pub fn get_port_id(&self) -> Result<PortID, Error> {
+        let port_api = self.sai.port_api().ok_or(Error::APIUnavailable)?;
+        let get_port_serdes_attribute = port_api
+            .get_port_serdes_attribute
+            .ok_or(Error::APIFunctionUnavailable)?;
+
+        let mut attr = sai_attribute_t {
+            id: _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_PORT_ID,
+            value: sai_attribute_value_t { oid: 0 },
+        };
+
+        let st = unsafe { get_port_serdes_attribute(self.id, 1, &mut attr as *mut _) };
+        if st != SAI_STATUS_SUCCESS as sai_status_t {
+            return Err(Error::SAI(Status::from(st)));
+        }
+
+        Ok(PortID {
+            id: unsafe { attr.value.oid },
+        })
+    }
+
+    /// gets a single serdes attribute
+    pub fn get_attribute(
+        &self,
+        attr_type: PortSerdesAttributeType,
+    ) -> Result<PortSerdesAttribute, Error> {
+        if attr_type == PortSerdesAttributeType::PortID {
+            return Ok(PortSerdesAttribute::PortID(self.get_port_id()?));
+        }
+
+        let port_api = self.sai.port_api().ok_or(Error::APIUnavailable)?;
+        let get_port_serdes_attribute = port_api
+            .get_port_serdes_attribute
+            .ok_or(Error::APIFunctionUnavailable)?;
+
+        // there are at most 8 lanes per port, so this is plenty
+        let mut values: Vec<i32> = vec![0i32; 16];
+        let mut attr = sai_attribute_t {
+            id: attr_type.id(),
+            value: sai_attribute_value_t {
+                s32list: sai_s32_list_t {
+                    count: values.len() as u32,
+                    list: values.as_mut_ptr(),
+                },
+            },
+        };
+
+        let st = unsafe { get_port_serdes_attribute(self.id, 1, &mut attr as *mut _) };
+        if st != SAI_STATUS_SUCCESS as sai_status_t {
+            return Err(Error::SAI(Status::from(st)));
+        }
+
+        let count = unsafe { attr.value.s32list.count };
+        values.truncate(count as usize);
+        Ok(attr_type.to_attribute(values))
+    }
+
+    pub fn remove(self) -> Result<(), Error> {
+        let port_api = self.sai.port_api().ok_or(Error::APIUnavailable)?;
+        let remove_port_serdes = port_api
+            .remove_port_serdes
+            .ok_or(Error::APIFunctionUnavailable)?;
+
+        let st = unsafe { remove_port_serdes(self.id) };
+        if st != SAI_STATUS_SUCCESS as sai_status_t {
+            Err(Error::SAI(Status::from(st)))
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::next_hop::NextHop;
 use crate::router_interface::{RouterInterface, RouterInterfaceID};
 
 use super::*;
@@ -54,6 +55,18 @@ impl From<RouterInterface<'_>> for NextHopID {
     }
 }
 
+impl From<NextHop<'_>> for NextHopID {
+    fn from(value: NextHop<'_>) -> Self {
+        Self { id: value.id }
+    }
+}
+
+impl From<&NextHop<'_>> for NextHopID {
+    fn from(value: &NextHop<'_>) -> Self {
+        Self { id: value.id }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum AddrFamily {
     IPv4,
@@ -176,6 +189,23 @@ impl std::fmt::Display for RouteEntry<'_> {
 }
 
 impl<'a> RouteEntry<'a> {
+    /// sets a single route entry attribute
+    pub fn set_attribute(&self, attr: RouteEntryAttribute) -> Result<(), Error> {
+        let route_api = self.sai.route_api().ok_or(Error::APIUnavailable)?;
+        let set_route_entry_attribute = route_api
+            .set_route_entry_attribute
+            .ok_or(Error::APIFunctionUnavailable)?;
+
+        let attr: sai_attribute_t = attr.into();
+
+        let st = unsafe { set_route_entry_attribute(&self.entry, &attr) };
+        if st != SAI_STATUS_SUCCESS as sai_status_t {
+            Err(Error::SAI(Status::from(st)))
+        } else {
+            Ok(())
+        }
+    }
+
     pub fn remove(self) -> Result<(), Error> {
         let route_api = self.sai.route_api().ok_or(Error::APIUnavailable)?;
         let remove_route_entry = route_api
@@ -243,6 +243,57 @@ impl std::fmt::Display for RouterInterface<'_> {
 }
 
 impl<'a> RouterInterface<'a> {
+    /// sets a single router interface attribute
+    pub fn set_attribute(&self, attr: RouterInterfaceAttribute) -> Result<(), Error> {
+        let router_interface_api = self
+            .sai
+            .router_interface_api()
+            .ok_or(Error::APIUnavailable)?;
+        let set_router_interface_attribute = router_interface_api
+            .set_router_interface_attribute
+            .ok_or(Error::APIFunctionUnavailable)?;
+
+        let attr: sai_attribute_t = attr.into();
+
+        let st = unsafe { set_router_interface_attribute(self.id, &attr) };
+        if st != SAI_STATUS_SUCCESS as sai_status_t {
+            Err(Error::SAI(Status::from(st)))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn set_src_mac_address(&self, mac_address: sai_mac_t) -> Result<(), Error> {
+        self.set_attribute(RouterInterfaceAttribute::SrcMacAddress(mac_address))
+    }
+
+    pub fn set_mtu(&self, mtu: u32) -> Result<(), Error> {
+        self.set_attribute(RouterInterfaceAttribute::MTU(mtu))
+    }
+
+    /// get the MTU of the router interface
+    pub fn get_mtu(&self) -> Result<u32, Error> {
+        let router_interface_api = self
+            .sai
+            .router_interface_api()
+            .ok_or(Error::APIUnavailable)?;
+        let get_router_interface_attribute = router_interface_api
+            .get_router_interface_attribute
+            .ok_or(Error::APIFunctionUnavailable)?;
+
+        let mut attr = sai_attribute_t {
+            id: _sai_router_interface_attr_t_SAI_ROUTER_INTERFACE_ATTR_MTU,
+            value: sai_attribute_value_t { u32_: 0 },
+        };
+
+        let st = unsafe { get_router_interface_attribute(self.id, 1, &mut attr as *mut _) };
+        if st != SAI_STATUS_SUCCESS as sai_status_t {
+            return Err(Error::SAI(Status::from(st)));
+        }
+
+        Ok(unsafe { attr.value.u32_ })
+    }
+
     pub fn remove(self) -> Result<(), Error> {
         let router_interface_api = self
             .sai
@@ -4,7 +4,10 @@ use crate::{
         table_entry::TableEntry, table_entry::TableEntryAttribute, trap::Trap, trap::TrapAttribute,
         trap_group::TrapGroup, HostIf, HostIfAttribute,
     },
-    port::{Port, PortSerdes},
+    neighbor::{NeighborEntry, NeighborEntryAttribute},
+    next_hop::{NextHop, NextHopAttribute},
+    port::{Port, PortAttribute, PortSerdes, PortSerdesAttribute},
+    router_interface::RouterInterfaceID,
     virtual_router::VirtualRouter,
     vlan::VLAN,
 };
@@ -346,6 +349,65 @@ impl<'a> Switch<'a> {
         })
     }
 
+    /// returns the host interface for an already existing object ID (e.g. after a warm boot)
+    pub fn get_hostif(&self, id: sai_object_id_t) -> Result<HostIf<'a>, Error> {
+        check_object_type(id, _sai_object_type_t_SAI_OBJECT_TYPE_HOSTIF)?;
+        Ok(HostIf { id, sai: self.sai })
+    }
+
+    /// creates a next hop: for IP next hops `NextHopAttribute::Type`, `NextHopAttribute::IP` and
+    /// `NextHopAttribute::RouterInterfaceID` are mandatory on create
+    pub fn create_next_hop(&self, attrs: Vec<NextHopAttribute>) -> Result<NextHop<'a>, Error> {
+        let next_hop_api = self.sai.next_hop_api().ok_or(Error::APIUnavailable)?;
+        let create_next_hop = next_hop_api
+            .create_next_hop
+            .ok_or(Error::APIFunctionUnavailable)?;
+
+        let args: Vec<sai_attribute_t> = attrs.into_iter().map(|v| v.into()).collect();
+
+        let mut oid: sai_object_id_t = 0;
+        let st = unsafe { create_next_hop(&mut oid, self.id, args.len() as u32, args.as_ptr()) };
+        if st != SAI_STATUS_SUCCESS as sai_status_t {
+            return Err(Error::SAI(Status::from(st)));
+        }
+
+        Ok(NextHop {
+            id: oid,
+            sai: self.sai,
+        })
+    }
+
+    /// creates a neighbor entry for the IP address on the router interface
+    /// NOTE: `NeighborEntryAttribute::DstMacAddress` is mandatory on create
+    pub fn create_neighbor_entry(
+        &self,
+        rif_id: RouterInterfaceID,
+        ip_address: std::net::IpAddr,
+        attrs: Vec<NeighborEntryAttribute>,
+    ) -> Result<NeighborEntry<'a>, Error> {
+        let neighbor_api = self.sai.neighbor_api().ok_or(Error::APIUnavailable)?;
+        let create_neighbor_entry = neighbor_api
+            .create_neighbor_entry
+            .ok_or(Error::APIFunctionUnavailable)?;
+
+        let args: Vec<sai_attribute_t> = attrs.into_iter().map(|v| v.into()).collect();
+
+        let entry = sai_neighbor_entry_t {
+            switch_id: self.id,
+            rif_id: rif_id.into(),
+            ip_address: ip_address.into(),
+        };
+        let st = unsafe { create_neighbor_entry(&entry, args.len() as u32, args.as_ptr()) };
+        if st != SAI_STATUS_SUCCESS as sai_status_t {
+            return Err(Error::SAI(Status::from(st)));
+        }
+
+        Ok(NeighborEntry {
+            entry: entry,
+            sai: self.sai,
+        })
+    }
+
     pub fn get_default_virtual_router(&self) -> Result<VirtualRouter<'a>, Error> {
         let switch_api = self.sai.switch_api().ok_or(Error::APIUnavailable)?;
         let get_switch_attribute = switch_api
@@ -369,35 +431,23 @@ impl<'a> Switch<'a> {
         })
     }
 
+    /// creates a serdes object for a port with the given tuning values
+    /// NOTE: `PortSerdesAttribute::PortID` is mandatory on create
     pub fn create_port_serdes(
         &self,
-        port_id: PortID,
-        preemphasis: Vec<i32>,
-    ) -> Result<PortSerdes, Error> {
+        attrs: Vec<PortSerdesAttribute>,
+    ) -> Result<PortSerdes<'a>, Error> {
         // check that API is available/callable
         let port_api = self.sai.port_api().ok_or(Error::APIUnavailable)?;
         let create_port_serdes = port_api
             .create_port_serdes
             .ok_or(Error::APIFunctionUnavailable)?;
 
-        let mut preemphasis = preemphasis.clone();
-        let args: Vec<sai_attribute_t> = vec![
-            sai_attribute_t {
-                id: _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_PORT_ID,
-                value: sai_attribute_value_t {
-                    oid: port_id.into(),
-                },
-            },
-            sai_attribute_t {
-                id: _sai_port_serdes_attr_t_SAI_PORT_SERDES_ATTR_PREEMPHASIS,
-                value: sai_attribute_value_t {
-                    s32list: sai_s32_list_t {
-                        count: preemphasis.len() as u32,
-                        list: preemphasis.as_mut_ptr(),
-                    },
-                },
-            },
-        ];
+        let mut backing: Vec<Vec<i32>> = Vec::with_capacity(attrs.len());
+        let args: Vec<sai_attribute_t> = attrs
+            .iter()
+            .map(|v| v.to_sai_attribute_t(&mut backing))
+            .collect();
 
         let mut oid: sai_object_id_t = 0;
         let st = unsafe { create_port_serdes(&mut oid, self.id, args.len() as u32, args.as_ptr()) };
@@ -411,27 +461,48 @@ impl<'a> Switch<'a> {
         })
     }
 
+    /// (re-)applies serdes tuning values to a port
+    /// all serdes attributes are create-only, so any existing serdes object of the port is removed first
+    pub fn set_port_serdes(
+        &self,
+        port: &Port<'a>,
+        attrs: Vec<PortSerdesAttribute>,
+    ) -> Result<PortSerdes<'a>, Error> {
+        if let Some(serdes) = port.get_port_serdes()? {
+            serdes.remove()?;
+        }
+
+        let mut args = vec![PortSerdesAttribute::PortID(port.to_id())];
+        args.extend(
+            attrs
+                .into_iter()
+                .filter(|attr| !matches!(attr, PortSerdesAttribute::PortID(_))),
+        );
+        self.create_port_serdes(args)
+    }
+
     pub fn create_port(&self, hw_lanes: Vec<u32>, speed: u32) -> Result<Port<'a>, Error> {
+        self.create_port_with_attributes(vec![
+            PortAttribute::HwLaneList(hw_lanes),
+            PortAttribute::Speed(speed),
+        ])
+    }
+
+    /// creates a port with all given attributes set at creation time
+    /// NOTE: `PortAttribute::HwLaneList` and `PortAttribute::Speed` are mandatory on create
+    pub fn create_port_with_attributes(
+        &self,
+        attrs: Vec<PortAttribute>,
+    ) -> Result<Port<'a>, Error> {
         // check that API is available/callable
         let port_api = self.sai.port_api().ok_or(Error::APIUnavailable)?;
         let create_port = port_api.create_port.ok_or(Error::APIFunctionUnavailable)?;
 
-        let mut hw_lanes = hw_lanes.clone();
-        let args: Vec<sai_attribute_t> = vec![
-            sai_attribute_t {
-                id: _sai_port_attr_t_SAI_PORT_ATTR_HW_LANE_LIST,
-                value: sai_attribute_value_t {
-                    u32list: sai_u32_list_t {
-                        count: hw_lanes.len() as u32,
-                        list: hw_lanes.as_mut_ptr(),
-                    },
-                },
-            },
-            sai_attribute_t {
-                id: _sai_port_attr_t_SAI_PORT_ATTR_SPEED,
-                value: sai_attribute_value_t { u32_: speed },
-            },
-        ];
+        let mut hw_lane_list_backing: Vec<u32> = Vec::new();
+        let args: Vec<sai_attribute_t> = attrs
+            .iter()
+            .map(|v| v.to_sai_attribute_t(&mut hw_lane_list_backing))
+            .collect();
 
         let mut oid: sai_object_id_t = 0;
         let st = unsafe { create_port(&mut oid, self.id, args.len() as u32, args.as_ptr()) };
@@ -482,17 +553,62 @@ impl<'a> Switch<'a> {
     }
 
     pub fn enable_shell(&self) -> Result<(), Error> {
+        self.shell_enabler()?()
+    }
+
+    /// returns a function which does the same as `enable_shell()`, but which can be moved to
+    /// a different thread. This is useful as the call blocks until the shell quits.
+    /// NOTE: the function must not be called anymore once SAI has been uninitialized
+    pub fn shell_enabler(&self) -> Result<Box<dyn FnOnce() -> Result<(), Error> + Send>, Error> {
         // check that API is available/callable
         let switch_api = self.sai.switch_api().ok_or(Error::APIUnavailable)?;
         let set_switch_attribute = switch_api
             .set_switch_attribute
             .ok_or(Error::APIFunctionUnavailable)?;
 
-        let attr = sai_attribute_t {
-            id: _sai_switch_attr_t_SAI_SWITCH_ATTR_SWITCH_SHELL_ENABLE,
-            value: sai_attribute_value_t { booldata: true },
-        };
-        let st: sai_status_t = unsafe { set_switch_attribute(1, &attr as *const _) };
+        Ok(Box::new(move || {
+            let attr = sai_attribute_t {
+                id: _sai_switch_attr_t_SAI_SWITCH_ATTR_SWITCH_SHELL_ENABLE,
+                value: sai_attribute_value_t { booldata: true },
+            };
+            let st: sai_status_t = unsafe { set_switch_attribute(1, &attr as *const _) };
+            if st != SAI_STATUS_SUCCESS as sai_status_t {
+                Err(Error::SAI(Status::from(st)))
+            } else {
+                Ok(())
+            }
+        }))
+    }
+
+    /// tells SAI that the next switch removal is a warm restart
+    /// NOTE: this must be set before calling `remove()` to have SAI write its warm boot state
+    pub fn set_restart_warm(&self, enable: bool) -> Result<(), Error> {
+        // check that API is available/callable
+        let switch_api = self.sai.switch_api().ok_or(Error::APIUnavailable)?;
+        let set_switch_attribute = switch_api
+            .set_switch_attribute
+            .ok_or(Error::APIFunctionUnavailable)?;
+
+        let attr: sai_attribute_t = SwitchAttribute::RestartWarm(enable).into();
+        let st: sai_status_t = unsafe { set_switch_attribute(self.id, &attr as *const _) };
+        if st != SAI_STATUS_SUCCESS as sai_status_t {
+            Err(Error::SAI(Status::from(st)))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// instructs SAI to execute the pre-shutdown step of a warm shutdown
+    /// NOTE: this is ignored by SAI if `set_restart_warm(true)` was not called before
+    pub fn set_pre_shutdown(&self, enable: bool) -> Result<(), Error> {
+        // check that API is available/callable
+        let switch_api = self.sai.switch_api().ok_or(Error::APIUnavailable)?;
+        let set_switch_attribute = switch_api
+            .set_switch_attribute
+            .ok_or(Error::APIFunctionUnavailable)?;
+
+        let attr: sai_attribute_t = SwitchAttribute::PreShutdown(enable).into();
+        let st: sai_status_t = unsafe { set_switch_attribute(self.id, &attr as *const _) };
         if st != SAI_STATUS_SUCCESS as sai_status_t {
             Err(Error::SAI(Status::from(st)))
         } else {
@@ -511,6 +627,8 @@ impl<'a> Switch<'a> {
         if st != SAI_STATUS_SUCCESS as sai_status_t {
             Err(Error::SAI(Status::from(st)))
         } else {
+            // the switch is gone, so a new one can be created again
+            *SWITCH_CREATED.lock().unwrap() = false;
             Ok(())
         }
     }
@@ -723,6 +841,8 @@ impl<'a> Switch<'a> {
 pub enum SwitchAttribute {
     InitSwitch(bool),
     SrcMacAddress(sai_mac_t),
+    RestartWarm(bool),
+    PreShutdown(bool),
 }
 
 impl From<SwitchAttribute> for sai_attribute_t {
@@ -736,6 +856,48 @@ impl From<SwitchAttribute> for sai_attribute_t {
                 id: _sai_switch_attr_t_SAI_SWITCH_ATTR_SRC_MAC_ADDRESS,
                 value: _sai_attribute_value_t { mac: v },
             },
+            SwitchAttribute::RestartWarm(v) => Self {
+                id: _sai_switch_attr_t_SAI_SWITCH_ATTR_RESTART_WARM,
+                value: sai_attribute_value_t { booldata: v },
+            },
+            SwitchAttribute::PreShutdown(v) => Self {
+                id: _sai_switch_attr_t_SAI_SWITCH_ATTR_PRE_SHUTDOWN,
+                value: sai_attribute_value_t { booldata: v },
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwitchOperStatus {
+    Unknown,
+    Up,
+    Down,
+    Failed,
+}
+
+impl From<sai_switch_oper_status_t> for SwitchOperStatus {
+    fn from(value: sai_switch_oper_status_t) -> Self {
+        match value {
+            x if x == _sai_switch_oper_status_t_SAI_SWITCH_OPER_STATUS_UP => SwitchOperStatus::Up,
+            x if x == _sai_switch_oper_status_t_SAI_SWITCH_OPER_STATUS_DOWN => {
+                SwitchOperStatus::Down
+            }
+            x if x == _sai_switch_oper_status_t_SAI_SWITCH_OPER_STATUS_FAILED => {
+                SwitchOperStatus::Failed
+            }
+            _ => SwitchOperStatus::Unknown,
+        }
+    }
+}
+
+impl std::fmt::Display for SwitchOperStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SwitchOperStatus::Unknown => write!(f, "unknown"),
+            SwitchOperStatus::Up => write!(f, "up"),
+            SwitchOperStatus::Down => write!(f, "down"),
+            SwitchOperStatus::Failed => write!(f, "failed"),
         }
     }
 }
@@ -112,6 +112,12 @@ impl<'a> VirtualRouter<'a> {
         })
     }
 
+    /// returns the router interface for an already existing object ID (e.g. after a warm boot)
+    pub fn get_router_interface(&self, id: sai_object_id_t) -> Result<RouterInterface<'a>, Error> {
+        check_object_type(id, _sai_object_type_t_SAI_OBJECT_TYPE_ROUTER_INTERFACE)?;
+        Ok(RouterInterface { id, sai: self.sai })
+    }
+
     pub fn create_route_entry(
         &self,
         destination: IpNet,
@@ -139,6 +145,34 @@ impl<'a> VirtualRouter<'a> {
             sai: self.sai,
         })
     }
+
+    /// returns the route entry for an already existing route (e.g. after a warm boot)
+    /// NOTE: this queries the packet action of the route entry to ensure that it exists
+    pub fn get_route_entry(&self, destination: IpNet) -> Result<RouteEntry<'a>, Error> {
+        let route_api = self.sai.route_api().ok_or(Error::APIUnavailable)?;
+        let get_route_entry_attribute = route_api
+            .get_route_entry_attribute
+            .ok_or(Error::APIFunctionUnavailable)?;
+
+        let entry = sai_route_entry_t {
+            switch_id: self.switch_id,
+            vr_id: self.id,
+            destination: destination.into(),
+        };
+        let mut attr = sai_attribute_t {
+            id: _sai_route_entry_attr_t_SAI_ROUTE_ENTRY_ATTR_PACKET_ACTION,
+            value: sai_attribute_value_t { s32: 0 },
+        };
+        let st = unsafe { get_route_entry_attribute(&entry, 1, &mut attr) };
+        if st != SAI_STATUS_SUCCESS as sai_status_t {
+            return Err(Error::SAI(Status::from(st)));
+        }
+
+        Ok(RouteEntry {
+            entry: entry,
+            sai: self.sai,
+        })
+    }
 }
 
 impl ObjectID<VirtualRouterID> for VirtualRouter<'_> {
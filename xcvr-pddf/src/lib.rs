@@ -340,3 +340,41 @@ pub extern "C" fn xcvr_get_transceiver_status(
 ) -> xcvr_status_t {
     xcvr_sys::XCVR_STATUS_ERROR_UNIMPLEMENTED
 }
+
+#[no_mangle]
+pub extern "C" fn xcvr_get_port_label(
+    platform: *const c_char,
+    index: idx_t,
+    label: *mut c_char,
+    label_size: usize,
+) -> xcvr_status_t {
+    if platform.is_null() {
+        return xcvr_sys::XCVR_STATUS_ERROR_UNSUPPORTED_PLATFORM;
+    }
+    if label.is_null() {
+        return xcvr_sys::XCVR_STATUS_ERROR_GENERAL;
+    }
+    let platform = unsafe { CStr::from_ptr(platform) }.to_string_lossy();
+    let port = match crate::common::get_port(platform.as_ref(), index) {
+        Ok(port) => port,
+        Err(_) => return xcvr_sys::XCVR_STATUS_ERROR_GENERAL,
+    };
+
+    // the display name must fit into the buffer including the NULL byte
+    let display_name = port.display_name.as_bytes();
+    if display_name.is_empty() {
+        return xcvr_sys::XCVR_STATUS_ERROR_UNIMPLEMENTED;
+    }
+    if display_name.len() >= label_size {
+        return xcvr_sys::XCVR_STATUS_ERROR_GENERAL;
+    }
+    unsafe {
+        std::ptr::copy_nonoverlapping(
+            display_name.as_ptr() as *const c_char,
+            label,
+            display_name.len(),
+        );
+        *label.add(display_name.len()) = 0;
+    }
+    xcvr_sys::XCVR_STATUS_SUCCESS
+}
@@ -439,6 +439,15 @@ type XcvrGetTransceiverStatusFunc = extern "C" fn(
     index: idx_t,
     transceiver_status: *mut xcvr_transceiver_status_t,
 ) -> xcvr_status_t;
+type XcvrGetPortLabelFunc = extern "C" fn(
+    platform: *const c_char,
+    index: idx_t,
+    label: *mut c_char,
+    label_size: usize,
+) -> xcvr_status_t;
+
+/// the buffer size that we pass for port labels, including the terminating NULL byte
+const PORT_LABEL_SIZE: usize = 255;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -494,6 +503,7 @@ pub trait PlatformContext {
     fn set_low_power_mode(&self, port_index: idx_t, low_power_mode: bool) -> Result<(), Error>;
     fn get_transceiver_info(&self, port_index: idx_t) -> Result<TransceiverInfo, Error>;
     fn get_transceiver_status(&self, port_index: idx_t) -> Result<TransceiverStatus, Error>;
+    fn get_port_label(&self, port_index: idx_t) -> Result<String, Error>;
 }
 
 pub struct Library<'a> {
@@ -511,6 +521,8 @@ pub struct Library<'a> {
     xcvr_set_low_power_mode: Symbol<'a, XcvrSetLowPowerModeFunc>,
     xcvr_get_transceiver_info: Symbol<'a, XcvrGetTransceiverInfoFunc>,
     xcvr_get_transceiver_status: Symbol<'a, XcvrGetTransceiverStatusFunc>,
+    // NOTE: optional, older libraries do not export this
+    xcvr_get_port_label: Option<Symbol<'a, XcvrGetPortLabelFunc>>,
 }
 
 impl<'a> Library<'a> {
@@ -530,6 +542,7 @@ impl<'a> Library<'a> {
             xcvr_set_low_power_mode: unsafe { lib.get(b"xcvr_set_low_power_mode\0") }?,
             xcvr_get_transceiver_info: unsafe { lib.get(b"xcvr_get_transceiver_info\0") }?,
             xcvr_get_transceiver_status: unsafe { lib.get(b"xcvr_get_transceiver_status\0") }?,
+            xcvr_get_port_label: unsafe { lib.get(b"xcvr_get_port_label\0") }.ok(),
         })
     }
 
@@ -598,6 +611,7 @@ pub struct PlatformLibrary<'a> {
     xcvr_set_low_power_mode: &'a Symbol<'a, XcvrSetLowPowerModeFunc>,
     xcvr_get_transceiver_info: &'a Symbol<'a, XcvrGetTransceiverInfoFunc>,
     xcvr_get_transceiver_status: &'a Symbol<'a, XcvrGetTransceiverStatusFunc>,
+    xcvr_get_port_label: Option<&'a Symbol<'a, XcvrGetPortLabelFunc>>,
 }
 
 impl<'a> PlatformLibrary<'a> {
@@ -615,6 +629,7 @@ impl<'a> PlatformLibrary<'a> {
             xcvr_set_low_power_mode: &lib.xcvr_set_low_power_mode,
             xcvr_get_transceiver_info: &lib.xcvr_get_transceiver_info,
             xcvr_get_transceiver_status: &lib.xcvr_get_transceiver_status,
+            xcvr_get_port_label: lib.xcvr_get_port_label.as_ref(),
         }
     }
 }
@@ -718,6 +733,22 @@ impl<'a> PlatformContext for PlatformLibrary<'a> {
         }
         Ok(TransceiverStatus::from(v))
     }
+
+    fn get_port_label(&self, port_index: idx_t) -> Result<String, Error> {
+        let f = self
+            .xcvr_get_port_label
+            .ok_or(Error::Status(Status::Unimplemented))?;
+        let mut v: [c_char; PORT_LABEL_SIZE] = [0; PORT_LABEL_SIZE];
+        let ret = (f)(self.platform.as_ptr(), port_index, v.as_mut_ptr(), v.len());
+        if ret != XCVR_STATUS_SUCCESS {
+            return Err(Error::Status(Status::from(ret)));
+        }
+        // make sure that we never read past our buffer if the library misbehaves
+        v[PORT_LABEL_SIZE - 1] = 0;
+        Ok(unsafe { CStr::from_ptr(v.as_ptr()) }
+            .to_string_lossy()
+            .to_string())
+    }
 }
 
 pub struct FallbackPlatformLibrary {}
@@ -766,6 +797,10 @@ impl PlatformContext for FallbackPlatformLibrary {
     fn get_transceiver_status(&self, _port_index: idx_t) -> Result<TransceiverStatus, Error> {
         Ok(TransceiverStatus::default())
     }
+
+    fn get_port_label(&self, _port_index: idx_t) -> Result<String, Error> {
+        Err(Error::Status(Status::Unimplemented))
+    }
 }
 
 #[cfg(test)]
@@ -249,6 +249,8 @@ fn get_port(platform_ctx: &PlatformContextHolder, index: idx_t) {
             Vec::new()
         }
     };
+    // not every platform library provides labels, so this is purely informational
+    let label = platform_ctx.obj.get_port_label(index).ok();
     let present = match platform_ctx.obj.get_presence(index) {
         Ok(v) => v,
         Err(e) => {
@@ -307,8 +309,9 @@ fn get_port(platform_ctx: &PlatformContextHolder, index: idx_t) {
 
     // simply print it to stdout
     println!(
-        "port {}: present: {}, supported port types: {:?}, inserted port type: {:?}, oper status: {:?}, reset status: {:?}, low power mode: {:?}",
+        "port {}: label: {:?}, present: {}, supported port types: {:?}, inserted port type: {:?}, oper status: {:?}, reset status: {:?}, low power mode: {:?}",
         index,
+        label,
         present,
         supported_port_types,
         inserted_port_type,